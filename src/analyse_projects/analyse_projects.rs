@@ -4,8 +4,9 @@ use crossbeam::queue::SegQueue;
 use yansi::Color;
 
 use crate::output;
-use crate::Project;
-use crate::Settings;
+use crate::plugins::utils;
+use crate::project::Project;
+use crate::settings::Settings;
 
 use super::filter_by_modified_date::filter_by_modified_date;
 
@@ -38,26 +39,20 @@ pub fn analyse_projects(projects: SegQueue<Project>, settings: &Settings) -> Vec
         dirs.append(&mut project.into_cleanable_dirs());
     }
 
+    // Nested directories are found by comparing paths, which only works if every
+    // directory is spelled the same way, and directories that vanished in the
+    // meantime need no deleting
+    let mut dirs: Vec<PathBuf> = dirs
+        .iter()
+        .filter_map(|dir| utils::canonical_path(dir))
+        .collect();
     dirs.sort();
+    dirs.dedup();
 
     // Filter out subdirectories when their parent directory is already in the list
     // This prevents "No such file or directory" errors when trying to delete
     // a subdirectory after its parent has already been deleted
-    let mut filtered_dirs = Vec::new();
-    for dir in dirs {
-        // Check if any already-accepted directory is a parent of this one
-        let is_subdirectory = filtered_dirs
-            .iter()
-            .any(|parent: &PathBuf| dir.starts_with(parent) && &dir != parent);
-
-        if !is_subdirectory {
-            // Remove any existing subdirectories of this new directory
-            filtered_dirs.retain(|existing| !existing.starts_with(&dir) || existing == &dir);
-            filtered_dirs.push(dir);
-        }
-    }
-
-    filtered_dirs
+    utils::remove_nested_dirs(dirs)
 }
 
 #[cfg(test)]
@@ -79,17 +74,7 @@ mod tests {
         dirs.sort();
 
         // Apply the filtering logic
-        let mut filtered_dirs = Vec::new();
-        for dir in dirs {
-            let is_subdirectory = filtered_dirs
-                .iter()
-                .any(|parent: &PathBuf| dir.starts_with(parent) && &dir != parent);
-
-            if !is_subdirectory {
-                filtered_dirs.retain(|existing| !existing.starts_with(&dir) || existing == &dir);
-                filtered_dirs.push(dir);
-            }
-        }
+        let filtered_dirs = utils::remove_nested_dirs(dirs);
 
         // Should only contain parent directories, not nested ones
         assert_eq!(filtered_dirs.len(), 2);
@@ -117,17 +102,7 @@ mod tests {
 
         dirs.sort();
 
-        let mut filtered_dirs = Vec::new();
-        for dir in dirs {
-            let is_subdirectory = filtered_dirs
-                .iter()
-                .any(|parent: &PathBuf| dir.starts_with(parent) && &dir != parent);
-
-            if !is_subdirectory {
-                filtered_dirs.retain(|existing| !existing.starts_with(&dir) || existing == &dir);
-                filtered_dirs.push(dir);
-            }
-        }
+        let filtered_dirs = utils::remove_nested_dirs(dirs);
 
         assert_eq!(filtered_dirs.len(), 3);
         assert!(filtered_dirs.contains(&PathBuf::from("/other/project/build")));
@@ -0,0 +1,174 @@
+/// Library entry points for embedding sweep in other tools
+use std::path::PathBuf;
+
+use regex::Regex;
+
+use crate::plugins::large_files::LargeFilePlugin;
+use crate::plugins::progress::ScanObserver;
+use crate::plugins::{mime, utils, PluginError, ScanResult};
+
+/// Options for a programmatic large file scan
+///
+/// These mirror the command line flags of `sweep --large-files`, but use
+/// plain values instead of strings that still need to be parsed.
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    /// Minimum size of files to report, in bytes
+    pub min_size: u64,
+
+    /// Maximum size of files to report, in bytes. Unbounded if `None`.
+    pub max_size: Option<u64>,
+
+    /// Only report files that have not been accessed in this many days
+    pub older_than_days: Option<u64>,
+
+    /// Include files tracked by git in the results
+    pub include_git_tracked: bool,
+
+    /// Skip files whose path matches any of these patterns
+    pub ignore: Vec<Regex>,
+
+    /// Skip these files and everything inside these directories
+    pub exclude: Vec<PathBuf>,
+    /// Sniff the MIME type of every file found from its first bytes
+    pub detect_mime: bool,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            min_size: 100 * 1024 * 1024,
+            max_size: None,
+            older_than_days: None,
+            include_git_tracked: false,
+            ignore: vec![],
+            exclude: vec![],
+            detect_mime: false,
+        }
+    }
+}
+
+/// Scans one or more directories for large files
+///
+/// Nothing is deleted and nothing is asked from the user, so this is safe
+/// to call from any kind of program.
+///
+/// # Arguments
+/// * `paths` - The directories to scan
+/// * `opts`  - The options that decide which files are reported
+///
+/// # Returns
+/// The large files found in all paths, largest first per path
+///
+/// # Examples
+/// ```no_run
+/// use std::path::PathBuf;
+/// use sweep::{scan_large_files, ScanOptions};
+///
+/// let opts = ScanOptions {
+///     min_size: 500 * 1024 * 1024,
+///     older_than_days: Some(30),
+///     ..ScanOptions::default()
+/// };
+///
+/// for result in scan_large_files(&[PathBuf::from("/home/me/Downloads")], opts).unwrap() {
+///     println!("{} ({} bytes)", result.path.display(), result.size);
+/// }
+/// ```
+pub fn scan_large_files(
+    paths: &[PathBuf],
+    opts: ScanOptions,
+) -> Result<Vec<ScanResult>, PluginError> {
+    scan_large_files_with_observer(paths, opts, None)
+}
+
+/// Scans one or more directories for large files, reporting progress to an observer
+///
+/// Same as [`scan_large_files`], but calls the observer for every scanned
+/// entry and every large file found.
+///
+/// # Arguments
+/// * `paths`    - The directories to scan
+/// * `opts`     - The options that decide which files are reported
+/// * `observer` - Receives the progress of each scan, if given
+pub fn scan_large_files_with_observer(
+    paths: &[PathBuf],
+    opts: ScanOptions,
+    observer: Option<&dyn ScanObserver>,
+) -> Result<Vec<ScanResult>, PluginError> {
+    let detect_mime = opts.detect_mime;
+    let plugin = LargeFilePlugin::from_options(opts);
+
+    // A path inside another one would be walked twice
+    let paths = utils::remove_nested_dirs(paths.to_vec());
+
+    let mut results = Vec::new();
+    for path in &paths {
+        results.append(&mut plugin.scan_with_observer(path, observer)?);
+    }
+
+    // Paths that reach the same files through links still find them more than once
+    utils::dedup_by_path(&mut results);
+
+    if detect_mime {
+        mime::detect_mime_types(&mut results);
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempdir::TempDir;
+
+    #[test]
+    fn test_scan_large_files() {
+        let temp_dir = TempDir::new("api_test").unwrap();
+        let base_path = temp_dir.path();
+
+        fs::write(base_path.join("large.bin"), vec![0u8; 4096]).unwrap();
+        fs::write(base_path.join("small.bin"), vec![0u8; 16]).unwrap();
+        fs::create_dir(base_path.join("cache")).unwrap();
+        fs::write(base_path.join("cache").join("cached.bin"), vec![0u8; 4096]).unwrap();
+
+        let opts = ScanOptions {
+            min_size: 1024,
+            ignore: vec![Regex::new("cache").unwrap()],
+            ..ScanOptions::default()
+        };
+
+        let results = scan_large_files(&[base_path.to_path_buf()], opts).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path, base_path.join("large.bin"));
+        assert_eq!(results[0].size, 4096);
+    }
+
+    #[test]
+    fn test_scan_large_files_missing_path() {
+        let paths = [PathBuf::from("/this/path/does/not/exist")];
+        assert!(scan_large_files(&paths, ScanOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_differently_spelled_roots_find_each_file_once() {
+        let temp_dir = TempDir::new("api_test").unwrap();
+        let root = temp_dir.path().join("a");
+        fs::create_dir(&root).unwrap();
+        fs::write(root.join("large.bin"), vec![0u8; 4096]).unwrap();
+
+        let opts = ScanOptions {
+            min_size: 1024,
+            ..ScanOptions::default()
+        };
+        let paths = [root.join("."), temp_dir.path().join("a/../a")];
+        let results = scan_large_files(&paths, opts).unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].path,
+            dunce::canonicalize(root.join("large.bin")).unwrap()
+        );
+    }
+}
@@ -0,0 +1,406 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use structopt::clap::ArgMatches;
+use yansi::Color;
+
+use crate::output;
+use crate::plugins::safety;
+use crate::settings::{Result, Settings, SettingsError};
+
+/// Names of the config files looked up in each scan root, in order of precedence
+pub const PROJECT_FILE_NAMES: &[&str] = &[".sweep.toml", ".sweeprc"];
+
+/// Persistent settings loaded from a `.sweep.toml` file
+///
+/// Every field mirrors a field in `Settings` and is optional, so a
+/// config file only needs to contain the values it wants to change.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub all: Option<bool>,
+    pub ignore: Option<String>,
+    pub enable_large_files: Option<bool>,
+    pub older_than_days: Option<u64>,
+    pub risk_recent_days: Option<u64>,
+    pub risk_week_days: Option<u64>,
+    pub risk_month_days: Option<u64>,
+    pub size_threshold: Option<String>,
+    pub max_size: Option<String>,
+    pub include_git_tracked: Option<bool>,
+    pub protected_patterns: Vec<String>,
+    pub ide_dirs: Option<Vec<String>>,
+
+    /// Keys that don't match any known setting
+    #[serde(flatten, skip_serializing)]
+    unknown: BTreeMap<String, toml::Value>,
+}
+
+impl Config {
+    /// Parses the contents of a config file
+    ///
+    /// # Arguments
+    /// * `text` - The TOML source of the config file
+    ///
+    /// # Returns
+    /// The parsed config, or the parser error message
+    pub fn parse(text: &str) -> std::result::Result<Config, String> {
+        toml::from_str(text).map_err(|err| err.to_string())
+    }
+
+    /// Reads and parses a config file, warning about unknown keys
+    ///
+    /// # Arguments
+    /// * `path` - Path of the config file
+    pub fn from_file(path: &Path) -> Result<Config> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| SettingsError::InvalidConfig(path.to_path_buf(), err.to_string()))?;
+        let config = Config::parse(&text)
+            .map_err(|err| SettingsError::InvalidConfig(path.to_path_buf(), err))?;
+
+        if let Some(pattern) = &config.ignore {
+            Regex::new(pattern).map_err(|err| {
+                SettingsError::InvalidConfig(path.to_path_buf(), format!("ignore: {}", err))
+            })?;
+        }
+
+        for key in config.unknown_keys() {
+            output::println(
+                "Warning",
+                Color::Yellow,
+                &format!("Unknown key `{}` in {}", key, path.display()),
+            );
+        }
+
+        Ok(config)
+    }
+
+    /// Loads and merges the global config file and the project config files
+    ///
+    /// The global `~/.config/sweep/config.toml` is loaded first, followed by
+    /// the first `.sweep.toml` or `.sweeprc` found in each scan root. Values
+    /// from later files override values from earlier ones.
+    ///
+    /// # Arguments
+    /// * `roots` - The scan roots to look for project config files in
+    pub fn load(roots: &[PathBuf]) -> Result<Config> {
+        let mut config = Config::default();
+        let global = global_config_file();
+
+        for path in config_files(roots) {
+            let mut file = Config::from_file(&path)?;
+            if global.as_ref() != Some(&path) {
+                file = file.without_relaxing_keys(&path);
+            }
+            config = config.merge(file);
+        }
+
+        Ok(config)
+    }
+
+    /// Drops the keys that make more files deletable, which only the global config may turn on
+    ///
+    /// A scanned repository must not be able to widen what is deleted in it,
+    /// so the keys are ignored with a warning naming the file.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the project config file, for the warning
+    pub fn without_relaxing_keys(mut self, path: &Path) -> Config {
+        for (key, value) in [
+            ("all", &mut self.all),
+            ("include_git_tracked", &mut self.include_git_tracked),
+        ] {
+            if *value == Some(true) {
+                *value = None;
+                output::println(
+                    "Warning",
+                    Color::Yellow,
+                    &format!(
+                        "Ignoring `{}` in {}, only the global config file can turn it on",
+                        key,
+                        path.display()
+                    ),
+                );
+            }
+        }
+        self
+    }
+
+    /// Lists the keys in the config file that don't match a known setting
+    pub fn unknown_keys(&self) -> Vec<&str> {
+        self.unknown.keys().map(String::as_str).collect()
+    }
+
+    /// Combines two configs, with values from `other` taking precedence
+    pub fn merge(mut self, other: Config) -> Config {
+        self.all = other.all.or(self.all);
+        self.ignore = other.ignore.or(self.ignore);
+        self.enable_large_files = other.enable_large_files.or(self.enable_large_files);
+        self.older_than_days = other.older_than_days.or(self.older_than_days);
+        self.risk_recent_days = other.risk_recent_days.or(self.risk_recent_days);
+        self.risk_week_days = other.risk_week_days.or(self.risk_week_days);
+        self.risk_month_days = other.risk_month_days.or(self.risk_month_days);
+        self.size_threshold = other.size_threshold.or(self.size_threshold);
+        self.max_size = other.max_size.or(self.max_size);
+        self.include_git_tracked = other.include_git_tracked.or(self.include_git_tracked);
+        self.ide_dirs = other.ide_dirs.or(self.ide_dirs);
+
+        for pattern in other.protected_patterns {
+            if !self.protected_patterns.contains(&pattern) {
+                self.protected_patterns.push(pattern);
+            }
+        }
+
+        self.unknown.extend(other.unknown);
+        self
+    }
+
+    /// Fills in the settings that weren't given on the command line
+    ///
+    /// Flags given on the command line always take precedence over the
+    /// config file. Protected patterns are combined from both sources.
+    ///
+    /// # Arguments
+    /// * `settings` - The settings parsed from the command line
+    /// * `matches`  - The raw command line matches, to detect which flags were given
+    pub fn apply(self, settings: &mut Settings, matches: &ArgMatches) {
+        settings.all |= self.all.unwrap_or(false);
+        settings.enable_large_files |= self.enable_large_files.unwrap_or(false);
+        settings.include_git_tracked |= self.include_git_tracked.unwrap_or(false);
+
+        // The pattern was validated when the file was loaded
+        if settings.ignore.is_empty() {
+            settings.ignore = self
+                .ignore
+                .and_then(|pattern| Regex::new(&pattern).ok())
+                .into_iter()
+                .collect();
+        }
+
+        if settings.older_than_days.is_none() {
+            settings.older_than_days = self.older_than_days;
+        }
+
+        if settings.risk_recent_days.is_none() {
+            settings.risk_recent_days = self.risk_recent_days;
+        }
+        if settings.risk_week_days.is_none() {
+            settings.risk_week_days = self.risk_week_days;
+        }
+        if settings.risk_month_days.is_none() {
+            settings.risk_month_days = self.risk_month_days;
+        }
+
+        // The size threshold always has a value, so check whether the user typed it
+        if matches.occurrences_of("size-threshold") == 0 {
+            if let Some(size) = self.size_threshold {
+                settings.size_threshold = size;
+            }
+        }
+
+        if settings.max_size.is_none() {
+            settings.max_size = self.max_size;
+        }
+
+        for pattern in self.protected_patterns {
+            if !settings.protected_patterns.contains(&pattern) {
+                settings.protected_patterns.push(pattern);
+            }
+        }
+
+        if settings.ide_dirs.is_none() {
+            settings.ide_dirs = self.ide_dirs;
+        }
+    }
+}
+
+/// Gets the path of the global config file, if the config directory is known
+fn global_config_file() -> Option<PathBuf> {
+    let dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| safety::home_dir().map(|home| home.join(".config")))?;
+
+    Some(dir.join("sweep").join("config.toml"))
+}
+
+/// Lists the existing config files that apply to the given scan roots
+fn config_files(roots: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    if let Some(global) = global_config_file() {
+        if global.is_file() {
+            files.push(global);
+        }
+    }
+
+    for root in roots {
+        let project_file = PROJECT_FILE_NAMES
+            .iter()
+            .map(|name| root.join(name))
+            .find(|path| path.is_file());
+
+        if let Some(path) = project_file {
+            if !files.contains(&path) {
+                files.push(path);
+            }
+        }
+    }
+
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use structopt::StructOpt;
+    use tempdir::TempDir;
+
+    #[test]
+    fn round_trip() {
+        let config = Config {
+            all: Some(true),
+            ignore: Some("vendor".to_string()),
+            enable_large_files: Some(true),
+            older_than_days: Some(30),
+            risk_recent_days: Some(1),
+            risk_week_days: Some(14),
+            risk_month_days: None,
+            size_threshold: Some("500MB".to_string()),
+            max_size: Some("2GB".to_string()),
+            include_git_tracked: None,
+            protected_patterns: vec!["*.iso".to_string()],
+            ide_dirs: Some(vec![".idea".to_string()]),
+            unknown: BTreeMap::new(),
+        };
+
+        let text = toml::to_string(&config).unwrap();
+        assert_eq!(Config::parse(&text).unwrap(), config);
+    }
+
+    #[test]
+    fn unknown_keys() {
+        let config = Config::parse("size_threshold = \"1GB\"\nsize_treshold = \"2GB\"\n").unwrap();
+
+        assert_eq!(config.size_threshold.as_deref(), Some("1GB"));
+        assert_eq!(config.unknown_keys(), vec!["size_treshold"]);
+    }
+
+    #[test]
+    fn invalid_file() {
+        let temp_dir = TempDir::new("config_test").unwrap();
+        let path = temp_dir.path().join(".sweep.toml");
+        fs::write(&path, "size_threshold = 100").unwrap();
+
+        match Config::from_file(&path) {
+            Err(SettingsError::InvalidConfig(p, _)) => assert_eq!(p, path),
+            _ => panic!("Expected an invalid config error"),
+        }
+    }
+
+    #[test]
+    fn later_files_override_earlier_files() {
+        let global = Config::parse("size_threshold = \"1GB\"\nolder_than_days = 7").unwrap();
+        let project = Config::parse("size_threshold = \"2GB\"").unwrap();
+        let config = global.merge(project);
+
+        assert_eq!(config.size_threshold.as_deref(), Some("2GB"));
+        assert_eq!(config.older_than_days, Some(7));
+    }
+
+    #[test]
+    fn project_files_cannot_relax_safety() {
+        let path = Path::new("/repo/.sweep.toml");
+        let config = Config::parse("all = true\ninclude_git_tracked = true\nolder_than_days = 7")
+            .unwrap()
+            .without_relaxing_keys(path);
+        assert_eq!(config.all, None);
+        assert_eq!(config.include_git_tracked, None);
+        assert_eq!(config.older_than_days, Some(7));
+
+        // Turning them off is still allowed
+        let config = Config::parse("include_git_tracked = false")
+            .unwrap()
+            .without_relaxing_keys(path);
+        assert_eq!(config.include_git_tracked, Some(false));
+
+        // Even when loaded from a scan root
+        let temp_dir = TempDir::new("config_test").unwrap();
+        fs::write(
+            temp_dir.path().join(".sweep.toml"),
+            "include_git_tracked = true",
+        )
+        .unwrap();
+        let config = Config::load(&[temp_dir.path().to_path_buf()]).unwrap();
+        assert_ne!(config.include_git_tracked, Some(true));
+    }
+
+    #[test]
+    fn cli_overrides_file() {
+        let temp_dir = TempDir::new("config_test").unwrap();
+        fs::write(
+            temp_dir.path().join(".sweep.toml"),
+            "size_threshold = \"1GB\"\nmax_size = \"8GB\"\nolder_than_days = 7\n\
+             enable_large_files = true\nprotected_patterns = [\"*.iso\"]\n\
+             risk_recent_days = 2\nrisk_week_days = 10\n",
+        )
+        .unwrap();
+
+        let matches = Settings::clap().get_matches_from(vec![
+            "swp",
+            "--min-size",
+            "5MB",
+            "--older-than",
+            "30",
+            "--protect",
+            "*.vmdk",
+            "--risk-recent-days",
+            "5",
+        ]);
+        let mut settings = Settings::from_clap(&matches);
+
+        let config = Config::from_file(&temp_dir.path().join(".sweep.toml"))
+            .expect("config file should load");
+        config.apply(&mut settings, &matches);
+
+        // Given on the command line
+        assert_eq!(settings.size_threshold, "5MB");
+        assert_eq!(settings.older_than_days, Some(30));
+        assert_eq!(settings.risk_recent_days, Some(5));
+
+        // Only in the config file
+        assert_eq!(settings.risk_week_days, Some(10));
+        assert_eq!(settings.risk_month_days, None);
+        assert_eq!(settings.max_size.as_deref(), Some("8GB"));
+        assert!(settings.enable_large_files);
+        assert_eq!(settings.protected_patterns, vec!["*.vmdk", "*.iso"]);
+    }
+
+    #[test]
+    fn ide_dirs_replace_the_defaults() {
+        let matches = Settings::clap().get_matches_from(vec!["swp"]);
+        let mut settings = Settings::from_clap(&matches);
+        assert_eq!(settings.ide_dirs, None);
+
+        let config = Config::parse("ide_dirs = [\".fleet\", \".idea\"]").unwrap();
+        config.apply(&mut settings, &matches);
+        assert_eq!(
+            settings.ide_dirs,
+            Some(vec![".fleet".to_string(), ".idea".to_string()])
+        );
+    }
+
+    #[test]
+    fn default_size_threshold_is_replaced() {
+        let matches = Settings::clap().get_matches_from(vec!["swp"]);
+        let mut settings = Settings::from_clap(&matches);
+
+        let config = Config::parse("size_threshold = \"1GB\"").unwrap();
+        config.apply(&mut settings, &matches);
+
+        assert_eq!(settings.size_threshold, "1GB");
+    }
+}
@@ -1,7 +1,7 @@
 use std::path::Path;
 
+use crate::project::Project;
 use crate::utils::file_utils::exists_in_path;
-use crate::Project;
 
 /// Checks if a given directory is cleanable and identifies the
 /// dependency subdirectories
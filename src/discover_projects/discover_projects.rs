@@ -6,9 +6,9 @@ use crossbeam::queue::SegQueue;
 use yansi::Color;
 
 use crate::output;
+use crate::project::Project;
+use crate::settings::Settings;
 use crate::utils::process_queue;
-use crate::Project;
-use crate::Settings;
 
 use super::detect_cleanable_project::detect_cleanable_project;
 
@@ -119,7 +119,8 @@ fn discover_projects_in_directory(
             .filter_map(|entry| entry.ok())
             .map(|entry| entry.path())
             .filter(|path| path.is_dir())
-            .filter(|path| !settings.is_path_ignored(path)),
+            .filter(|path| !settings.is_path_ignored(path))
+            .filter(|path| !settings.is_path_excluded(path)),
     };
 
     // Go over all subdirectories in the given directory and check if they're cleanable
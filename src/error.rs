@@ -0,0 +1,103 @@
+/// The error a whole run of sweep can fail with, whichever step it came from
+use std::fmt;
+use std::io;
+
+use crate::plugins::PluginError;
+use crate::settings::SettingsError;
+
+/// An error that ends a run of sweep
+///
+/// Plugins keep failing with [`PluginError`], this only brings their errors
+/// together with those of the settings so callers can handle both with `?`.
+#[derive(Debug)]
+pub enum SweepError {
+    Settings(SettingsError),
+    Plugin(PluginError),
+    Io(io::Error),
+}
+
+impl From<SettingsError> for SweepError {
+    fn from(error: SettingsError) -> Self {
+        SweepError::Settings(error)
+    }
+}
+
+impl From<PluginError> for SweepError {
+    fn from(error: PluginError) -> Self {
+        SweepError::Plugin(error)
+    }
+}
+
+impl From<io::Error> for SweepError {
+    fn from(error: io::Error) -> Self {
+        SweepError::Io(error)
+    }
+}
+
+impl fmt::Display for SweepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SweepError::Settings(err) => write!(f, "{}", err),
+            SweepError::Plugin(err) => write!(f, "{}", err),
+            SweepError::Io(err) => write!(f, "IO error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SweepError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SweepError::Settings(err) => Some(err),
+            SweepError::Plugin(err) => Some(err),
+            SweepError::Io(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+    use std::path::PathBuf;
+
+    fn fail_with<E>(error: E) -> Result<(), SweepError>
+    where
+        SweepError: From<E>,
+    {
+        Err(error)?
+    }
+
+    #[test]
+    fn test_errors_convert_with_question_mark() {
+        let settings = fail_with(SettingsError::InvalidPath(PathBuf::from("/no/such/dir")));
+        assert!(matches!(
+            settings,
+            Err(SweepError::Settings(SettingsError::InvalidPath(_)))
+        ));
+
+        let plugin = fail_with(PluginError::Scan("walk failed".to_string()));
+        assert!(matches!(
+            plugin,
+            Err(SweepError::Plugin(PluginError::Scan(_)))
+        ));
+
+        let io = fail_with(io::Error::new(io::ErrorKind::NotFound, "gone"));
+        assert!(matches!(io, Err(SweepError::Io(_))));
+    }
+
+    #[test]
+    fn test_display_keeps_the_wrapped_message() {
+        let settings = SweepError::from(SettingsError::InvalidPercent(120.0));
+        assert_eq!(
+            settings.to_string(),
+            "Invalid percentage 120 for --when-full, expected 0 to 100"
+        );
+        assert!(settings.source().is_some());
+
+        let plugin = SweepError::from(PluginError::Cleanup("disk full".to_string()));
+        assert_eq!(plugin.to_string(), "Cleanup error: disk full");
+
+        let io = SweepError::from(io::Error::new(io::ErrorKind::NotFound, "gone"));
+        assert_eq!(io.to_string(), "IO error: gone");
+    }
+}
@@ -0,0 +1,22 @@
+//! Sweep finds build artifacts, dependency directories and large files that can be removed
+//! to free up disk space.
+//!
+//! Besides the `sweep` command line tool, the large file detection can be embedded in
+//! other tools through [`scan_large_files`].
+
+pub mod analyse_projects;
+pub mod config;
+pub mod discover_projects;
+pub mod error;
+pub mod output;
+pub mod plugins;
+pub mod project;
+pub mod settings;
+
+mod api;
+mod swpfile;
+mod utils;
+
+pub use api::{scan_large_files, scan_large_files_with_observer, ScanOptions};
+pub use error::SweepError;
+pub use plugins::progress::{ScanObserver, ScanProgress, ScanTemplates};
@@ -1,63 +1,95 @@
 use std::fs::remove_dir_all;
-use std::io::{stdin, stdout, Write};
+use std::path::Path;
+use std::process::ExitCode;
 
 use yansi::{Color, Paint};
 
-use crate::project::Project;
-use crate::settings::{Settings, SettingsError};
+use sweep::settings::{Command, Settings};
+use sweep::{analyse_projects, discover_projects, output, plugins, SweepError};
 
-mod output;
-mod project;
-mod settings;
-mod swpfile;
+fn main() -> ExitCode {
+    let settings = Settings::get();
 
-mod analyse_projects;
-mod discover_projects;
-mod utils;
+    // The color choice is needed before anything is printed, even if the settings are invalid
+    let color = settings
+        .as_ref()
+        .map(|settings| settings.color)
+        .unwrap_or(output::ColorChoice::Auto);
+    output::init_color(color);
 
-fn main() {
-    if cfg!(windows) && !Paint::enable_windows_ascii() {
-        Paint::disable();
+    // The JSON formats and --print0 print nothing but the results
+    let machine_readable = settings
+        .as_ref()
+        .is_ok_and(|settings| settings.lists_only());
+    if !machine_readable {
+        println!(
+            "{} v{}",
+            Paint::new("Sweep").bold(),
+            Paint::new(env!("CARGO_PKG_VERSION")).dimmed()
+        );
     }
 
-    println!(
-        "{} v{}",
-        Paint::new("Sweep").bold(),
-        Paint::new(env!("CARGO_PKG_VERSION")).dimmed()
-    );
-
-    let settings = match Settings::get() {
-        Ok(settings) => settings,
+    match settings.map_err(SweepError::from).and_then(run) {
+        Ok(()) => ExitCode::SUCCESS,
         Err(err) => {
-            match err {
-                SettingsError::InvalidPath(path) => {
-                    output::error(format!("Invalid path: {}", path.to_str().unwrap_or("")))
-                }
-            };
+            output::error(err.to_string());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run the command or sweep the settings ask for
+fn run(mut settings: Settings) -> Result<(), SweepError> {
+    let machine_readable = settings.lists_only();
+
+    if let Some(Command::Explain { path }) = &settings.command {
+        plugins::explain::run_explain(path, &settings)?;
+        return Ok(());
+    }
 
-            return;
+    if let Some(Command::UndoReport) = &settings.command {
+        plugins::manifest::run_undo_report()?;
+        return Ok(());
+    }
+
+    if !machine_readable {
+        for path in &settings.paths {
+            output::println("Path", Color::Blue, path.to_str().unwrap_or(""));
         }
-    };
+    }
 
-    for path in &settings.paths {
-        output::println("Path", Color::Blue, path.to_str().unwrap_or(""));
+    // Sweeping a volume with enough room left is not worth it, so only roots on full volumes are swept
+    if let Some(percent) = settings.when_full {
+        settings
+            .paths
+            .retain(|path| volume_is_full(path, percent, machine_readable));
+        if settings.paths.is_empty() {
+            return Ok(());
+        }
+    }
+    let settings = &settings;
+
+    // Feature plugins replace the default project sweep when enabled
+    if plugins::runner::any_enabled(settings) {
+        plugins::runner::run_plugins(settings)?;
+        return Ok(());
     }
 
     // Discover cleanable projects
-    let cleanables = match discover_projects::discover_projects(&settings) {
+    let cleanables = match discover_projects::discover_projects(settings) {
         Some(cleanables) => cleanables,
         None => {
             output::println_plain(Some(Color::Yellow), "No sweepable projects found");
             output::println_plain(None, "  Check your paths and try again.");
             output::println_plain(None, "  See `--help` for more options");
-            return;
+            return Ok(());
         }
     };
 
     output::println_info(format!("{} sweepable projects found", cleanables.len()));
 
     // Figure out which directories can be deleted
-    let delete_dirs = analyse_projects::analyse_projects(cleanables, &settings);
+    let delete_dirs = analyse_projects::analyse_projects(cleanables, settings);
 
     if delete_dirs.is_empty() {
         output::println_plain(Some(Color::Yellow), "No sweepable projects found");
@@ -70,7 +102,7 @@ fn main() {
             "  Run the application with `--all` to disregard file age",
         );
         output::println_plain(None, "  See `--help` for more options");
-        return;
+        return Ok(());
     }
 
     let message = if delete_dirs.len() == 1 {
@@ -87,7 +119,9 @@ fn main() {
         output::println_info(dir.to_str().unwrap_or(""));
     }
 
-    if !settings.force {
+    // Sweeping projects passes no safety limit, so --yes and --force both skip the question
+    let confirmation = plugins::safety::confirmation(settings.yes, settings.force, false);
+    if confirmation == plugins::safety::Confirmation::Ask {
         println!(
             "{}{} {}",
             " ".repeat(output::LABEL_WIDTH - 8),
@@ -95,44 +129,76 @@ fn main() {
             Paint::red("Above directories will be permanently deleted").bold()
         );
 
-        loop {
-            print!(
-                "{} {} (y/n): ",
-                " ".repeat(output::LABEL_WIDTH),
-                Paint::new("Continue?").bold()
-            );
-            stdout().flush().unwrap();
-
-            let mut input = String::new();
-            stdin()
-                .read_line(&mut input)
-                .expect("Could not read CLI input");
-            let input = input.trim();
-
-            if input == "n" {
-                return;
-            }
-
-            if input == "y" {
-                break;
-            }
-
-            output::println_info("Please answer either 'y' or 'n'");
+        if !output::confirm("Continue?") {
+            return Ok(());
         }
     }
 
-    for dir in delete_dirs {
+    for dir in &delete_dirs {
         output::print("Deleting", Color::Cyan, dir.to_str().unwrap_or(""));
-        if let Err(error) = remove_dir_all(&dir) {
+        if let Err(error) = remove_dir_all(dir) {
             println!();
             output::error(format!(
                 "Could not delete directory {}",
                 &dir.to_str().unwrap_or("")
             ));
             output::println_info(error.to_string());
-            return;
+            return Ok(());
         }
     }
 
     output::println("Deleted", Color::Green, "All directories deleted");
+
+    if settings.prune_empty_dirs {
+        let pruned = plugins::utils::prune_empty_dirs(
+            &delete_dirs,
+            &settings.paths,
+            &plugins::runner::protection_filter(settings),
+            settings.i_know_what_im_doing,
+        );
+        if pruned > 0 {
+            output::println_info(format!("Pruned {} empty directories", pruned));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check if the volume of a path is at least `percent` full, printing why not otherwise
+///
+/// A volume whose usage can't be determined counts as not full, so that
+/// nothing is deleted by mistake.
+fn volume_is_full(path: &Path, percent: f64, quiet: bool) -> bool {
+    let space = plugins::utils::free_space(path)
+        .and_then(|free| Ok((free, plugins::utils::total_space(path)?)));
+
+    let (free, total) = match space {
+        Ok(space) => space,
+        Err(err) => {
+            output::error(format!(
+                "Could not tell how full the volume of {} is: {}",
+                path.display(),
+                err
+            ));
+            return false;
+        }
+    };
+
+    if plugins::utils::is_volume_full(free, total, percent) {
+        return true;
+    }
+
+    if !quiet {
+        output::println(
+            "Skip",
+            Color::Yellow,
+            &format!(
+                "The volume of {} is {:.0}% full, below the --when-full threshold of {}%",
+                path.display(),
+                plugins::utils::usage_percent(free, total),
+                percent
+            ),
+        );
+    }
+    false
 }
@@ -1,8 +1,100 @@
-use std::io::{stdout, Write};
+use std::env;
+use std::io::{stdin, stdout, BufRead, IsTerminal, Write};
+use std::str::FromStr;
 use yansi::{Color, Paint, Style};
 
 pub const LABEL_WIDTH: usize = 12;
 
+/// When to color the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Color only when writing to a terminal and `NO_COLOR` is not set
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!(
+                "Invalid color choice '{}', expected auto, always or never",
+                s
+            )),
+        }
+    }
+}
+
+/// How the results are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Messages for people, with the interactive selection
+    Text,
+    /// One JSON array of all results
+    Json,
+    /// One JSON object per line, printed as soon as each result is found
+    Ndjson,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            _ => Err(format!(
+                "Invalid format '{}', expected text, json or ndjson",
+                s
+            )),
+        }
+    }
+}
+
+impl OutputFormat {
+    /// Check if the output is meant for other programs instead of people
+    pub fn is_machine_readable(self) -> bool {
+        self != OutputFormat::Text
+    }
+}
+
+impl ColorChoice {
+    /// Decides whether to color the output
+    ///
+    /// # Arguments
+    /// * `no_color`    - Whether the `NO_COLOR` environment variable is set
+    /// * `is_terminal` - Whether the output is a terminal
+    pub fn should_color(self, no_color: bool, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => !no_color && is_terminal,
+        }
+    }
+}
+
+/// Enables or disables colored output for the rest of the program
+pub fn init_color(choice: ColorChoice) {
+    // See https://no-color.org, an empty value does not count
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+    if choice.should_color(no_color, stdout().is_terminal()) {
+        Paint::enable();
+
+        if cfg!(windows) && !Paint::enable_windows_ascii() {
+            Paint::disable();
+        }
+    } else {
+        Paint::disable();
+    }
+}
+
 pub fn error<S: Into<String>>(message: S) {
     println("Error", Color::Red, &message.into());
 }
@@ -68,6 +160,49 @@ pub fn print_plain<S: Into<String>>(colour: Option<Color>, message: S) {
     stdout().flush().unwrap();
 }
 
+/// Asks the user a yes/no question and waits for a valid answer
+///
+/// Without an answer, because stdin is closed or can't be read, the
+/// question counts as answered with 'n'.
+///
+/// # Returns
+/// `true` if the user answered 'y', `false` if they answered 'n'
+pub fn confirm<S: Into<String>>(question: S) -> bool {
+    confirm_from(&question.into(), &mut stdin().lock())
+}
+
+/// Asks a yes/no question like [`confirm`], reading the answer from `input`
+fn confirm_from(question: &str, input: &mut impl BufRead) -> bool {
+    loop {
+        print!(
+            "{} {} (y/n): ",
+            " ".repeat(LABEL_WIDTH),
+            Paint::new(question).bold()
+        );
+        stdout().flush().unwrap();
+
+        let mut answer = String::new();
+        match input.read_line(&mut answer) {
+            Ok(0) | Err(_) => {
+                println!();
+                return false;
+            }
+            Ok(_) => {}
+        }
+        let answer = answer.trim();
+
+        if answer == "n" {
+            return false;
+        }
+
+        if answer == "y" {
+            return true;
+        }
+
+        println_info("Please answer either 'y' or 'n'");
+    }
+}
+
 /// Shortens a message by omitting the middle part and replacing it with '...'
 ///
 /// If the given message is shorter than the available width, the
@@ -99,3 +234,16 @@ fn get_term_width() -> usize {
         80
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirm_without_an_answer_is_no() {
+        assert!(!confirm_from("Continue?", &mut std::io::empty()));
+        assert!(!confirm_from("Continue?", &mut "maybe\n".as_bytes()));
+        assert!(confirm_from("Continue?", &mut "maybe\ny\n".as_bytes()));
+        assert!(!confirm_from("Continue?", &mut "n\n".as_bytes()));
+    }
+}
@@ -0,0 +1,303 @@
+/// Shared parts of the plugins that clean whole directories, such as `vendor` or `bin`
+use super::progress::CleanupProgress;
+use super::{filter, safety, utils};
+use super::{CleanupError, CleanupReport, PluginError, RiskLevel, ScanResult};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+/// Describes a directory as a scan result, unless the age filter excludes it
+///
+/// A directory holding a lockfile is a project rather than output of one,
+/// so it is never offered, whatever plugin found it.
+///
+/// # Arguments
+/// * `dir`             - The directory to describe
+/// * `description`     - What the directory is, shown to the user
+/// * `risk_level`      - The risk of deleting the directory
+/// * `older_than_days` - Only include directories not modified in this many days, if set
+pub fn dir_result(
+    dir: &Path,
+    description: &str,
+    risk_level: RiskLevel,
+    older_than_days: Option<u64>,
+) -> Option<ScanResult> {
+    if filter::is_lockfile(dir)
+        || filter::LOCKFILE_NAMES
+            .iter()
+            .any(|name| dir.join(name).exists())
+    {
+        return None;
+    }
+
+    let last_modified = utils::newest_modification(dir);
+    if !is_old_enough(last_modified, older_than_days) {
+        return None;
+    }
+
+    Some(ScanResult {
+        path: dir.to_path_buf(),
+        size: utils::dir_size(dir),
+        description: description.to_string(),
+        risk_level,
+        last_modified,
+        mime_type: None,
+    })
+}
+
+/// Check if a directory was last modified long enough ago for the age filter
+pub fn is_old_enough(last_modified: Option<SystemTime>, older_than_days: Option<u64>) -> bool {
+    let Some(days) = older_than_days else {
+        return true;
+    };
+
+    match last_modified.map(|newest| SystemTime::now().duration_since(newest)) {
+        Some(Ok(age)) => age > Duration::from_secs(days.saturating_mul(24 * 60 * 60)),
+        // If we can't determine the age, include it
+        _ => true,
+    }
+}
+
+/// Deletes the selected directories with everything in them
+///
+/// # Arguments
+/// * `selected`           - The directories to delete
+/// * `allow_system_paths` - Allow deleting inside system directories
+/// * `quiet`              - Hide the progress bar
+pub fn clean_dirs(
+    selected: Vec<ScanResult>,
+    allow_system_paths: bool,
+    quiet: bool,
+) -> Result<CleanupReport, PluginError> {
+    // Refuse the whole batch before touching anything if a single path is off-limits
+    for result in &selected {
+        safety::check_deletion(&result.path, allow_system_paths)?;
+    }
+
+    let mut report = CleanupReport::default();
+    let progress = if quiet {
+        CleanupProgress::with_quiet(selected.len() as u64, true)
+    } else {
+        CleanupProgress::new(selected.len() as u64)
+    };
+
+    for result in selected {
+        if !result.path.is_dir() {
+            report.items_skipped += 1;
+            continue;
+        }
+
+        let (freed, errors) = remove_dir_tree(&result.path, &progress);
+        report.space_freed += freed;
+        if errors.is_empty() {
+            report.items_cleaned += 1;
+        } else {
+            report.errors.extend(errors);
+        }
+    }
+
+    progress.finish();
+    Ok(report)
+}
+
+/// Deletes a directory and everything in it, the contents first
+///
+/// Every removed file is reported to `progress`. Unlike `fs::remove_dir_all`,
+/// a file that can't be removed doesn't stop the deletion, so as much of the
+/// tree as possible is gone afterwards.
+///
+/// # Returns
+/// The combined size of the removed files, and the errors of what is left.
+/// Directories that are only left because something in them is left are not
+/// reported again.
+///
+/// A symlink to a directory is removed itself, and the directory it points at is left alone.
+pub fn remove_dir_tree(dir: &Path, progress: &CleanupProgress) -> (u64, Vec<CleanupError>) {
+    if fs::symlink_metadata(dir).is_ok_and(|metadata| metadata.file_type().is_symlink()) {
+        // Windows removes links to directories like directories
+        let removed = utils::remove_with_retry(dir, |path| {
+            fs::remove_file(path).or_else(|_| fs::remove_dir(path))
+        });
+        if removed.is_ok() {
+            progress.part_deleted(dir, 0);
+        }
+        progress.dir_finished();
+        return match removed {
+            Ok(()) => (0, Vec::new()),
+            Err(e) => (
+                0,
+                vec![CleanupError {
+                    path: dir.to_path_buf(),
+                    message: e.to_string(),
+                }],
+            ),
+        };
+    }
+
+    // Some tools, like Go for its module cache, make their directories read-only.
+    // Where that can't be undone, removing the files below reports why.
+    let _ = make_writable(dir);
+
+    let mut freed = 0;
+    let mut errors: Vec<CleanupError> = Vec::new();
+
+    for entry in WalkDir::new(dir).contents_first(true) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                errors.push(CleanupError {
+                    path: e.path().unwrap_or(dir).to_path_buf(),
+                    message: e.to_string(),
+                });
+                continue;
+            }
+        };
+        let path = entry.path();
+
+        if entry.file_type().is_dir() {
+            if errors.iter().any(|error| error.path.starts_with(path)) {
+                continue;
+            }
+            if let Err(e) = utils::remove_with_retry(path, |path| fs::remove_dir(path)) {
+                errors.push(CleanupError {
+                    path: path.to_path_buf(),
+                    message: e.to_string(),
+                });
+            }
+            continue;
+        }
+
+        // Only regular files count towards the space, like in `utils::dir_size`
+        let size = match entry.metadata() {
+            Ok(metadata) if metadata.is_file() => metadata.len(),
+            _ => 0,
+        };
+        match utils::remove_with_retry(path, |path| fs::remove_file(path)) {
+            Ok(()) => {
+                progress.part_deleted(path, size);
+                freed += size;
+            }
+            Err(e) => errors.push(CleanupError {
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    progress.dir_finished();
+    (freed, errors)
+}
+
+/// Gives the owner write access to a directory and everything in it
+#[cfg(unix)]
+fn make_writable(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    for entry in WalkDir::new(path).into_iter().filter_map(Result::ok) {
+        if entry.file_type().is_dir() {
+            let mode = entry.metadata()?.permissions().mode();
+            fs::set_permissions(entry.path(), fs::Permissions::from_mode(mode | 0o200))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+#[allow(clippy::permissions_set_readonly_false)]
+fn make_writable(path: &Path) -> std::io::Result<()> {
+    for entry in WalkDir::new(path).into_iter().filter_map(Result::ok) {
+        let mut permissions = entry.metadata()?.permissions();
+        if permissions.readonly() {
+            permissions.set_readonly(false);
+            fs::set_permissions(entry.path(), permissions)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::tests::risky_result;
+    use tempdir::TempDir;
+
+    #[test]
+    fn test_age_filter_with_huge_day_counts() {
+        let year_ago = SystemTime::now() - Duration::from_secs(365 * 24 * 60 * 60);
+        assert!(is_old_enough(Some(year_ago), Some(30)));
+        assert!(!is_old_enough(Some(year_ago), Some(u64::MAX)));
+        assert!(is_old_enough(None, Some(u64::MAX)));
+    }
+
+    #[test]
+    fn test_remove_dir_tree_frees_the_size_of_its_contents() {
+        let temp_dir = TempDir::new("dirs_test").unwrap();
+        let target = temp_dir.path().join("target");
+        let nested = target.join("debug").join("deps").join("incremental");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(target.join("empty")).unwrap();
+        fs::write(target.join("CACHEDIR.TAG"), vec![b'a'; 43]).unwrap();
+        fs::write(target.join("debug").join("app"), vec![b'b'; 5000]).unwrap();
+        fs::write(nested.join("query-cache.bin"), vec![b'c'; 12_345]).unwrap();
+        let contents = utils::dir_size(&target);
+        assert_eq!(contents, 43 + 5000 + 12_345);
+
+        let progress = CleanupProgress::with_quiet(1, true);
+        let (freed, errors) = remove_dir_tree(&target, &progress);
+
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(freed, contents);
+        assert_eq!(progress.space_freed(), contents);
+        assert!(!target.exists());
+        assert!(temp_dir.path().exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_remove_dir_tree_removes_links_but_not_their_targets() {
+        let temp_dir = TempDir::new("dirs_test").unwrap();
+        let target = temp_dir.path().join("real");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("data.bin"), vec![0; 1000]).unwrap();
+        let link = temp_dir.path().join("link");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let report = clean_dirs(
+            vec![risky_result(&link, 1000, RiskLevel::Safe)],
+            false,
+            true,
+        )
+        .unwrap();
+
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(report.items_cleaned, 1);
+        assert_eq!(report.space_freed, 0);
+        assert!(fs::symlink_metadata(&link).is_err());
+        assert!(target.join("data.bin").exists());
+    }
+
+    #[test]
+    fn test_clean_dirs_reports_the_space_freed() {
+        let temp_dir = TempDir::new("dirs_test").unwrap();
+        let bin = temp_dir.path().join("bin");
+        fs::create_dir_all(bin.join("Debug")).unwrap();
+        fs::write(bin.join("Debug").join("app.dll"), vec![0; 2048]).unwrap();
+        let result = dir_result(&bin, "Build output", RiskLevel::Safe, None).unwrap();
+
+        // Directories that went away since the scan are skipped
+        let gone = ScanResult {
+            path: temp_dir.path().join("obj"),
+            ..result.clone()
+        };
+        let report = clean_dirs(vec![result, gone], false, true).unwrap();
+
+        assert_eq!(report.items_cleaned, 1);
+        assert_eq!(report.items_skipped, 1);
+        assert_eq!(report.space_freed, 2048);
+        assert!(report.errors.is_empty());
+        assert!(!bin.exists());
+    }
+}
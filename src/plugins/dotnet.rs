@@ -0,0 +1,199 @@
+/// Finds the `bin` and `obj` build output directories of .NET projects and solutions
+use super::dirs;
+use super::mounts::RemoteFilter;
+use super::{CleanupReport, FeaturePlugin, Plugin, PluginError, RiskLevel, ScanResult};
+use crate::settings::Settings;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use walkdir::{DirEntry, WalkDir};
+
+/// Extensions of the files that mark a .NET project or solution directory
+const PROJECT_EXTENSIONS: [&str; 2] = ["csproj", "sln"];
+
+/// Build output directories next to a project or solution file
+const OUTPUT_DIRS: [&str; 2] = ["bin", "obj"];
+
+/// .NET plugin for `bin` and `obj` directories
+#[derive(Debug, Default)]
+pub struct DotNetPlugin {
+    older_than_days: Option<u64>,
+    include_remote: bool,
+    allow_system_paths: bool,
+    quiet: bool,
+}
+
+impl DotNetPlugin {
+    /// Create a new .NET plugin with default settings
+    pub fn new() -> Self {
+        DotNetPlugin::default()
+    }
+}
+
+impl Plugin for DotNetPlugin {
+    fn name(&self) -> &str {
+        "dotnet"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn is_enabled(&self, settings: &Settings) -> bool {
+        settings.enable_dotnet
+    }
+
+    fn configure(&mut self, settings: &Settings) -> Result<(), PluginError> {
+        if let Some(days) = settings.older_than_days {
+            self.apply_age_filter(days)?;
+        }
+
+        self.include_remote = settings.include_remote;
+        self.allow_system_paths = settings.i_know_what_im_doing;
+        self.quiet = settings.quiet;
+        Ok(())
+    }
+
+    fn apply_age_filter(&mut self, days: u64) -> Result<(), PluginError> {
+        self.older_than_days = Some(days);
+        Ok(())
+    }
+}
+
+impl FeaturePlugin for DotNetPlugin {
+    fn scan(&self, path: &Path) -> Result<Vec<ScanResult>, PluginError> {
+        let mut remote = RemoteFilter::new(path, self.include_remote, self.quiet);
+        Ok(output_dirs(path, &mut remote)
+            .iter()
+            .filter_map(|dir| {
+                dirs::dir_result(
+                    dir,
+                    ".NET build output, recreated by `dotnet build`",
+                    RiskLevel::Safe,
+                    self.older_than_days,
+                )
+            })
+            .collect())
+    }
+
+    fn clean(&self, selected: Vec<ScanResult>) -> Result<CleanupReport, PluginError> {
+        dirs::clean_dirs(selected, self.allow_system_paths, self.quiet)
+    }
+}
+
+/// Finds the `bin` and `obj` directories next to the project and solution files below a path
+///
+/// A solution and a project in the same directory share their output
+/// directories, and output directories inside other output directories are
+/// covered by their parent, so every directory is listed only once.
+fn output_dirs(path: &Path, remote: &mut RemoteFilter) -> Vec<PathBuf> {
+    let found: BTreeSet<PathBuf> = WalkDir::new(path)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0 || !(is_skipped_dir(entry) || remote.skips_entry(entry))
+        })
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file() && is_project_file(entry.path()))
+        .filter_map(|entry| entry.path().parent().map(Path::to_path_buf))
+        .flat_map(|dir| OUTPUT_DIRS.iter().map(move |name| dir.join(name)))
+        .filter(|dir| dir.is_dir())
+        .collect();
+
+    // Sorted, so a parent always comes before the directories inside it
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for dir in found {
+        if !dirs.iter().any(|parent| dir.starts_with(parent)) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// Check if a file is a C# project or a solution
+fn is_project_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            PROJECT_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+/// Check if the scan should not descend into a directory
+fn is_skipped_dir(entry: &DirEntry) -> bool {
+    let name = entry.file_name().to_string_lossy();
+    entry.file_type().is_dir() && (OUTPUT_DIRS.contains(&name.as_ref()) || name.starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempdir::TempDir;
+
+    /// Creates a project file with `bin` and `obj` directories of 100 bytes each next to it
+    fn create_project(dir: &Path, project_file: &str) {
+        for output in OUTPUT_DIRS {
+            let debug = dir.join(output).join("Debug");
+            fs::create_dir_all(&debug).unwrap();
+            fs::write(debug.join("App.dll"), vec![0; 100]).unwrap();
+        }
+        fs::write(
+            dir.join(project_file),
+            "<Project Sdk=\"Microsoft.NET.Sdk\" />",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_solution_outputs_are_found_once() {
+        let temp_dir = TempDir::new("dotnet_test").unwrap();
+        let solution = temp_dir.path().join("Shop");
+        fs::create_dir(&solution).unwrap();
+        fs::write(solution.join("Shop.sln"), "").unwrap();
+        create_project(&solution.join("Shop.Web"), "Shop.Web.csproj");
+        create_project(&solution.join("Shop.Core"), "Shop.Core.csproj");
+
+        // A project next to its solution shares the output directories
+        create_project(temp_dir.path(), "Tool.csproj");
+        fs::write(temp_dir.path().join("Tool.sln"), "").unwrap();
+
+        let results = DotNetPlugin::new().scan(temp_dir.path()).unwrap();
+        let paths: Vec<PathBuf> = results.iter().map(|r| r.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                solution.join("Shop.Core").join("bin"),
+                solution.join("Shop.Core").join("obj"),
+                solution.join("Shop.Web").join("bin"),
+                solution.join("Shop.Web").join("obj"),
+                temp_dir.path().join("bin"),
+                temp_dir.path().join("obj"),
+            ]
+        );
+        assert!(results.iter().all(|r| r.risk_level == RiskLevel::Safe));
+        assert_eq!(results.iter().map(|r| r.size).sum::<u64>(), 600);
+    }
+
+    #[test]
+    fn test_directories_without_project_are_ignored() {
+        let temp_dir = TempDir::new("dotnet_test").unwrap();
+        fs::create_dir_all(temp_dir.path().join("bin")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("obj")).unwrap();
+
+        assert!(DotNetPlugin::new()
+            .scan(temp_dir.path())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_older_than_days_is_respected() {
+        let temp_dir = TempDir::new("dotnet_test").unwrap();
+        create_project(temp_dir.path(), "App.csproj");
+
+        let mut plugin = DotNetPlugin::new();
+        plugin.apply_age_filter(30).unwrap();
+        assert!(plugin.scan(temp_dir.path()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+/// Explains why a single file gets its risk level, without scanning anything else
+use super::filter::{AgeThresholds, RiskAssessment, SmartFilter};
+use super::{PluginError, RiskLevel};
+use crate::output;
+use crate::settings::Settings;
+use std::fs;
+use std::path::Path;
+use yansi::Color;
+
+/// Calculates the risk level of a single file along with its reasons
+///
+/// Only the git repository and the `.gitignore` files that apply to the
+/// file are loaded, so this is much cheaper than a scan.
+///
+/// # Arguments
+/// `path`     - The file to explain
+/// `settings` - The application settings struct
+pub fn explain(path: &Path, settings: &Settings) -> Result<RiskAssessment, PluginError> {
+    let path = dunce::canonicalize(path)?;
+    let metadata = fs::metadata(&path)?;
+
+    let mut filter = SmartFilter::new();
+    for pattern in &settings.protected_patterns {
+        filter.add_protected_pattern(pattern);
+    }
+    if let Some(dirs) = &settings.ide_dirs {
+        filter.set_ide_dirs(dirs.clone());
+    }
+    let parse_size = if settings.si {
+        super::utils::parse_size_string_si
+    } else {
+        super::utils::parse_size_string
+    };
+    filter.set_size_threshold(parse_size(&settings.size_threshold)?);
+    filter.set_use_ignore_rules(!settings.no_gitignore);
+    filter.set_case_insensitive(settings.ignores_name_case());
+    filter.set_age_thresholds(AgeThresholds::from_days(
+        settings.risk_recent_days,
+        settings.risk_week_days,
+        settings.risk_month_days,
+    )?);
+
+    let dir = if metadata.is_dir() {
+        path.as_path()
+    } else {
+        path.parent().unwrap_or(&path)
+    };
+
+    filter.discover_git_repos(dir)?;
+
+    // Load the ignore files from the file's directory up to the repository root
+    for ancestor in dir.ancestors() {
+        let _ = filter.load_ignore_files(ancestor);
+        if ancestor.join(".git").exists() {
+            break;
+        }
+    }
+
+    Ok(filter.calculate_risk_assessment(&path, &metadata, settings.include_git_tracked))
+}
+
+/// Prints the risk level of a single file and the reasons that led to it
+///
+/// # Arguments
+/// `path`     - The file to explain
+/// `settings` - The application settings struct
+pub fn run_explain(path: &Path, settings: &Settings) -> Result<(), PluginError> {
+    let assessment = explain(path, settings)?;
+
+    output::println("Path", Color::Blue, path.to_str().unwrap_or(""));
+    output::println(
+        "Risk",
+        risk_color(assessment.level),
+        &format!("{:?}", assessment.level),
+    );
+    for reason in &assessment.reasons {
+        output::println_info(reason.as_str());
+    }
+
+    Ok(())
+}
+
+/// Gets the colour the interactive selector uses for a risk level
+fn risk_color(level: RiskLevel) -> Color {
+    match level {
+        RiskLevel::Safe => Color::Green,
+        RiskLevel::Low => Color::Yellow,
+        RiskLevel::Medium => Color::Magenta,
+        RiskLevel::High | RiskLevel::Critical => Color::Red,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use structopt::StructOpt;
+    use tempdir::TempDir;
+
+    #[test]
+    fn test_explain_protected_file() {
+        let temp_dir = TempDir::new("explain_test").unwrap();
+        let env_file = temp_dir.path().join(".env");
+        fs::write(&env_file, "SECRET=1").unwrap();
+
+        let settings = Settings::from_iter(vec!["swp"]);
+        let assessment = explain(&env_file, &settings).unwrap();
+
+        assert_eq!(assessment.level, RiskLevel::Critical);
+        assert_eq!(
+            assessment.reasons.last().unwrap(),
+            "Matches protected pattern `.env`"
+        );
+    }
+
+    #[test]
+    fn test_explain_gitignored_file() {
+        let temp_dir = TempDir::new("explain_test").unwrap();
+        fs::write(temp_dir.path().join(".gitignore"), "*.bin\n").unwrap();
+        let file = temp_dir.path().join("build.bin");
+        fs::write(&file, "data").unwrap();
+
+        let settings = Settings::from_iter(vec!["swp"]);
+        let assessment = explain(&file, &settings).unwrap();
+
+        assert_eq!(assessment.level, RiskLevel::Safe);
+        assert!(assessment
+            .reasons
+            .iter()
+            .any(|reason| reason == "Matches a pattern in .gitignore, .ignore or .fdignore"));
+    }
+
+    #[test]
+    fn test_explain_missing_file() {
+        let settings = Settings::from_iter(vec!["swp"]);
+        assert!(explain(Path::new("/this/path/does/not/exist"), &settings).is_err());
+    }
+}
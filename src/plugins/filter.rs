@@ -1,12 +1,22 @@
 use super::{PluginError, RiskLevel};
-use git2::{Repository, Status};
+use git2::{AttrCheckFlags, Repository, Status, StatusOptions};
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use std::collections::HashMap;
 use std::fs::Metadata;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, SystemTime};
 
+/// Directories where editors and IDEs keep their settings
+pub const DEFAULT_IDE_DIRS: [&str; 4] = [".idea", ".vscode", ".vs", ".gradle"];
+
+/// How many times the size threshold a file must reach to count as huge
+pub const HUGE_FILE_FACTOR: u64 = 10;
+
+/// Names of the ignore files loaded per directory, from lowest to highest precedence
+pub const IGNORE_FILE_NAMES: [&str; 3] = [".gitignore", ".ignore", ".fdignore"];
+
 /// File type classification
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -16,30 +26,215 @@ pub enum FileType {
     Archive,
     Media,
     Log,
+    /// Compiled output that a build recreates, such as object files and bytecode
+    BuildArtifact,
     Binary,
     Document,
     Source,
     Configuration,
+    BrokenSymlink,
     Unknown,
 }
 
+impl FileType {
+    /// Every file type, in declaration order
+    pub const ALL: [FileType; 12] = [
+        FileType::TestData,
+        FileType::Database,
+        FileType::Archive,
+        FileType::Media,
+        FileType::Log,
+        FileType::BuildArtifact,
+        FileType::Binary,
+        FileType::Document,
+        FileType::Source,
+        FileType::Configuration,
+        FileType::BrokenSymlink,
+        FileType::Unknown,
+    ];
+
+    /// Human readable name of the file type, e.g. "test data"
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileType::TestData => "test data",
+            FileType::Database => "database",
+            FileType::Archive => "archive",
+            FileType::Media => "media",
+            FileType::Log => "log",
+            FileType::BuildArtifact => "build artifact",
+            FileType::Binary => "binary",
+            FileType::Document => "document",
+            FileType::Source => "source",
+            FileType::Configuration => "configuration",
+            FileType::BrokenSymlink => "broken symlink",
+            FileType::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for FileType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl std::str::FromStr for FileType {
+    type Err = String;
+
+    /// Parses a file type name, ignoring case and treating spaces, dashes and
+    /// underscores alike, so "test data", "test-data" and "TestData" all work
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalize = |name: &str| -> String {
+            name.chars()
+                .filter(|c| !matches!(c, ' ' | '-' | '_'))
+                .flat_map(char::to_lowercase)
+                .collect()
+        };
+
+        let wanted = normalize(s);
+        FileType::ALL
+            .iter()
+            .find(|file_type| normalize(file_type.as_str()) == wanted)
+            .copied()
+            .ok_or_else(|| format!("Invalid file type '{}'", s))
+    }
+}
+
 /// Git file status
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GitFileStatus {
     Tracked,
+    /// Tracked and stored in git LFS, so the file on disk is the only local copy
+    LfsTracked,
     Modified,
     Untracked,
     Ignored,
     NotInRepo,
 }
 
+/// How recently a file must have been modified to raise its risk level
+///
+/// A file modified within `recent` is High risk, within `week` Medium and
+/// within `month` Low. Older files are rated by their type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgeThresholds {
+    pub recent: Duration,
+    pub week: Duration,
+    pub month: Duration,
+}
+
+impl Default for AgeThresholds {
+    fn default() -> Self {
+        AgeThresholds {
+            recent: days(3),
+            week: days(7),
+            month: days(30),
+        }
+    }
+}
+
+impl AgeThresholds {
+    /// Builds the thresholds from a number of days each, using the defaults for the missing ones
+    ///
+    /// # Arguments
+    /// * `recent` - Days within which a file is High risk
+    /// * `week`   - Days within which a file is Medium risk
+    /// * `month`  - Days within which a file is Low risk
+    ///
+    /// # Returns
+    /// An error if the thresholds don't increase from `recent` to `month`
+    pub fn from_days(
+        recent: Option<u64>,
+        week: Option<u64>,
+        month: Option<u64>,
+    ) -> Result<AgeThresholds, PluginError> {
+        let defaults = AgeThresholds::default();
+        let thresholds = AgeThresholds {
+            recent: recent.map(days).unwrap_or(defaults.recent),
+            week: week.map(days).unwrap_or(defaults.week),
+            month: month.map(days).unwrap_or(defaults.month),
+        };
+
+        if thresholds.recent > thresholds.week || thresholds.week > thresholds.month {
+            return Err(PluginError::Configuration(format!(
+                "Risk age thresholds must not decrease, got {}, {} and {} days",
+                as_days(thresholds.recent),
+                as_days(thresholds.week),
+                as_days(thresholds.month)
+            )));
+        }
+
+        Ok(thresholds)
+    }
+}
+
+/// A duration of whole days
+fn days(count: u64) -> Duration {
+    Duration::from_secs(count.saturating_mul(24 * 60 * 60))
+}
+
+/// The number of whole days in a duration
+fn as_days(duration: Duration) -> u64 {
+    duration.as_secs() / (24 * 60 * 60)
+}
+
+/// Risk level of a file along with the reasons that led to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RiskAssessment {
+    pub level: RiskLevel,
+    pub reasons: Vec<String>,
+}
+
+impl RiskAssessment {
+    fn new(level: RiskLevel, reasons: Vec<String>) -> Self {
+        RiskAssessment { level, reasons }
+    }
+}
+
+/// Lockfiles of package managers, which pin a project's dependencies and can't be recreated as they were
+pub const LOCKFILE_NAMES: [&str; 11] = [
+    "Cargo.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "poetry.lock",
+    "Pipfile.lock",
+    "go.sum",
+    "composer.lock",
+    "Gemfile.lock",
+    "packages.lock.json",
+];
+
+/// Checks if a file is a package manager lockfile such as `Cargo.lock`
+pub fn is_lockfile(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| LOCKFILE_NAMES.contains(&name))
+}
+
+/// Whether the usual filesystem of the platform ignores the case of file names
+pub const CASE_INSENSITIVE_FILESYSTEM: bool = cfg!(any(windows, target_os = "macos"));
+
 /// Smart filter for analyzing files
 pub struct SmartFilter {
     git_repos: HashMap<PathBuf, Repository>,
+    /// Status of every changed, untracked or ignored path per repository, relative to its root
+    git_statuses: HashMap<PathBuf, HashMap<PathBuf, Status>>,
+    /// Number of statuses looked up one file at a time, which the snapshots avoid
+    uncached_status_calls: AtomicUsize,
     gitignore_cache: HashMap<PathBuf, Gitignore>,
+    /// Whether ignore files and git's ignored status make files Safe
+    use_ignore_rules: bool,
     repo_root_cache: Arc<RwLock<HashMap<PathBuf, Option<PathBuf>>>>,
     protected_patterns: Vec<String>,
     test_data_patterns: Vec<String>,
+    /// Whether file names match the protected and test data patterns regardless of case
+    case_insensitive: bool,
+    ide_dirs: Vec<String>,
+    /// The smallest size that is reported, files far above it are riskier
+    size_threshold: u64,
+    age_thresholds: AgeThresholds,
 }
 
 impl std::fmt::Debug for SmartFilter {
@@ -47,20 +242,35 @@ impl std::fmt::Debug for SmartFilter {
         let repo_cache_len = self.repo_root_cache.read().map(|c| c.len()).unwrap_or(0);
         f.debug_struct("SmartFilter")
             .field("git_repos_count", &self.git_repos.len())
+            .field("git_status_snapshots", &self.git_statuses.len())
             .field("gitignore_cache_count", &self.gitignore_cache.len())
+            .field("use_ignore_rules", &self.use_ignore_rules)
             .field("repo_root_cache_count", &repo_cache_len)
             .field("protected_patterns", &self.protected_patterns)
             .field("test_data_patterns", &self.test_data_patterns)
+            .field("case_insensitive", &self.case_insensitive)
+            .field("ide_dirs", &self.ide_dirs)
+            .field("size_threshold", &self.size_threshold)
+            .field("age_thresholds", &self.age_thresholds)
             .finish()
     }
 }
 
+impl Default for SmartFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SmartFilter {
     /// Create a new smart filter
     pub fn new() -> Self {
         SmartFilter {
             git_repos: HashMap::new(),
+            git_statuses: HashMap::new(),
+            uncached_status_calls: AtomicUsize::new(0),
             gitignore_cache: HashMap::new(),
+            use_ignore_rules: true,
             repo_root_cache: Arc::new(RwLock::new(HashMap::new())),
             protected_patterns: vec![
                 ".env".to_string(),
@@ -74,7 +284,10 @@ impl SmartFilter {
                 "*.p12".to_string(),
                 "credentials*".to_string(),
                 "secrets*".to_string(),
-            ],
+            ]
+            .into_iter()
+            .chain(LOCKFILE_NAMES.iter().map(|name| name.to_string()))
+            .collect(),
             test_data_patterns: vec![
                 "test-data*".to_string(),
                 "test_data*".to_string(),
@@ -86,38 +299,31 @@ impl SmartFilter {
                 "*_test.*".to_string(),
                 "*_spec.*".to_string(),
             ],
+            case_insensitive: CASE_INSENSITIVE_FILESYSTEM,
+            ide_dirs: DEFAULT_IDE_DIRS.iter().map(|dir| dir.to_string()).collect(),
+            size_threshold: 100 * 1024 * 1024,
+            age_thresholds: AgeThresholds::default(),
         }
     }
 
     /// Discover git repositories in a path and its parents
+    ///
+    /// The repository is registered under its working directory. That is also
+    /// where linked worktrees are found, whose `.git` is a file pointing at the
+    /// main repository. Bare repositories have no working directory and thus
+    /// no files to protect, so the search goes on past them.
     pub fn discover_git_repos(&mut self, path: &Path) -> Result<(), PluginError> {
-        let mut current = path;
-
-        loop {
-            // Try to open repository at current path
-            if let Ok(repo) = Repository::open(current) {
-                if let Some(workdir) = repo.workdir() {
-                    if let Ok(canonical) = workdir.canonicalize() {
-                        self.git_repos.insert(canonical, repo);
-                        break;
-                    }
-                }
-            }
-
-            // Check for .git directory
-            let git_dir = current.join(".git");
-            if git_dir.exists() && git_dir.is_dir() {
-                if let Ok(repo) = Repository::open(current) {
-                    self.git_repos.insert(current.to_path_buf(), repo);
-                    break;
-                }
-            }
-
-            // Move to parent directory
-            match current.parent() {
-                Some(parent) => current = parent,
-                None => break,
-            }
+        for current in path.ancestors() {
+            let Ok(repo) = Repository::open(current) else {
+                continue;
+            };
+            let Some(workdir) = repo.workdir() else {
+                continue;
+            };
+
+            let root = dunce::canonicalize(workdir).unwrap_or_else(|_| current.to_path_buf());
+            self.git_repos.insert(root, repo);
+            break;
         }
 
         Ok(())
@@ -132,14 +338,14 @@ impl SmartFilter {
             }
         }
 
-        // Find repository containing this path
-        let mut result = None;
-        for repo_path in self.git_repos.keys() {
-            if path.starts_with(repo_path) {
-                result = Some(repo_path.clone());
-                break;
-            }
-        }
+        // Find the innermost repository containing this path, since worktrees
+        // and other repositories can live inside another repository
+        let result = self
+            .git_repos
+            .keys()
+            .filter(|repo_path| path.starts_with(repo_path))
+            .max_by_key(|repo_path| repo_path.components().count())
+            .cloned();
 
         // Cache the result (even if None)
         if let Ok(mut cache) = self.repo_root_cache.write() {
@@ -149,19 +355,73 @@ impl SmartFilter {
         result
     }
 
+    /// Take a snapshot of the status of every file in the discovered repositories
+    ///
+    /// Afterwards [`SmartFilter::get_git_status`] looks statuses up in the
+    /// snapshot instead of asking git about every file on its own. Taking a
+    /// new snapshot replaces the old one.
+    pub fn snapshot_git_statuses(&mut self) {
+        for (repo_path, repo) in &self.git_repos {
+            // Untracked and ignored directories are listed once instead of file by file
+            let mut options = StatusOptions::new();
+            options
+                .include_untracked(true)
+                .include_ignored(true)
+                .recurse_untracked_dirs(false)
+                .recurse_ignored_dirs(false);
+
+            if let Ok(statuses) = repo.statuses(Some(&mut options)) {
+                let snapshot = statuses
+                    .iter()
+                    .filter_map(|entry| Some((PathBuf::from(entry.path()?), entry.status())))
+                    .collect();
+                self.git_statuses.insert(repo_path.clone(), snapshot);
+            }
+        }
+    }
+
+    /// Look up the status of a path relative to a repository root
+    fn status_of(
+        &self,
+        repo_path: &Path,
+        repo: &Repository,
+        relative_path: &Path,
+    ) -> Option<Status> {
+        let Some(snapshot) = self.git_statuses.get(repo_path) else {
+            self.uncached_status_calls.fetch_add(1, Ordering::Relaxed);
+            return repo.status_file(relative_path).ok();
+        };
+
+        if let Some(status) = snapshot.get(relative_path) {
+            return Some(*status);
+        }
+
+        // Files in untracked or ignored directories share the status of the directory
+        let inherited = relative_path
+            .ancestors()
+            .skip(1)
+            .find_map(|dir| snapshot.get(dir))
+            .filter(|status| status.intersects(Status::IGNORED | Status::WT_NEW));
+
+        // Everything else is unchanged since the last commit
+        Some(inherited.copied().unwrap_or(Status::CURRENT))
+    }
+
     /// Get git status for a file
     pub fn get_git_status(&self, file_path: &Path) -> GitFileStatus {
         // Find the repository containing this file using cache
         if let Some(repo_path) = self.find_repo_root(file_path) {
             if let Some(repo) = self.git_repos.get(&repo_path) {
                 // Get relative path from repository root
-                if let Ok(relative_path) = file_path.strip_prefix(repo_path) {
+                if let Ok(relative_path) = file_path.strip_prefix(&repo_path) {
                     // Check file status
-                    if let Ok(status) = repo.status_file(relative_path) {
+                    if let Some(status) = self.status_of(&repo_path, repo, relative_path) {
                         if status.contains(Status::IGNORED) {
                             return GitFileStatus::Ignored;
                         } else if status.contains(Status::WT_NEW) {
                             return GitFileStatus::Untracked;
+                        } else if is_lfs_file(repo, relative_path) {
+                            return GitFileStatus::LfsTracked;
                         } else if status.contains(Status::WT_MODIFIED)
                             || status.contains(Status::INDEX_MODIFIED)
                         {
@@ -177,50 +437,108 @@ impl SmartFilter {
         GitFileStatus::NotInRepo
     }
 
-    /// Load gitignore patterns for a directory
-    pub fn load_gitignore(&mut self, dir: &Path) -> Result<(), PluginError> {
-        let gitignore_path = dir.join(".gitignore");
+    /// Load the ignore files of a directory
+    ///
+    /// Reads `.gitignore`, `.ignore` and `.fdignore`, as used by git, ripgrep
+    /// and fd. Their rules are merged in that order, so a rule in `.ignore`
+    /// overrides `.gitignore` and a rule in `.fdignore` overrides both.
+    pub fn load_ignore_files(&mut self, dir: &Path) -> Result<(), PluginError> {
+        if !self.use_ignore_rules {
+            return Ok(());
+        }
+
+        if let Some(gitignore) = Self::build_ignore_rules(dir)? {
+            self.add_ignore_rules(dir, gitignore);
+        }
+        Ok(())
+    }
 
-        if gitignore_path.exists() {
-            let mut builder = GitignoreBuilder::new(dir);
+    /// Build the rules of the ignore files of a directory, like [`SmartFilter::load_ignore_files`]
+    ///
+    /// This needs no filter, so the rules of many directories can be built at
+    /// once and added with [`SmartFilter::add_ignore_rules`] afterwards.
+    ///
+    /// # Returns
+    /// `None` if the directory has no ignore files
+    pub fn build_ignore_rules(dir: &Path) -> Result<Option<Gitignore>, PluginError> {
+        let mut builder = GitignoreBuilder::new(dir);
+        let mut found = false;
+
+        for name in IGNORE_FILE_NAMES {
+            let path = dir.join(name);
+            if !path.is_file() {
+                continue;
+            }
 
             // add() returns Option<Error>, not Result
-            if let Some(e) = builder.add(&gitignore_path) {
+            if let Some(e) = builder.add(&path) {
                 return Err(PluginError::Configuration(format!(
-                    "Failed to parse .gitignore: {}",
-                    e
+                    "Failed to parse {}: {}",
+                    name, e
                 )));
             }
+            found = true;
+        }
 
-            match builder.build() {
-                Ok(gitignore) => {
-                    self.gitignore_cache.insert(dir.to_path_buf(), gitignore);
-                }
-                Err(e) => {
-                    return Err(PluginError::Configuration(format!(
-                        "Failed to build gitignore: {}",
-                        e
-                    )));
-                }
-            }
+        if !found {
+            return Ok(None);
         }
 
-        Ok(())
+        builder
+            .build()
+            .map(Some)
+            .map_err(|e| PluginError::Configuration(format!("Failed to build ignore rules: {}", e)))
+    }
+
+    /// Add the ignore rules built for a directory, unless ignore rules are not used
+    pub fn add_ignore_rules(&mut self, dir: &Path, gitignore: Gitignore) {
+        if self.use_ignore_rules {
+            self.gitignore_cache.insert(dir.to_path_buf(), gitignore);
+        }
+    }
+
+    /// Whether ignore files and git's ignored status make files Safe
+    pub fn uses_ignore_rules(&self) -> bool {
+        self.use_ignore_rules
+    }
+
+    /// The number of directories whose ignore rules are loaded
+    pub fn ignore_rules_count(&self) -> usize {
+        self.gitignore_cache.len()
     }
 
     /// Check if a file matches gitignore patterns
+    ///
+    /// The rules of the directory nearest to the file are checked first, like
+    /// git does. The first directory with a matching rule decides, so a
+    /// `!pattern` re-including a file overrides an ignore rule in a parent.
     pub fn is_gitignored(&self, file_path: &Path) -> bool {
-        for (dir_path, gitignore) in &self.gitignore_cache {
-            if file_path.starts_with(dir_path) {
-                if let Ok(relative) = file_path.strip_prefix(dir_path) {
-                    let is_dir = file_path.is_dir();
-                    let matched = gitignore.matched(relative, is_dir);
-                    if matched.is_ignore() {
-                        return true;
-                    }
-                }
+        let mut dirs: Vec<&PathBuf> = self
+            .gitignore_cache
+            .keys()
+            .filter(|dir| file_path.starts_with(dir))
+            .collect();
+
+        // Deepest directory first
+        dirs.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+
+        let is_dir = file_path.is_dir();
+        for dir in dirs {
+            let relative = match file_path.strip_prefix(dir) {
+                Ok(relative) => relative,
+                Err(_) => continue,
+            };
+
+            // Also checks the parents, so `build/` ignores the files inside it
+            let matched = self.gitignore_cache[dir].matched_path_or_any_parents(relative, is_dir);
+            if matched.is_ignore() {
+                return true;
+            }
+            if matched.is_whitelist() {
+                return false;
             }
         }
+
         false
     }
 
@@ -259,8 +577,14 @@ impl SmartFilter {
                     return FileType::Configuration
                 }
 
+                // Build artifacts. Shared libraries stay binaries, since programs may load them
+                // at runtime, and `.obj` is left out because 3D models use it too.
+                "o" | "a" | "pyc" | "pyo" | "class" | "rlib" | "rmeta" | "gch" | "pch" => {
+                    return FileType::BuildArtifact
+                }
+
                 // Binary
-                "exe" | "dll" | "so" | "dylib" | "o" | "a" => return FileType::Binary,
+                "exe" | "dll" | "so" | "dylib" => return FileType::Binary,
 
                 _ => {}
             }
@@ -286,18 +610,86 @@ impl SmartFilter {
         FileType::Unknown
     }
 
+    /// Add a pattern for files that should never be deleted
+    pub fn add_protected_pattern(&mut self, pattern: &str) {
+        if !self.protected_patterns.iter().any(|p| p == pattern) {
+            self.protected_patterns.push(pattern.to_string());
+        }
+    }
+
+    /// The patterns for files that should never be deleted
+    pub fn protected_patterns(&self) -> &[String] {
+        &self.protected_patterns
+    }
+
+    /// The patterns for test data files
+    pub fn test_data_patterns(&self) -> &[String] {
+        &self.test_data_patterns
+    }
+
     /// Check if a file is protected (should never be deleted)
     pub fn is_protected(&self, path: &Path) -> bool {
-        if let Some(name) = path.file_name() {
-            let name_str = name.to_string_lossy();
+        self.is_protected_by(path).is_some()
+    }
 
-            for pattern in &self.protected_patterns {
-                if Self::matches_pattern(&name_str, pattern) {
-                    return true;
-                }
-            }
+    /// Find the first protected pattern that matches a file
+    pub fn is_protected_by(&self, path: &Path) -> Option<&str> {
+        let name_str = path.file_name()?.to_string_lossy();
+
+        self.protected_patterns
+            .iter()
+            .find(|pattern| self.name_matches(&name_str, pattern))
+            .map(String::as_str)
+    }
+
+    /// Set whether file names match the protected and test data patterns regardless of case
+    ///
+    /// Case-insensitive filesystems open `.ENV` for `.env`, so protecting
+    /// one has to protect the other there.
+    pub fn set_case_insensitive(&mut self, case_insensitive: bool) {
+        self.case_insensitive = case_insensitive;
+    }
+
+    /// Replace the names of the directories that hold editor and IDE settings
+    pub fn set_ide_dirs(&mut self, dirs: Vec<String>) {
+        self.ide_dirs = dirs;
+    }
+
+    /// Find the IDE settings directory a file is in, if any
+    pub fn ide_dir_of(&self, path: &Path) -> Option<&str> {
+        path.parent()?.components().rev().find_map(|component| {
+            let name = component.as_os_str().to_string_lossy();
+            self.ide_dirs
+                .iter()
+                .find(|dir| **dir == name)
+                .map(String::as_str)
+        })
+    }
+
+    /// Set whether ignore files and git's ignored status are consulted, for `--no-gitignore`
+    ///
+    /// Without them the risk of ignored files is computed from their type,
+    /// age and git status like that of any other file.
+    pub fn set_use_ignore_rules(&mut self, use_ignore_rules: bool) {
+        self.use_ignore_rules = use_ignore_rules;
+        if !use_ignore_rules {
+            self.gitignore_cache.clear();
         }
-        false
+    }
+
+    /// Set the size threshold that huge files are measured against
+    pub fn set_size_threshold(&mut self, bytes: u64) {
+        self.size_threshold = bytes;
+    }
+
+    /// Set how recently a file must have been modified to raise its risk level
+    pub fn set_age_thresholds(&mut self, thresholds: AgeThresholds) {
+        self.age_thresholds = thresholds;
+    }
+
+    /// Check if a size is at least `HUGE_FILE_FACTOR` times the size threshold
+    pub fn is_huge(&self, size: u64) -> bool {
+        size >= self.size_threshold.saturating_mul(HUGE_FILE_FACTOR).max(1)
     }
 
     /// Check if a file matches test data patterns
@@ -306,7 +698,7 @@ impl SmartFilter {
             let name_str = name.to_string_lossy();
 
             for pattern in &self.test_data_patterns {
-                if Self::matches_pattern(&name_str, pattern) {
+                if self.name_matches(&name_str, pattern) {
                     return true;
                 }
             }
@@ -314,6 +706,15 @@ impl SmartFilter {
         false
     }
 
+    /// Match a file name against a pattern, ignoring case if the filter does
+    fn name_matches(&self, name: &str, pattern: &str) -> bool {
+        if self.case_insensitive {
+            Self::matches_pattern(&name.to_lowercase(), &pattern.to_lowercase())
+        } else {
+            Self::matches_pattern(name, pattern)
+        }
+    }
+
     /// Simple pattern matching (supports * wildcard)
     fn matches_pattern(text: &str, pattern: &str) -> bool {
         if pattern.contains('*') {
@@ -355,70 +756,218 @@ impl SmartFilter {
         metadata: &Metadata,
         include_git_tracked: bool,
     ) -> RiskLevel {
+        self.calculate_risk_assessment(path, metadata, include_git_tracked)
+            .level
+    }
+
+    /// Calculate the risk level of a file along with the reasons that led to it
+    ///
+    /// The checks run in order of importance and the first one that decides
+    /// the level ends the assessment. Every check that ran is listed in the
+    /// reasons, so the last reason is the one that decided the level.
+    pub fn calculate_risk_assessment(
+        &self,
+        path: &Path,
+        metadata: &Metadata,
+        include_git_tracked: bool,
+    ) -> RiskAssessment {
+        let mut assessment = self.assess_file(path, metadata, include_git_tracked);
+
+        // Deleting editor settings loses configuration, even if the file looks disposable
+        if let Some(dir) = self.ide_dir_of(path) {
+            if assessment.level < RiskLevel::High {
+                assessment.reasons.push(format!(
+                    "Inside the IDE settings directory `{}`, so at least High",
+                    dir
+                ));
+                assessment.level = RiskLevel::High;
+            }
+        }
+
+        assessment
+    }
+
+    /// Assess a file on its own, without the IDE settings directory rule
+    fn assess_file(
+        &self,
+        path: &Path,
+        metadata: &Metadata,
+        include_git_tracked: bool,
+    ) -> RiskAssessment {
+        let mut reasons = Vec::new();
+
         // Check if file is protected - never delete
-        if self.is_protected(path) {
-            return RiskLevel::Critical;
+        if let Some(pattern) = self.is_protected_by(path) {
+            reasons.push(format!("Matches protected pattern `{}`", pattern));
+            return RiskAssessment::new(RiskLevel::Critical, reasons);
         }
+        reasons.push("Does not match any protected pattern".to_string());
 
         // Check git status
         let git_status = self.get_git_status(path);
         match git_status {
+            GitFileStatus::LfsTracked if !include_git_tracked => {
+                reasons.push(
+                    "Git status is LfsTracked, the file is stored in git LFS and git-tracked files are excluded"
+                        .to_string(),
+                );
+                return RiskAssessment::new(RiskLevel::Critical, reasons);
+            }
             GitFileStatus::Tracked | GitFileStatus::Modified if !include_git_tracked => {
-                return RiskLevel::Critical;
+                reasons.push(format!(
+                    "Git status is {:?}, and git-tracked files are excluded",
+                    git_status
+                ));
+                return RiskAssessment::new(RiskLevel::Critical, reasons);
             }
-            GitFileStatus::Ignored => {
+            GitFileStatus::Ignored if self.use_ignore_rules => {
                 // Ignored files are generally safe to delete
-                return RiskLevel::Safe;
+                reasons.push("Git status is Ignored".to_string());
+                return RiskAssessment::new(RiskLevel::Safe, reasons);
             }
-            _ => {}
+            _ => reasons.push(format!("Git status is {:?}", git_status)),
         }
 
         // Check if file is in gitignore
-        if self.is_gitignored(path) {
-            return RiskLevel::Safe;
+        if !self.use_ignore_rules {
+            reasons.push("Ignore files are not used, because of --no-gitignore".to_string());
+        } else if self.is_gitignored(path) {
+            reasons.push("Matches a pattern in .gitignore, .ignore or .fdignore".to_string());
+            return RiskAssessment::new(RiskLevel::Safe, reasons);
+        } else {
+            reasons
+                .push("Does not match any pattern in .gitignore, .ignore or .fdignore".to_string());
         }
 
+        let file_type = self.detect_file_type(path);
+        let huge = self.is_huge(metadata.len());
+
+        // Huge source and configuration files keep the level of their type however old they are
+        let type_floor = match file_type {
+            FileType::Configuration if huge => Some(RiskLevel::High),
+            FileType::Source if huge => Some(RiskLevel::Medium),
+            _ => None,
+        };
+
         // Check modification time
         if let Ok(modified) = metadata.modified() {
             if let Ok(age) = SystemTime::now().duration_since(modified) {
-                if age < Duration::from_secs(3 * 24 * 60 * 60) {
-                    return RiskLevel::High; // Modified in last 3 days
-                }
-                if age < Duration::from_secs(7 * 24 * 60 * 60) {
-                    return RiskLevel::Medium; // Modified in last week
-                }
-                if age < Duration::from_secs(30 * 24 * 60 * 60) {
-                    return RiskLevel::Low; // Modified in last month
+                let days = age.as_secs() / (24 * 60 * 60);
+                let thresholds = &self.age_thresholds;
+                let age_level = if age < thresholds.recent {
+                    reasons.push(format!(
+                        "Modified {} days ago, within the last {} days",
+                        days,
+                        as_days(thresholds.recent)
+                    ));
+                    Some(RiskLevel::High)
+                } else if age < thresholds.week {
+                    reasons.push(format!(
+                        "Modified {} days ago, within the last {} days",
+                        days,
+                        as_days(thresholds.week)
+                    ));
+                    Some(RiskLevel::Medium)
+                } else if age < thresholds.month {
+                    reasons.push(format!(
+                        "Modified {} days ago, within the last {} days",
+                        days,
+                        as_days(thresholds.month)
+                    ));
+                    Some(RiskLevel::Low)
+                } else {
+                    reasons.push(format!("Modified {} days ago", days));
+                    None
+                };
+
+                if let Some(level) = age_level {
+                    return match type_floor {
+                        Some(floor) if floor > level => {
+                            reasons
+                                .push(format!("Huge {} file, so at least {:?}", file_type, floor));
+                            RiskAssessment::new(floor, reasons)
+                        }
+                        _ => RiskAssessment::new(level, reasons),
+                    };
                 }
             }
         }
 
         // Check file type
-        let file_type = self.detect_file_type(path);
-        match file_type {
-            FileType::Database | FileType::Configuration => return RiskLevel::High,
-            FileType::Source => return RiskLevel::Medium,
-            FileType::TestData => return RiskLevel::Low,
-            FileType::Log | FileType::Archive => return RiskLevel::Safe,
-            _ => {}
+        let type_level = match file_type {
+            FileType::Database | FileType::Configuration => Some(RiskLevel::High),
+            FileType::Source => Some(RiskLevel::Medium),
+            FileType::TestData => Some(RiskLevel::Low),
+            FileType::Log | FileType::Archive | FileType::BuildArtifact => Some(RiskLevel::Safe),
+            _ => None,
+        };
+        reasons.push(format!("Detected file type is {}", file_type));
+        if let Some(level) = type_level {
+            return RiskAssessment::new(level, reasons);
         }
 
         // Check if it's test data
         if self.is_test_data(path) {
-            return RiskLevel::Low;
+            reasons.push("Matches a test data pattern".to_string());
+            return RiskAssessment::new(RiskLevel::Low, reasons);
+        }
+
+        // An unexpected huge file is more likely to matter than a small one
+        if huge {
+            reasons.push(format!(
+                "Old file of no particular type, at least {} times the size threshold",
+                HUGE_FILE_FACTOR
+            ));
+            return RiskAssessment::new(RiskLevel::Medium, reasons);
         }
 
         // Default to low risk for old files
-        RiskLevel::Low
+        reasons.push("Old file of no particular type".to_string());
+        RiskAssessment::new(RiskLevel::Low, reasons)
     }
 }
 
+/// Check if `.gitattributes` hands a file in a repository to the git LFS filter
+fn is_lfs_file(repo: &Repository, relative_path: &Path) -> bool {
+    matches!(
+        repo.get_attr(relative_path, "filter", AttrCheckFlags::FILE_THEN_INDEX),
+        Ok(Some("lfs"))
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs::{self, File};
     use tempdir::TempDir;
 
+    #[test]
+    fn test_file_type_display() {
+        assert_eq!(FileType::TestData.to_string(), "test data");
+        assert_eq!(FileType::BrokenSymlink.to_string(), "broken symlink");
+        assert_eq!(FileType::Configuration.to_string(), "configuration");
+        assert_eq!(format!("{:?}", FileType::TestData), "TestData");
+        assert_eq!(format!("{:<6}|", FileType::Log), "log   |");
+    }
+
+    #[test]
+    fn test_file_type_from_str() {
+        for file_type in FileType::ALL {
+            assert_eq!(file_type.to_string().parse::<FileType>(), Ok(file_type));
+            assert_eq!(
+                format!("{:?}", file_type).parse::<FileType>(),
+                Ok(file_type)
+            );
+        }
+
+        assert_eq!("test-data".parse::<FileType>(), Ok(FileType::TestData));
+        assert_eq!(
+            "BROKEN_SYMLINK".parse::<FileType>(),
+            Ok(FileType::BrokenSymlink)
+        );
+        assert!("spreadsheet".parse::<FileType>().is_err());
+    }
+
     #[test]
     fn test_file_type_detection() {
         let filter = SmartFilter::new();
@@ -462,6 +1011,61 @@ mod tests {
         assert!(!filter.is_protected(Path::new("regular.txt")));
     }
 
+    #[test]
+    fn test_protection_ignores_case_if_asked() {
+        let mut filter = SmartFilter::new();
+        filter.add_protected_pattern("*.KEEP");
+
+        filter.set_case_insensitive(true);
+        assert!(filter.is_protected(Path::new("/app/.ENV")));
+        assert!(filter.is_protected(Path::new("Credentials.txt")));
+        assert!(filter.is_protected(Path::new("dump.keep")));
+        assert!(filter.is_test_data(Path::new("Fixture-users.json")));
+
+        filter.set_case_insensitive(false);
+        assert!(!filter.is_protected(Path::new("/app/.ENV")));
+        assert!(!filter.is_protected(Path::new("Credentials.txt")));
+        assert!(!filter.is_protected(Path::new("dump.keep")));
+        assert!(!filter.is_test_data(Path::new("Fixture-users.json")));
+        assert!(filter.is_protected(Path::new("/app/.env")));
+    }
+
+    #[test]
+    fn test_lockfiles_are_protected() {
+        let filter = SmartFilter::new();
+
+        for name in LOCKFILE_NAMES {
+            let path = Path::new("/projects/app").join(name);
+            assert!(is_lockfile(&path), "{}", name);
+            assert_eq!(filter.is_protected_by(&path), Some(name));
+        }
+        assert!(filter.is_protected(Path::new("package-lock.json")));
+        assert!(!is_lockfile(Path::new("/projects/app/Cargo.toml")));
+        assert!(!filter.is_protected(Path::new("/projects/app/package.json")));
+    }
+
+    #[test]
+    fn test_is_protected_by_names_the_pattern() {
+        let mut filter = SmartFilter::new();
+        filter.add_protected_pattern("*.keep");
+
+        assert_eq!(filter.is_protected_by(Path::new(".env")), Some(".env"));
+        assert_eq!(
+            filter.is_protected_by(Path::new("/app/.env.production")),
+            Some(".env.*")
+        );
+        assert_eq!(
+            filter.is_protected_by(Path::new("dump.keep")),
+            Some("*.keep")
+        );
+        assert_eq!(filter.is_protected_by(Path::new("regular.txt")), None);
+
+        assert!(filter.protected_patterns().contains(&"*.keep".to_string()));
+        assert!(filter
+            .test_data_patterns()
+            .contains(&"fixture*".to_string()));
+    }
+
     #[test]
     fn test_pattern_matching() {
         assert!(SmartFilter::matches_pattern("test.txt", "*.txt"));
@@ -497,6 +1101,327 @@ mod tests {
         assert!(!filter.git_repos.is_empty());
     }
 
+    /// Commit the given files to a new repository
+    fn init_repo_with_commit(path: &Path, files: &[&str]) -> Repository {
+        let repo = Repository::init(path).unwrap();
+        {
+            let mut index = repo.index().unwrap();
+            for file in files {
+                fs::write(path.join(file), "data").unwrap();
+                index.add_path(Path::new(file)).unwrap();
+            }
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+            repo.commit(Some("HEAD"), &signature, &signature, "Initial", &tree, &[])
+                .unwrap();
+        }
+        repo
+    }
+
+    #[test]
+    fn test_git_status_in_linked_worktree() {
+        let temp_dir = TempDir::new("git_test").unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let main_path = root.join("main");
+        fs::create_dir(&main_path).unwrap();
+        let repo = init_repo_with_commit(&main_path, &["tracked.dat", "changed.dat"]);
+
+        // A worktree inside the main repository, as tools like to put them
+        let worktree_path = main_path.join("feature");
+        repo.worktree("feature", &worktree_path, None).unwrap();
+        assert!(worktree_path.join(".git").is_file());
+        fs::write(worktree_path.join("changed.dat"), "changed").unwrap();
+        fs::write(worktree_path.join("new.dat"), "data").unwrap();
+
+        let mut filter = SmartFilter::new();
+        filter.discover_git_repos(&main_path).unwrap();
+        filter.discover_git_repos(&worktree_path).unwrap();
+        assert_eq!(filter.git_repos.len(), 2);
+        assert!(filter.git_repos.contains_key(&worktree_path));
+
+        let statuses = |filter: &SmartFilter| {
+            ["tracked.dat", "changed.dat", "new.dat"]
+                .map(|name| filter.get_git_status(&worktree_path.join(name)))
+        };
+        let expected = [
+            GitFileStatus::Tracked,
+            GitFileStatus::Modified,
+            GitFileStatus::Untracked,
+        ];
+        assert_eq!(statuses(&filter), expected);
+
+        // The worktree gets a snapshot of its own, apart from the main repository
+        filter.snapshot_git_statuses();
+        assert_eq!(statuses(&filter), expected);
+        assert_eq!(
+            filter.get_git_status(&main_path.join("changed.dat")),
+            GitFileStatus::Tracked
+        );
+    }
+
+    #[test]
+    fn test_bare_repo_is_skipped() {
+        let temp_dir = TempDir::new("git_test").unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let bare_path = root.join("backup.git");
+        Repository::init_bare(&bare_path).unwrap();
+        fs::write(bare_path.join("large.dat"), "data").unwrap();
+
+        let mut filter = SmartFilter::new();
+        filter.discover_git_repos(&bare_path).unwrap();
+        assert!(filter.git_repos.is_empty());
+        assert_eq!(
+            filter.get_git_status(&bare_path.join("large.dat")),
+            GitFileStatus::NotInRepo
+        );
+    }
+
+    #[test]
+    fn test_git_status_snapshot_matches_per_file_status() {
+        let temp_dir = TempDir::new("git_test").unwrap();
+        let repo_path = temp_dir.path().canonicalize().unwrap();
+        let repo = Repository::init(&repo_path).unwrap();
+
+        fs::write(repo_path.join(".gitignore"), "*.log\nbuild/\n").unwrap();
+        fs::create_dir(repo_path.join("build")).unwrap();
+        fs::create_dir(repo_path.join("new_dir")).unwrap();
+        let mut files = vec![];
+        for i in 0..50 {
+            for name in [
+                format!("tracked_{}.dat", i),
+                format!("changed_{}.dat", i),
+                format!("untracked_{}.dat", i),
+                format!("debug_{}.log", i),
+                format!("build/out_{}.o", i),
+                format!("new_dir/file_{}.dat", i),
+            ] {
+                fs::write(repo_path.join(&name), "data").unwrap();
+                files.push(repo_path.join(name));
+            }
+        }
+
+        // Stage the tracked and changed files, then change the latter
+        let mut index = repo.index().unwrap();
+        for i in 0..50 {
+            index
+                .add_path(Path::new(&format!("tracked_{}.dat", i)))
+                .unwrap();
+            index
+                .add_path(Path::new(&format!("changed_{}.dat", i)))
+                .unwrap();
+        }
+        index.write().unwrap();
+        for i in 0..50 {
+            fs::write(repo_path.join(format!("changed_{}.dat", i)), "changed").unwrap();
+        }
+
+        let mut per_file = SmartFilter::new();
+        per_file.discover_git_repos(&repo_path).unwrap();
+        let expected: Vec<GitFileStatus> =
+            files.iter().map(|f| per_file.get_git_status(f)).collect();
+        assert_eq!(
+            per_file.uncached_status_calls.load(Ordering::Relaxed),
+            files.len()
+        );
+
+        let mut snapshot = SmartFilter::new();
+        snapshot.discover_git_repos(&repo_path).unwrap();
+        snapshot.snapshot_git_statuses();
+        let actual: Vec<GitFileStatus> = files.iter().map(|f| snapshot.get_git_status(f)).collect();
+
+        assert_eq!(actual, expected);
+        assert_eq!(snapshot.uncached_status_calls.load(Ordering::Relaxed), 0);
+
+        // Every kind of status is covered
+        for status in [
+            GitFileStatus::Tracked,
+            GitFileStatus::Modified,
+            GitFileStatus::Untracked,
+            GitFileStatus::Ignored,
+        ] {
+            assert!(actual.contains(&status), "{:?} not covered", status);
+        }
+    }
+
+    #[test]
+    fn test_ide_dirs_are_at_least_high_risk() {
+        let temp_dir = TempDir::new("ide_test").unwrap();
+        let idea_dir = temp_dir.path().join(".idea");
+        fs::create_dir(&idea_dir).unwrap();
+
+        // Old logs are safe on their own
+        let old = SystemTime::now() - Duration::from_secs(60 * 24 * 60 * 60);
+        for path in [
+            idea_dir.join("workspace.log"),
+            temp_dir.path().join("app.log"),
+        ] {
+            let file = fs::File::create(&path).unwrap();
+            file.set_modified(old).unwrap();
+        }
+
+        let filter = SmartFilter::new();
+        let level_of = |filter: &SmartFilter, path: &Path| {
+            let metadata = fs::metadata(path).unwrap();
+            filter.calculate_risk_level(path, &metadata, false)
+        };
+
+        let in_idea = idea_dir.join("workspace.log");
+        assert_eq!(filter.ide_dir_of(&in_idea), Some(".idea"));
+        assert_eq!(level_of(&filter, &in_idea), RiskLevel::High);
+        assert_eq!(
+            level_of(&filter, &temp_dir.path().join("app.log")),
+            RiskLevel::Safe
+        );
+
+        // The set can be replaced
+        let mut filter = SmartFilter::new();
+        filter.set_ide_dirs(vec![".fleet".to_string()]);
+        assert_eq!(filter.ide_dir_of(&in_idea), None);
+        assert_eq!(level_of(&filter, &in_idea), RiskLevel::Safe);
+    }
+
+    #[test]
+    fn test_huge_files_are_riskier() {
+        let temp_dir = TempDir::new("huge_test").unwrap();
+        let mut filter = SmartFilter::new();
+        filter.set_size_threshold(1024);
+
+        // Otherwise identical files, one just above the threshold and one far above it
+        let create = |name: &str, size: u64, days_old: u64| {
+            let path = temp_dir.path().join(name);
+            let file = File::create(&path).unwrap();
+            file.set_len(size).unwrap();
+            file.set_modified(SystemTime::now() - Duration::from_secs(days_old * 24 * 60 * 60))
+                .unwrap();
+            path
+        };
+        let level_of = |path: &Path| {
+            let metadata = fs::metadata(path).unwrap();
+            filter.calculate_risk_level(path, &metadata, false)
+        };
+
+        // Source and configuration files keep the level of their type despite their age
+        assert_eq!(level_of(&create("small.rs", 2048, 20)), RiskLevel::Low);
+        assert_eq!(
+            level_of(&create("huge.rs", 20 * 1024, 20)),
+            RiskLevel::Medium
+        );
+        assert_eq!(level_of(&create("small.toml", 2048, 5)), RiskLevel::Medium);
+        assert_eq!(
+            level_of(&create("huge.toml", 20 * 1024, 5)),
+            RiskLevel::High
+        );
+
+        // Old files of unknown type
+        assert_eq!(level_of(&create("small.bin", 2048, 60)), RiskLevel::Low);
+        assert_eq!(
+            level_of(&create("huge.bin", 20 * 1024, 60)),
+            RiskLevel::Medium
+        );
+
+        // The protected patterns still decide
+        assert_eq!(
+            level_of(&create("huge.key", 20 * 1024, 60)),
+            RiskLevel::Critical
+        );
+    }
+
+    #[test]
+    fn test_build_artifacts_are_safe() {
+        let temp_dir = TempDir::new("artifact_test").unwrap();
+        let filter = SmartFilter::new();
+
+        for name in ["module.cpython-311.pyc", "Main.class", "main.o", "libfoo.a"] {
+            let path = temp_dir.path().join(name);
+            let file = File::create(&path).unwrap();
+            file.set_modified(SystemTime::now() - days(60)).unwrap();
+            let metadata = fs::metadata(&path).unwrap();
+
+            assert_eq!(
+                filter.detect_file_type(&path),
+                FileType::BuildArtifact,
+                "{}",
+                name
+            );
+            assert_eq!(
+                filter.calculate_risk_level(&path, &metadata, false),
+                RiskLevel::Safe,
+                "{}",
+                name
+            );
+        }
+
+        // Programs may need shared libraries at runtime
+        for name in ["libfoo.so", "foo.dll", "libfoo.dylib"] {
+            assert_eq!(filter.detect_file_type(Path::new(name)), FileType::Binary);
+        }
+    }
+
+    #[test]
+    fn test_custom_age_thresholds() {
+        let temp_dir = TempDir::new("age_test").unwrap();
+        let path = temp_dir.path().join("report.bin");
+        let file = File::create(&path).unwrap();
+        file.set_modified(SystemTime::now() - days(5)).unwrap();
+        let metadata = fs::metadata(&path).unwrap();
+
+        // Five days old is within the default week
+        let mut filter = SmartFilter::new();
+        assert_eq!(
+            filter.calculate_risk_level(&path, &metadata, false),
+            RiskLevel::Medium
+        );
+
+        // Still recent if recent means a week
+        filter.set_age_thresholds(AgeThresholds::from_days(Some(7), Some(14), None).unwrap());
+        assert_eq!(
+            filter.calculate_risk_level(&path, &metadata, false),
+            RiskLevel::High
+        );
+
+        // Already stale if a month is only four days
+        filter.set_age_thresholds(AgeThresholds::from_days(Some(1), Some(2), Some(4)).unwrap());
+        assert_eq!(
+            filter.calculate_risk_level(&path, &metadata, false),
+            RiskLevel::Low
+        );
+        assert!(filter
+            .calculate_risk_assessment(&path, &metadata, false)
+            .reasons
+            .contains(&"Modified 5 days ago".to_string()));
+    }
+
+    #[test]
+    fn test_reasons_name_file_types_readably() {
+        let temp_dir = TempDir::new("reason_test").unwrap();
+        let path = temp_dir.path().join("server.log");
+        let file = File::create(&path).unwrap();
+        file.set_modified(SystemTime::now() - days(60)).unwrap();
+        let metadata = fs::metadata(&path).unwrap();
+
+        let assessment = SmartFilter::new().calculate_risk_assessment(&path, &metadata, false);
+        assert_eq!(assessment.level, RiskLevel::Safe);
+        assert_eq!(
+            assessment.reasons.last().unwrap(),
+            "Detected file type is log"
+        );
+    }
+
+    #[test]
+    fn test_age_thresholds_must_increase() {
+        assert_eq!(
+            AgeThresholds::from_days(None, None, None).unwrap(),
+            AgeThresholds::default()
+        );
+        assert!(AgeThresholds::from_days(Some(10), None, None).is_err());
+        assert!(AgeThresholds::from_days(None, Some(60), None).is_err());
+        assert!(AgeThresholds::from_days(Some(7), Some(7), Some(7)).is_ok());
+
+        // Huge values don't overflow
+        assert!(AgeThresholds::from_days(Some(u64::MAX), Some(u64::MAX), Some(u64::MAX)).is_ok());
+    }
+
     #[test]
     fn test_gitignore_loading() {
         let temp_dir = TempDir::new("gitignore_test").unwrap();
@@ -507,9 +1432,70 @@ mod tests {
         fs::write(&gitignore_path, "*.log\ntemp/\n").unwrap();
 
         // Load gitignore
-        filter.load_gitignore(temp_dir.path()).unwrap();
+        filter.load_ignore_files(temp_dir.path()).unwrap();
 
         assert!(!filter.gitignore_cache.is_empty());
+        assert!(filter.is_gitignored(&temp_dir.path().join("debug.log")));
+    }
+
+    #[test]
+    fn test_ignore_and_fdignore_loading() {
+        let temp_dir = TempDir::new("gitignore_test").unwrap();
+        let mut filter = SmartFilter::new();
+
+        fs::write(temp_dir.path().join(".gitignore"), "*.log\n").unwrap();
+        fs::write(temp_dir.path().join(".ignore"), "*.iso\n").unwrap();
+        fs::write(temp_dir.path().join(".fdignore"), "*.bin\n").unwrap();
+
+        filter.load_ignore_files(temp_dir.path()).unwrap();
+
+        assert!(filter.is_gitignored(&temp_dir.path().join("debug.log")));
+        assert!(filter.is_gitignored(&temp_dir.path().join("disk.iso")));
+        assert!(filter.is_gitignored(&temp_dir.path().join("data.bin")));
+        assert!(!filter.is_gitignored(&temp_dir.path().join("notes.txt")));
+    }
+
+    #[test]
+    fn test_gitignore_negation() {
+        let temp_dir = TempDir::new("gitignore_test").unwrap();
+        let mut filter = SmartFilter::new();
+
+        fs::write(temp_dir.path().join(".gitignore"), "*.log\n!keep.log\n").unwrap();
+        filter.load_ignore_files(temp_dir.path()).unwrap();
+
+        assert!(filter.is_gitignored(&temp_dir.path().join("debug.log")));
+        assert!(!filter.is_gitignored(&temp_dir.path().join("keep.log")));
+    }
+
+    #[test]
+    fn test_gitignore_nearest_directory_decides() {
+        let temp_dir = TempDir::new("gitignore_test").unwrap();
+        let sub_dir = temp_dir.path().join("logs");
+        let build_dir = temp_dir.path().join("build");
+        fs::create_dir(&sub_dir).unwrap();
+        fs::create_dir(&build_dir).unwrap();
+
+        let mut filter = SmartFilter::new();
+        fs::write(temp_dir.path().join(".gitignore"), "*.log\nbuild/\n").unwrap();
+        fs::write(sub_dir.join(".gitignore"), "!keep.log\n").unwrap();
+        filter.load_ignore_files(temp_dir.path()).unwrap();
+        filter.load_ignore_files(&sub_dir).unwrap();
+
+        // Re-included by the nearer .gitignore
+        assert!(!filter.is_gitignored(&sub_dir.join("keep.log")));
+        // No rule in the nearer file, so the parent's rule applies
+        assert!(filter.is_gitignored(&sub_dir.join("other.log")));
+        // Files inside an ignored directory are ignored too
+        assert!(filter.is_gitignored(&build_dir.join("output.bin")));
+    }
+
+    #[test]
+    fn test_no_ignore_files() {
+        let temp_dir = TempDir::new("gitignore_test").unwrap();
+        let mut filter = SmartFilter::new();
+
+        filter.load_ignore_files(temp_dir.path()).unwrap();
+        assert!(filter.gitignore_cache.is_empty());
     }
 
     #[test]
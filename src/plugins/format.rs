@@ -0,0 +1,207 @@
+/// Writes scan results in the machine readable formats of `--format` and `--print0`
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use serde::Serialize;
+
+use super::report::{self, Summary};
+use super::{utils, ScanResult};
+
+/// The version of the `--format json` document, raised whenever its shape changes incompatibly
+pub const JSON_VERSION: u32 = 1;
+
+/// Writes results as newline delimited JSON, one result per line
+///
+/// Results can be written from several threads at once. Every line is
+/// written whole and flushed right away, so a reader never sees half a
+/// result and can start on each result as soon as it is found.
+#[derive(Debug)]
+pub struct NdjsonWriter<W: Write> {
+    state: Mutex<NdjsonState<W>>,
+}
+
+#[derive(Debug)]
+struct NdjsonState<W> {
+    out: W,
+    /// Paths that were written, since followed links can find a file twice
+    written: HashSet<PathBuf>,
+}
+
+impl<W: Write> NdjsonWriter<W> {
+    /// Create a writer that writes to `out`
+    pub fn new(out: W) -> Self {
+        NdjsonWriter {
+            state: Mutex::new(NdjsonState {
+                out,
+                written: HashSet::new(),
+            }),
+        }
+    }
+
+    /// Writes a result as one line, unless a result with the same path was written before
+    pub fn write(&self, result: &ScanResult) -> io::Result<()> {
+        let mut line = serde_json::to_vec(result)?;
+        line.push(b'\n');
+
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Failed to lock the output"))?;
+        if !state.written.insert(result.path.clone()) {
+            return Ok(());
+        }
+        state.out.write_all(&line)?;
+        state.out.flush()
+    }
+
+    /// Writes every result that was not written yet
+    pub fn write_all(&self, results: &[ScanResult]) -> io::Result<()> {
+        results.iter().try_for_each(|result| self.write(result))
+    }
+
+    /// Returns the underlying writer
+    pub fn into_inner(self) -> W {
+        let state = self
+            .state
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.out
+    }
+}
+
+/// The document `--format json` prints
+#[derive(Debug, Serialize)]
+struct JsonDocument<'a> {
+    version: u32,
+    scanned_at: String,
+    /// The first scan path, which is the only one in most runs
+    root: Option<&'a PathBuf>,
+    roots: &'a [PathBuf],
+    results: &'a [ScanResult],
+    summary: Summary,
+}
+
+/// Writes all results as one JSON document, along with when and where they were found
+///
+/// # Arguments
+/// * `out`        - Where to write the document
+/// * `roots`      - The scan paths
+/// * `scanned_at` - When the scan started
+/// * `results`    - The results of every plugin
+pub fn write_json<W: Write>(
+    mut out: W,
+    roots: &[PathBuf],
+    scanned_at: SystemTime,
+    results: &[ScanResult],
+) -> io::Result<()> {
+    let document = JsonDocument {
+        version: JSON_VERSION,
+        scanned_at: utils::format_rfc3339(scanned_at),
+        root: roots.first(),
+        roots,
+        results,
+        summary: report::summarize(results),
+    };
+    serde_json::to_writer_pretty(&mut out, &document)?;
+    writeln!(out)
+}
+
+/// Writes the path of every result followed by a NUL byte, as `find -print0` does
+///
+/// Paths are written as they are, so paths containing newlines survive the
+/// trip through `xargs -0`.
+pub fn write_print0<W: Write>(mut out: W, results: &[ScanResult]) -> io::Result<()> {
+    for result in results {
+        #[cfg(unix)]
+        out.write_all(std::os::unix::ffi::OsStrExt::as_bytes(
+            result.path.as_os_str(),
+        ))?;
+        #[cfg(not(unix))]
+        out.write_all(result.path.to_string_lossy().as_bytes())?;
+
+        out.write_all(b"\0")?;
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::tests::{result, risky_result};
+    use crate::plugins::RiskLevel;
+
+    #[test]
+    fn test_ndjson_lines_parse_as_results() {
+        let results = vec![
+            result("/data/a.bin", 300),
+            result("/data/b\nc.bin", 200),
+            result("/data/a.bin", 300),
+        ];
+
+        let writer = NdjsonWriter::new(Vec::new());
+        std::thread::scope(|scope| {
+            for result in &results {
+                let writer = &writer;
+                scope.spawn(move || writer.write(result).unwrap());
+            }
+        });
+
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        let mut parsed: Vec<ScanResult> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        parsed.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(parsed, results[..2]);
+    }
+
+    #[test]
+    fn test_print0_separates_paths_with_nul() {
+        let results = vec![
+            result("/data/a.bin", 300),
+            result("/data/b\nc.bin", 200),
+            result("/data/d e.bin", 100),
+        ];
+
+        let mut out = Vec::new();
+        write_print0(&mut out, &results).unwrap();
+
+        assert_eq!(out, b"/data/a.bin\0/data/b\nc.bin\0/data/d e.bin\0");
+    }
+
+    #[test]
+    fn test_json_document_shape() {
+        let results = vec![
+            result("/data/a.bin", 300),
+            result("/data/b.bin", 200),
+            risky_result("/data/c.bin", 100, RiskLevel::High),
+        ];
+        let scanned_at = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+
+        let mut out = Vec::new();
+        write_json(&mut out, &[PathBuf::from("/data")], scanned_at, &results).unwrap();
+        let document: serde_json::Value = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(document["version"], JSON_VERSION);
+        assert_eq!(document["scanned_at"], "2023-11-14T22:13:20Z");
+        assert_eq!(document["root"], "/data");
+        assert_eq!(document["roots"], serde_json::json!(["/data"]));
+
+        let parsed: Vec<ScanResult> = serde_json::from_value(document["results"].clone()).unwrap();
+        assert_eq!(parsed, results);
+
+        let summary = &document["summary"];
+        assert_eq!(summary["count"], 3);
+        assert_eq!(summary["total_size"], 600);
+        assert_eq!(
+            summary["by_risk"],
+            serde_json::json!([
+                { "risk_level": "Low", "count": 2, "total_size": 500 },
+                { "risk_level": "High", "count": 1, "total_size": 100 },
+            ])
+        );
+    }
+}
@@ -0,0 +1,255 @@
+/// Finds the `vendor` directories of Go modules and, on request, the shared module cache
+use super::mounts::RemoteFilter;
+use super::{dirs, safety};
+use super::{CleanupReport, FeaturePlugin, Plugin, PluginError, RiskLevel, ScanResult};
+use crate::settings::Settings;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use walkdir::{DirEntry, WalkDir};
+
+/// Go plugin for `vendor` directories and the module cache
+#[derive(Debug)]
+pub struct GoPlugin {
+    older_than_days: Option<u64>,
+    include_module_cache: bool,
+    include_remote: bool,
+    allow_system_paths: bool,
+    quiet: bool,
+    /// Where `go` keeps downloaded modules, if it could be found
+    module_cache: Option<PathBuf>,
+    /// The cache is shared by every path, so it is only reported by the first scan
+    module_cache_offered: AtomicBool,
+}
+
+impl Default for GoPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GoPlugin {
+    /// Create a new Go plugin with default settings
+    pub fn new() -> Self {
+        GoPlugin {
+            older_than_days: None,
+            include_module_cache: false,
+            include_remote: false,
+            allow_system_paths: false,
+            quiet: false,
+            module_cache: None,
+            module_cache_offered: AtomicBool::new(false),
+        }
+    }
+}
+
+impl Plugin for GoPlugin {
+    fn name(&self) -> &str {
+        "go"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn is_enabled(&self, settings: &Settings) -> bool {
+        settings.enable_go
+    }
+
+    fn configure(&mut self, settings: &Settings) -> Result<(), PluginError> {
+        if let Some(days) = settings.older_than_days {
+            self.apply_age_filter(days)?;
+        }
+
+        self.include_module_cache = settings.include_go_module_cache;
+        self.include_remote = settings.include_remote;
+        self.allow_system_paths = settings.i_know_what_im_doing;
+        self.quiet = settings.quiet;
+        if self.include_module_cache {
+            self.module_cache = module_cache_dir();
+        }
+
+        Ok(())
+    }
+
+    fn apply_age_filter(&mut self, days: u64) -> Result<(), PluginError> {
+        self.older_than_days = Some(days);
+        Ok(())
+    }
+}
+
+impl FeaturePlugin for GoPlugin {
+    fn scan(&self, path: &Path) -> Result<Vec<ScanResult>, PluginError> {
+        let mut remote = RemoteFilter::new(path, self.include_remote, self.quiet);
+        let mut results: Vec<ScanResult> = go_modules(path, &mut remote)
+            .iter()
+            .map(|module| module.join("vendor"))
+            .filter(|vendor| vendor.is_dir())
+            .filter_map(|vendor| {
+                dirs::dir_result(
+                    &vendor,
+                    "Go vendor directory, restored by `go mod vendor`",
+                    RiskLevel::Low,
+                    self.older_than_days,
+                )
+            })
+            .collect();
+
+        if self.include_module_cache && !self.module_cache_offered.swap(true, Ordering::Relaxed) {
+            let cache = self.module_cache.as_deref().filter(|cache| cache.is_dir());
+            if let Some(result) = cache.and_then(|cache| {
+                dirs::dir_result(
+                    cache,
+                    "Go module cache, shared by all Go projects of this user",
+                    RiskLevel::Medium,
+                    self.older_than_days,
+                )
+            }) {
+                results.push(result);
+            }
+        }
+
+        Ok(results)
+    }
+
+    fn clean(&self, selected: Vec<ScanResult>) -> Result<CleanupReport, PluginError> {
+        dirs::clean_dirs(selected, self.allow_system_paths, self.quiet)
+    }
+}
+
+/// Finds the directories below a path that contain a `go.mod` file
+///
+/// Hidden directories and `vendor` directories are not searched, since
+/// the modules vendored into a project are not projects of their own.
+fn go_modules(path: &Path, remote: &mut RemoteFilter) -> Vec<PathBuf> {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0 || !(is_skipped_dir(entry) || remote.skips_entry(entry))
+        })
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file() && entry.file_name() == "go.mod")
+        .filter_map(|entry| entry.path().parent().map(Path::to_path_buf))
+        .collect()
+}
+
+/// Check if the scan should not descend into a directory
+fn is_skipped_dir(entry: &DirEntry) -> bool {
+    let name = entry.file_name().to_string_lossy();
+    entry.file_type().is_dir() && (name == "vendor" || name.starts_with('.'))
+}
+
+/// Finds the module cache the way `go env GOMODCACHE` does
+///
+/// `GOMODCACHE` wins if set, then `pkg/mod` in the first `GOPATH` entry,
+/// then `go/pkg/mod` in the home directory.
+fn module_cache_dir() -> Option<PathBuf> {
+    if let Some(cache) = std::env::var_os("GOMODCACHE").filter(|c| !c.is_empty()) {
+        return Some(PathBuf::from(cache));
+    }
+
+    let gopath = std::env::var_os("GOPATH")
+        .and_then(|paths| std::env::split_paths(&paths).next())
+        .filter(|path| !path.as_os_str().is_empty())
+        .or_else(|| safety::home_dir().map(|home| home.join("go")))?;
+
+    Some(gopath.join("pkg").join("mod"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempdir::TempDir;
+
+    /// Creates a Go module with a vendored dependency in `dir`
+    fn create_module(dir: &Path) {
+        let dependency = dir
+            .join("vendor")
+            .join("github.com")
+            .join("pkg")
+            .join("errors");
+        fs::create_dir_all(&dependency).unwrap();
+        fs::write(dir.join("go.mod"), "module example.com/app\n\ngo 1.21\n").unwrap();
+        fs::write(dependency.join("errors.go"), vec![b'a'; 1000]).unwrap();
+        fs::write(dir.join("vendor").join("modules.txt"), vec![b'b'; 24]).unwrap();
+    }
+
+    #[test]
+    fn test_vendor_directories_are_found() {
+        let temp_dir = TempDir::new("go_test").unwrap();
+        let app = temp_dir.path().join("app");
+        create_module(&app);
+
+        // A vendor directory without a go.mod next to it is not Go's
+        fs::create_dir_all(temp_dir.path().join("php").join("vendor")).unwrap();
+
+        let results = GoPlugin::new().scan(temp_dir.path()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path, app.join("vendor"));
+        assert_eq!(results[0].size, 1024);
+        assert_eq!(results[0].risk_level, RiskLevel::Low);
+    }
+
+    #[test]
+    fn test_older_than_days_is_respected() {
+        let temp_dir = TempDir::new("go_test").unwrap();
+        create_module(temp_dir.path());
+
+        let mut plugin = GoPlugin::new();
+        plugin.apply_age_filter(30).unwrap();
+        assert!(plugin.scan(temp_dir.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_module_cache_only_with_flag() {
+        let temp_dir = TempDir::new("go_test").unwrap();
+        let project = temp_dir.path().join("project");
+        create_module(&project);
+        let cache = temp_dir.path().join("gopath").join("pkg").join("mod");
+        fs::create_dir_all(cache.join("cache")).unwrap();
+        fs::write(cache.join("cache").join("download.zip"), vec![0; 4096]).unwrap();
+
+        let mut plugin = GoPlugin::new();
+        plugin.module_cache = Some(cache.clone());
+        let paths = |results: Vec<ScanResult>| -> Vec<PathBuf> {
+            results.into_iter().map(|result| result.path).collect()
+        };
+        assert_eq!(
+            paths(plugin.scan(&project).unwrap()),
+            vec![project.join("vendor")]
+        );
+
+        // With the flag the cache is offered once, however many paths are scanned
+        plugin.include_module_cache = true;
+        let results = plugin.scan(&project).unwrap();
+        assert_eq!(results[1].risk_level, RiskLevel::Medium);
+        assert_eq!(results[1].size, 4096);
+        assert_eq!(paths(results), vec![project.join("vendor"), cache]);
+        assert_eq!(
+            paths(plugin.scan(&project).unwrap()),
+            vec![project.join("vendor")]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_clean_removes_read_only_module_cache() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new("go_test").unwrap();
+        let module = temp_dir.path().join("mod").join("example.com@v1.0.0");
+        fs::create_dir_all(&module).unwrap();
+        fs::write(module.join("lib.go"), "package lib").unwrap();
+        fs::set_permissions(&module, fs::Permissions::from_mode(0o555)).unwrap();
+
+        let mut plugin = GoPlugin::new();
+        plugin.module_cache = Some(temp_dir.path().join("mod"));
+        plugin.include_module_cache = true;
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        let report = plugin.clean(results).unwrap();
+        assert_eq!(report.items_cleaned, 1);
+        assert!(report.errors.is_empty());
+        assert!(!temp_dir.path().join("mod").exists());
+    }
+}
@@ -0,0 +1,93 @@
+/// Runs the user's post-clean hook command
+use std::io;
+use std::process::{Command, ExitStatus};
+
+use super::CleanupReport;
+
+/// Lists the environment variables passed to the hook
+///
+/// # Arguments
+/// * `report` - The report of the cleanup that just finished
+pub fn hook_env(report: &CleanupReport) -> Vec<(&'static str, String)> {
+    vec![
+        ("SWEEP_ITEMS_CLEANED", report.items_cleaned.to_string()),
+        ("SWEEP_ITEMS_SKIPPED", report.items_skipped.to_string()),
+        ("SWEEP_ERRORS", report.errors.len().to_string()),
+        ("SWEEP_SPACE_FREED", report.space_freed.to_string()),
+    ]
+}
+
+/// Runs the hook command through the system shell and waits for it to finish
+///
+/// # Arguments
+/// * `command` - The shell command to run
+/// * `report`  - The report of the cleanup, passed to the command as environment variables
+///
+/// # Returns
+/// The exit status of the command
+pub fn run_post_clean_hook(command: &str, report: &CleanupReport) -> io::Result<ExitStatus> {
+    shell_command(command).envs(hook_env(report)).status()
+}
+
+/// Describes the exit status of the hook for the final summary
+pub fn describe_status(status: &ExitStatus) -> String {
+    match status.code() {
+        Some(0) => "Post-clean hook succeeded".to_string(),
+        Some(code) => format!("Post-clean hook failed with exit code {}", code),
+        None => "Post-clean hook was terminated by a signal".to_string(),
+    }
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
+
+#[cfg(not(windows))]
+fn shell_command(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn report() -> CleanupReport {
+        CleanupReport {
+            items_cleaned: 3,
+            items_skipped: 1,
+            space_freed: 4096,
+            dirs_pruned: 0,
+            errors: vec![],
+        }
+    }
+
+    #[test]
+    fn test_trivial_hook() {
+        let status = run_post_clean_hook("exit 0", &report()).unwrap();
+        assert!(status.success());
+        assert_eq!(describe_status(&status), "Post-clean hook succeeded");
+
+        let status = run_post_clean_hook("exit 3", &report()).unwrap();
+        assert_eq!(
+            describe_status(&status),
+            "Post-clean hook failed with exit code 3"
+        );
+    }
+
+    #[test]
+    fn test_hook_env() {
+        let status = run_post_clean_hook(
+            "test \"$SWEEP_ITEMS_CLEANED\" = 3 && test \"$SWEEP_ITEMS_SKIPPED\" = 1 && \
+             test \"$SWEEP_ERRORS\" = 0 && test \"$SWEEP_SPACE_FREED\" = 4096",
+            &report(),
+        )
+        .unwrap();
+
+        assert!(status.success());
+    }
+}
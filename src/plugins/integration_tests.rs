@@ -1,6 +1,9 @@
 use super::{
-    filter::SmartFilter, large_files::LargeFilePlugin, utils, FeaturePlugin, Plugin, RiskLevel,
+    filter::{GitFileStatus, SmartFilter},
+    large_files::LargeFilePlugin,
+    utils, FeaturePlugin, Plugin, RiskLevel,
 };
+use crate::output::{ColorChoice, OutputFormat};
 use crate::settings::Settings;
 use git2::{Repository, Signature};
 use std::fs::{self, File};
@@ -130,17 +133,70 @@ pub fn create_test_settings(
 ) -> Settings {
     Settings {
         paths: vec![],
+        command: None,
         all: false,
-        ignore: None,
+        ignore: vec![],
+        exclude: vec![],
+        paths_from: None,
+        null_separated: false,
         force: false,
+        yes: false,
+        select_safe: false,
+        interactive: false,
+        quiet: false,
+        color: ColorChoice::Auto,
+        format: OutputFormat::Text,
+        print0: false,
+        max_depth: None,
+        follow_symlinks: false,
+        include_hidden: false,
+        include_remote: false,
+        detect_mime: false,
+        threads: None,
         enable_large_files,
+        enable_go: false,
+        include_go_module_cache: false,
+        enable_dotnet: false,
+        enable_largest_dirs: false,
         enable_python: false,
         enable_java: false,
         enable_javascript: false,
         enable_rust: false,
         older_than_days,
+        modified_before: None,
+        accessed_before: None,
+        risk_month_days: None,
+        risk_week_days: None,
+        risk_recent_days: None,
         size_threshold: size_threshold.to_string(),
+        max_size: None,
+        si: false,
+        max_risk: None,
+        exclude_types: vec![],
         include_git_tracked,
+        no_gitignore: false,
+        save_scan: None,
+        load_scan: None,
+        post_clean_hook: None,
+        export_script: None,
+        confirm_over: None,
+        when_full: None,
+        watch: None,
+        prune_empty_dirs: false,
+        stats: false,
+        stats_by_dir: false,
+        summary_only: false,
+        shred: false,
+        top: None,
+        per_dir_limit: None,
+        sort: None,
+        reverse: false,
+        i_know_what_im_doing: false,
+        protected_patterns: vec![],
+        case_insensitive: false,
+        case_sensitive: false,
+        ide_dirs: None,
+        manifest_path: None,
     }
 }
 
@@ -193,6 +249,73 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_no_plugin_offers_lockfiles() -> Result<(), Box<dyn std::error::Error>> {
+        use super::super::{dotnet::DotNetPlugin, filter::LOCKFILE_NAMES, go::GoPlugin};
+
+        let env = TestEnvironment::new(false)?;
+        let size = 2 * 1024 * 1024;
+
+        // A Go module with its go.sum and vendored dependencies
+        env.create_file("app/go.mod", 32)?;
+        env.create_file("app/go.sum", size)?;
+        env.create_file("app/vendor/modules.txt", size)?;
+        // A .NET project with its lockfile and build output
+        env.create_file("service/service.csproj", 32)?;
+        env.create_file("service/packages.lock.json", size)?;
+        env.create_file("service/bin/service.dll", size)?;
+        // Lockfiles of other package managers, large enough to be found
+        for name in LOCKFILE_NAMES {
+            env.create_file(&format!("projects/{}", name), size)?;
+        }
+        // Output directories that hold a lockfile are projects, not output
+        env.create_file("legacy/go.mod", 32)?;
+        env.create_file("legacy/vendor/go.sum", size)?;
+        env.create_file("tool/tool.csproj", 32)?;
+        env.create_file("tool/bin/yarn.lock", size)?;
+
+        let settings = Settings {
+            enable_go: true,
+            enable_dotnet: true,
+            ..create_test_settings(true, "1MB", None, false)
+        };
+        let mut plugins: Vec<Box<dyn FeaturePlugin>> = vec![
+            Box::new(LargeFilePlugin::new()),
+            Box::new(GoPlugin::new()),
+            Box::new(DotNetPlugin::new()),
+        ];
+
+        let mut offered = Vec::new();
+        for plugin in &mut plugins {
+            plugin.configure(&settings)?;
+            offered.extend(plugin.scan(env.path())?);
+        }
+
+        // Each plugin still finds what it should
+        let paths: Vec<&Path> = offered.iter().map(|r| r.path.as_path()).collect();
+        assert!(paths.iter().any(|p| p.ends_with("app/vendor")));
+        assert!(paths.iter().any(|p| p.ends_with("service/bin")));
+        assert!(!paths.iter().any(|p| p.ends_with("legacy/vendor")));
+        assert!(!paths.iter().any(|p| p.ends_with("tool/bin")));
+
+        for result in &offered {
+            assert!(
+                !super::super::filter::is_lockfile(&result.path),
+                "{} was offered",
+                result.path.display()
+            );
+            for name in LOCKFILE_NAMES {
+                assert!(
+                    !result.path.join(name).exists(),
+                    "{}",
+                    result.path.display()
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_plugin_flag_combinations() -> Result<(), Box<dyn std::error::Error>> {
         // Create test environment to test different configurations
@@ -263,6 +386,42 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_lfs_tracked_file_protection() -> Result<(), Box<dyn std::error::Error>> {
+        let env = TestEnvironment::new(true)?;
+
+        fs::write(
+            env.path().join(".gitattributes"),
+            "*.bin filter=lfs diff=lfs merge=lfs -text\n",
+        )?;
+        env.create_file("model.bin", 4096)?;
+        env.create_file("notes.dat", 4096)?;
+        env.git_add_and_commit(".gitattributes", "Track binaries with LFS")?;
+        env.git_add_and_commit("model.bin", "Add model")?;
+        env.git_add_and_commit("notes.dat", "Add notes")?;
+
+        let mut filter = SmartFilter::new();
+        filter.discover_git_repos(env.path())?;
+
+        let model = env.path().join("model.bin");
+        let notes = env.path().join("notes.dat");
+        assert_eq!(filter.get_git_status(&model), GitFileStatus::LfsTracked);
+        assert_eq!(filter.get_git_status(&notes), GitFileStatus::Tracked);
+
+        let metadata = fs::metadata(&model)?;
+        let assessment = filter.calculate_risk_assessment(&model, &metadata, false);
+        assert_eq!(assessment.level, RiskLevel::Critical);
+        assert!(assessment.reasons.last().unwrap().contains("git LFS"));
+
+        // Only critical because git-tracked files are excluded
+        assert_ne!(
+            filter.calculate_risk_level(&model, &metadata, true),
+            RiskLevel::Critical
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_gitignore_file_handling() -> Result<(), Box<dyn std::error::Error>> {
         // Create test environment with git
@@ -339,17 +498,70 @@ mod tests {
         // Test invalid size threshold
         let invalid_settings = Settings {
             paths: vec![],
+            command: None,
             all: false,
-            ignore: None,
+            ignore: vec![],
+            exclude: vec![],
+            paths_from: None,
+            null_separated: false,
             force: false,
+            yes: false,
+            select_safe: false,
+            interactive: false,
+            quiet: false,
+            color: ColorChoice::Auto,
+            format: OutputFormat::Text,
+            print0: false,
+            max_depth: None,
+            follow_symlinks: false,
+            include_hidden: false,
+            include_remote: false,
+            detect_mime: false,
+            threads: None,
             enable_large_files: true,
+            enable_go: false,
+            include_go_module_cache: false,
+            enable_dotnet: false,
+            enable_largest_dirs: false,
             enable_python: false,
             enable_java: false,
             enable_javascript: false,
             enable_rust: false,
             older_than_days: None,
+            modified_before: None,
+            accessed_before: None,
+            risk_month_days: None,
+            risk_week_days: None,
+            risk_recent_days: None,
             size_threshold: "invalid_size".to_string(),
+            max_size: None,
+            si: false,
+            max_risk: None,
+            exclude_types: vec![],
             include_git_tracked: false,
+            no_gitignore: false,
+            save_scan: None,
+            load_scan: None,
+            post_clean_hook: None,
+            export_script: None,
+            confirm_over: None,
+            when_full: None,
+            watch: None,
+            prune_empty_dirs: false,
+            stats: false,
+            stats_by_dir: false,
+            summary_only: false,
+            shred: false,
+            top: None,
+            per_dir_limit: None,
+            sort: None,
+            reverse: false,
+            i_know_what_im_doing: false,
+            protected_patterns: vec![],
+            case_insensitive: false,
+            case_sensitive: false,
+            ide_dirs: None,
+            manifest_path: None,
         };
 
         // Should fail to configure with invalid size
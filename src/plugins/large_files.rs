@@ -1,14 +1,28 @@
-use super::filter::{FileType, GitFileStatus, SmartFilter};
-use super::progress::ScanProgress;
-use super::{CleanupReport, FeaturePlugin, Plugin, PluginError, RiskLevel, ScanResult};
-use crate::settings::Settings;
-use crossbeam::channel::unbounded;
+use super::filter::{AgeThresholds, FileType, GitFileStatus, SmartFilter, IGNORE_FILE_NAMES};
+use super::manifest::DeletionManifest;
+use super::mounts::RemoteFilter;
+use super::progress::{
+    CleanupProgress, IndexingProgress, ResultStream, ScanObserver, ScanProgress,
+};
+use super::{dirs, safety, utils};
+use super::{
+    CleanupError, CleanupReport, FeaturePlugin, Plugin, PluginError, RiskLevel, ScanResult,
+};
+use crate::output;
+use crate::settings::{is_excluded, Settings};
+use crate::ScanOptions;
+use crossbeam::channel::bounded;
 use rayon::prelude::*;
+use regex::Regex;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime};
 use walkdir::{DirEntry, WalkDir};
+use yansi::Color;
+
+/// Number of found files that can queue up per scan thread before the threads wait
+const RESULTS_PER_THREAD: usize = 64;
 
 /// File information for large file detection
 #[allow(dead_code)]
@@ -27,31 +41,105 @@ pub struct LargeFile {
 #[derive(Debug)]
 pub struct LargeFilePlugin {
     size_threshold_bytes: u64,
+    max_size_bytes: Option<u64>,
+    max_risk: Option<RiskLevel>,
+    exclude_types: Vec<FileType>,
     older_than_days: Option<u64>,
+    /// Only files modified before this time are reported
+    modified_before: Option<SystemTime>,
+    /// Only files accessed before this time are reported
+    accessed_before: Option<SystemTime>,
     include_git_tracked: bool,
+    allow_system_paths: bool,
+    ignore_patterns: Vec<Regex>,
+    exclude_paths: Vec<PathBuf>,
+    quiet: bool,
+    si_units: bool,
+    threads: usize,
+    max_depth: Option<usize>,
+    follow_symlinks: bool,
+    include_hidden: bool,
+    include_remote: bool,
+    shred: bool,
+    top: Option<usize>,
+    /// Where the manifest of a cleanup is written before deleting, not written if `None`
+    manifest_path: Option<PathBuf>,
     filter: Arc<Mutex<SmartFilter>>,
 }
 
+impl Default for LargeFilePlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl LargeFilePlugin {
     /// Create a new large file plugin with default settings
     pub fn new() -> Self {
         LargeFilePlugin {
             size_threshold_bytes: 100 * 1024 * 1024, // 100MB default
+            max_size_bytes: None,
+            max_risk: None,
+            exclude_types: Vec::new(),
             older_than_days: None,
+            modified_before: None,
+            accessed_before: None,
             include_git_tracked: false,
+            allow_system_paths: false,
+            ignore_patterns: vec![],
+            exclude_paths: vec![],
+            quiet: false,
+            si_units: false,
+            threads: 0, // All cores
+            max_depth: None,
+            follow_symlinks: false,
+            include_hidden: false,
+            include_remote: false,
+            shred: false,
+            top: None,
+            manifest_path: None,
             filter: Arc::new(Mutex::new(SmartFilter::new())),
         }
     }
 
-    /// Check if a file should be included based on age filter
+    /// Create a large file plugin from library scan options
+    pub fn from_options(opts: ScanOptions) -> Self {
+        LargeFilePlugin {
+            size_threshold_bytes: opts.min_size,
+            max_size_bytes: opts.max_size,
+            older_than_days: opts.older_than_days,
+            include_git_tracked: opts.include_git_tracked,
+            ignore_patterns: opts.ignore,
+            exclude_paths: opts
+                .exclude
+                .iter()
+                .map(|p| dunce::canonicalize(p).unwrap_or_else(|_| p.clone()))
+                .collect(),
+            ..LargeFilePlugin::new()
+        }
+    }
+
+    /// Check if a file should be included based on the age filters, which all have to pass
+    ///
+    /// Files whose timestamps can't be read pass, as they always have with `--older-than`.
     fn should_include_by_age(&self, metadata: &fs::Metadata) -> bool {
+        let before = |cutoff: Option<SystemTime>, time: std::io::Result<SystemTime>| match cutoff {
+            Some(cutoff) => time.map_or(true, |time| time < cutoff),
+            None => true,
+        };
+        if !before(self.modified_before, metadata.modified())
+            || !before(self.accessed_before, metadata.accessed())
+        {
+            return false;
+        }
+
         match self.older_than_days {
             None => true,
             Some(days) => {
                 match metadata.accessed() {
                     Ok(accessed) => {
                         match SystemTime::now().duration_since(accessed) {
-                            Ok(age) => age > Duration::from_secs(days * 24 * 60 * 60),
+                            Ok(age) => age > Duration::from_secs(days.saturating_mul(24 * 60 * 60)),
                             Err(_) => true, // If we can't determine age, include it
                         }
                     }
@@ -63,26 +151,63 @@ impl LargeFilePlugin {
 
     /// Process a single directory entry
     fn process_entry(&self, entry: DirEntry) -> Option<LargeFile> {
-        // Skip directories and symlinks
+        // Skip ignored paths
+        if self.is_ignored(entry.path()) {
+            return None;
+        }
+
+        // Followed links report the type of their target, so only unfollowed links get here
         let file_type = entry.file_type();
+        if file_type.is_symlink() {
+            return self.process_symlink(entry.path());
+        }
+
+        // Skip directories
         if !file_type.is_file() {
             return None;
         }
 
-        // Get metadata
-        let metadata = match entry.metadata() {
-            Ok(m) => m,
-            Err(_) => return None,
-        };
+        let metadata = entry.metadata().ok()?;
+        self.process_file(entry.path(), &metadata)
+    }
 
-        // Check size threshold
+    /// Process a path from a `--paths-from` list, without walking into directories
+    fn process_listed_path(&self, path: &Path) -> Option<LargeFile> {
+        if self.is_ignored(path) || is_excluded(&self.exclude_paths, path) {
+            return None;
+        }
+
+        let metadata = fs::symlink_metadata(path).ok()?;
+        if metadata.file_type().is_symlink() && !self.follow_symlinks {
+            return self.process_symlink(path);
+        }
+
+        let metadata = fs::metadata(path).ok()?;
+        if !metadata.is_file() {
+            return None;
+        }
+        self.process_file(path, &metadata)
+    }
+
+    /// Check if a path matches one of the `--ignore` patterns
+    fn is_ignored(&self, path: &Path) -> bool {
+        let path_str = path.to_string_lossy();
+        self.ignore_patterns.iter().any(|re| re.is_match(&path_str))
+    }
+
+    /// Process a regular file, reporting it if it passes the size, age and risk filters
+    fn process_file(&self, path: &Path, metadata: &fs::Metadata) -> Option<LargeFile> {
+        // Check size bounds
         let size = metadata.len();
         if size < self.size_threshold_bytes {
             return None;
         }
+        if matches!(self.max_size_bytes, Some(max) if size > max) {
+            return None;
+        }
 
         // Check age filter
-        if !self.should_include_by_age(&metadata) {
+        if !self.should_include_by_age(metadata) {
             return None;
         }
 
@@ -92,18 +217,31 @@ impl LargeFilePlugin {
 
         // Use smart filter for enhanced analysis
         let filter = self.filter.lock().ok()?;
-        let path = entry.path();
         let file_type = filter.detect_file_type(path);
+        if self.exclude_types.contains(&file_type) {
+            return None;
+        }
         let git_status = filter.get_git_status(path);
-        let risk_level = filter.calculate_risk_level(path, &metadata, self.include_git_tracked);
+        let risk_level = filter.calculate_risk_level(path, metadata, self.include_git_tracked);
 
         // Skip critical risk files unless explicitly included
         if risk_level == RiskLevel::Critical && !self.include_git_tracked {
             return None;
         }
 
+        // Skip files above the requested risk level
+        if matches!(self.max_risk, Some(max) if risk_level > max) {
+            return None;
+        }
+
+        // The same file can be reached through differently spelled paths
+        let path = utils::canonical_path(path)?;
+        if is_excluded(&self.exclude_paths, &path) {
+            return None;
+        }
+
         Some(LargeFile {
-            path: path.to_path_buf(),
+            path,
             size,
             last_modified,
             last_accessed,
@@ -113,6 +251,161 @@ impl LargeFilePlugin {
         })
     }
 
+    /// Process a symlink that is not followed, reporting it only if it is broken
+    fn process_symlink(&self, path: &Path) -> Option<LargeFile> {
+        if fs::metadata(path).is_ok() {
+            return None;
+        }
+
+        // A dangling link takes up no real space and points nowhere, so it's always low risk
+        let risk_level = RiskLevel::Low;
+        if matches!(self.max_risk, Some(max) if risk_level > max)
+            || self.exclude_types.contains(&FileType::BrokenSymlink)
+        {
+            return None;
+        }
+
+        let metadata = fs::symlink_metadata(path).ok()?;
+        let filter = self.filter.lock().ok()?;
+
+        let canonical = utils::canonical_path(path)?;
+        if is_excluded(&self.exclude_paths, &canonical) {
+            return None;
+        }
+
+        Some(LargeFile {
+            path: canonical,
+            size: metadata.len(),
+            last_modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            last_accessed: metadata.accessed().unwrap_or(SystemTime::UNIX_EPOCH),
+            risk_level,
+            file_type: FileType::BrokenSymlink,
+            git_status: filter.get_git_status(path),
+        })
+    }
+
+    /// Scan a path for large files, reporting progress to the observer if one is given
+    ///
+    /// Pass `None` to scan without any terminal output.
+    pub fn scan_with_observer(
+        &self,
+        path: &Path,
+        observer: Option<&dyn ScanObserver>,
+    ) -> Result<Vec<ScanResult>, PluginError> {
+        // Check if path exists
+        if !path.exists() {
+            return Err(PluginError::Scan(format!(
+                "Path does not exist: {:?}",
+                path
+            )));
+        }
+
+        // A file given as the scan path is classified on its own, like a listed path
+        if path.is_file() {
+            return self.scan_file(path, observer);
+        }
+
+        // Perform parallel scan
+        let large_files = self.scan_parallel(path, observer)?;
+
+        // Convert to ScanResult with enhanced information
+        Ok(large_files
+            .into_iter()
+            .map(|file| self.to_scan_result(file))
+            .collect())
+    }
+
+    /// Classify a single file that was given as the scan path
+    fn scan_file(
+        &self,
+        path: &Path,
+        observer: Option<&dyn ScanObserver>,
+    ) -> Result<Vec<ScanResult>, PluginError> {
+        if let Some(observer) = observer {
+            observer.on_start(1);
+            observer.on_scanned(path);
+        }
+
+        let results = self.scan_paths(&[path.to_path_buf()])?;
+
+        if let Some(observer) = observer {
+            for result in &results {
+                observer.on_found();
+                observer.on_result(result);
+            }
+            observer.on_finish();
+        }
+        Ok(results)
+    }
+
+    /// Classify exactly the given files, instead of walking a directory
+    ///
+    /// Paths that don't exist, directories and files that don't pass the
+    /// filters are skipped. The results are sorted like those of a scan.
+    ///
+    /// # Arguments
+    /// * `paths` - The files to classify, e.g. from `fd` or `find`
+    pub fn scan_paths(&self, paths: &[PathBuf]) -> Result<Vec<ScanResult>, PluginError> {
+        // Relative paths from `find .` would not match the git and ignore file paths
+        let paths: Vec<PathBuf> = paths
+            .iter()
+            .filter_map(|path| utils::canonical_path(path))
+            .collect();
+        self.initialize_filters_for_files(&paths)?;
+
+        let mut files: Vec<LargeFile> = paths
+            .iter()
+            .filter_map(|path| self.process_listed_path(path))
+            .collect();
+        files.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+        files.dedup_by(|a, b| a.path == b.path);
+        if let Some(top) = self.top {
+            files.truncate(top);
+        }
+
+        Ok(files
+            .into_iter()
+            .map(|file| self.to_scan_result(file))
+            .collect())
+    }
+
+    /// Describe a large file as a scan result
+    fn to_scan_result(&self, file: LargeFile) -> ScanResult {
+        let size_str = if self.si_units {
+            utils::format_size_si(file.size)
+        } else {
+            utils::format_size(file.size)
+        };
+        // Modification times in the future count as just now
+        let age = utils::humanize_age(
+            SystemTime::now()
+                .duration_since(file.last_modified)
+                .unwrap_or_default(),
+        );
+
+        let git_str = format!("{:?}", file.git_status);
+
+        ScanResult {
+            path: file.path,
+            size: file.size,
+            description: format!(
+                "{} | {} | Type: {} | Git: {}",
+                size_str, age, file.file_type, git_str
+            ),
+            risk_level: file.risk_level,
+            last_modified: Some(file.last_modified),
+            mime_type: None,
+        }
+    }
+
+    /// Initialize the filters for a path, with a spinner since big repositories take a while
+    fn index_filters(&self, root: &Path) -> Result<(), PluginError> {
+        let indexing = IndexingProgress::new(self.quiet);
+        self.initialize_filters(root)?;
+        indexing.finish();
+        Ok(())
+    }
+
     /// Initialize git repositories and gitignore caches for a path
     fn initialize_filters(&self, root: &Path) -> Result<(), PluginError> {
         let mut filter = self
@@ -123,78 +416,363 @@ impl LargeFilePlugin {
         // Discover git repositories
         filter.discover_git_repos(root)?;
 
-        // Load gitignore files
-        for entry in WalkDir::new(root)
+        // Ask git for all statuses at once instead of once per file
+        filter.snapshot_git_statuses();
+
+        if !filter.uses_ignore_rules() {
+            return Ok(());
+        }
+        // Building the rules takes a while in big monorepos, so others may use the filter meanwhile
+        drop(filter);
+
+        // Find the directories with .gitignore, .ignore or .fdignore files
+        let mut dirs: Vec<PathBuf> = WalkDir::new(root)
             .max_depth(5) // Limit depth for performance
             .into_iter()
             .filter_map(|e| e.ok())
-        {
-            if entry.file_name() == ".gitignore" {
-                if let Some(parent) = entry.path().parent() {
-                    let _ = filter.load_gitignore(parent);
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| IGNORE_FILE_NAMES.contains(&name))
+            })
+            .filter_map(|entry| entry.path().parent().map(Path::to_path_buf))
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+
+        // Files that fail to parse are skipped, the other directories still get their rules
+        let rules: Vec<_> = dirs
+            .into_par_iter()
+            .filter_map(|dir| {
+                let gitignore = SmartFilter::build_ignore_rules(&dir).ok()??;
+                Some((dir, gitignore))
+            })
+            .collect();
+
+        let mut filter = self
+            .filter
+            .lock()
+            .map_err(|e| PluginError::Configuration(format!("Failed to lock filter: {}", e)))?;
+        for (dir, gitignore) in rules {
+            filter.add_ignore_rules(&dir, gitignore);
+        }
+
+        Ok(())
+    }
+
+    /// Initialize git repositories and ignore files for a list of files
+    ///
+    /// Only the ignore files from each file's directory up to its repository
+    /// root are loaded, since nothing else is walked.
+    fn initialize_filters_for_files(&self, paths: &[PathBuf]) -> Result<(), PluginError> {
+        let mut filter = self
+            .filter
+            .lock()
+            .map_err(|e| PluginError::Configuration(format!("Failed to lock filter: {}", e)))?;
+
+        let mut dirs: Vec<&Path> = paths.iter().filter_map(|path| path.parent()).collect();
+        dirs.sort();
+        dirs.dedup();
+
+        for dir in dirs {
+            filter.discover_git_repos(dir)?;
+            for ancestor in dir.ancestors() {
+                let _ = filter.load_ignore_files(ancestor);
+                if ancestor.join(".git").exists() {
+                    break;
                 }
             }
         }
 
+        filter.snapshot_git_statuses();
         Ok(())
     }
 
-    /// Scan directory in parallel
-    fn scan_parallel(&self, root: &Path) -> Result<Vec<LargeFile>, PluginError> {
+    /// Scan directory in parallel, reporting progress to the observer if one is given
+    fn scan_parallel(
+        &self,
+        root: &Path,
+        observer: Option<&dyn ScanObserver>,
+    ) -> Result<Vec<LargeFile>, PluginError> {
         // Initialize filters with git repo and gitignore discovery
-        self.initialize_filters(root)?;
-
-        let (tx, rx) = unbounded();
+        self.index_filters(root)?;
 
         // Clone Arc for parallel processing
         let filter_arc = Arc::clone(&self.filter);
         let size_threshold = self.size_threshold_bytes;
+        let max_size_bytes = self.max_size_bytes;
+        let max_risk = self.max_risk;
+        let exclude_types = self.exclude_types.clone();
         let older_than_days = self.older_than_days;
+        let modified_before = self.modified_before;
+        let accessed_before = self.accessed_before;
         let include_git_tracked = self.include_git_tracked;
+        let allow_system_paths = self.allow_system_paths;
+        let ignore_patterns = self.ignore_patterns.clone();
+        let exclude_paths = self.exclude_paths.clone();
+        let quiet = self.quiet;
+        let si_units = self.si_units;
+        let threads = self.threads;
+        let max_depth = self.max_depth;
+        let follow_symlinks = self.follow_symlinks;
+        let include_hidden = self.include_hidden;
+        let include_remote = self.include_remote;
+        let shred = self.shred;
+        let top = self.top;
+        let manifest_path = self.manifest_path.clone();
 
         // Create a plugin instance for the parallel context
         let plugin_for_scan = LargeFilePlugin {
             size_threshold_bytes: size_threshold,
+            max_size_bytes,
+            max_risk,
+            exclude_types,
             older_than_days,
+            modified_before,
+            accessed_before,
             include_git_tracked,
+            allow_system_paths,
+            ignore_patterns,
+            exclude_paths,
+            quiet,
+            si_units,
+            threads,
+            max_depth,
+            follow_symlinks,
+            include_hidden,
+            include_remote,
+            shred,
+            top,
+            manifest_path,
             filter: filter_arc,
         };
 
         // Collect entries first to enable parallel processing
-        let entries: Vec<_> = WalkDir::new(root)
+        // When following links, walkdir detects loops and yields them as errors
+        let mut walker = WalkDir::new(root).follow_links(self.follow_symlinks);
+        if let Some(depth) = self.max_depth {
+            walker = walker.max_depth(depth);
+        }
+        let mut remote = RemoteFilter::new(root, self.include_remote, self.quiet);
+        // Hidden directories are pruned here so their contents are never walked
+        let entries: Vec<_> = walker
             .into_iter()
+            .filter_entry(|entry| {
+                !is_excluded(&self.exclude_paths, entry.path())
+                    && (self.include_hidden || !is_hidden(entry))
+                    && !remote.skips_entry(entry)
+            })
             .filter_map(|e| e.ok())
             .collect();
 
-        // Create progress bar
-        let progress = Arc::new(ScanProgress::new(entries.len() as u64));
-        let progress_clone = Arc::clone(&progress);
+        if let Some(observer) = observer {
+            observer.on_start(entries.len() as u64);
+        }
 
-        // Process entries in parallel
-        entries.par_iter().for_each_with(tx, |tx, entry| {
-            // Update progress
-            progress_clone.update(entry.path());
+        // Use a dedicated pool so the number of threads can be limited
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.threads)
+            .build()
+            .map_err(|e| PluginError::Scan(format!("Failed to create thread pool: {}", e)))?;
 
-            if let Some(large_file) = plugin_for_scan.process_entry(entry.clone()) {
-                progress_clone.found_file();
-                let _ = tx.send(large_file);
-            }
-        });
+        // Workers wait for the collector once the channel is full, so memory use stays bounded
+        let (tx, rx) = bounded(pool.current_num_threads() * RESULTS_PER_THREAD);
+
+        let mut results = std::thread::scope(|scope| {
+            // Collect results while the workers are still sending them
+            let plugin = &plugin_for_scan;
+            let result_observer = observer.filter(|observer| observer.wants_results());
+            let collector = scope.spawn(move || {
+                rx.iter()
+                    .inspect(|file: &LargeFile| {
+                        if let Some(observer) = result_observer {
+                            observer.on_result(&plugin.to_scan_result(file.clone()));
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            });
 
-        // Collect results
-        let mut results = Vec::new();
-        while let Ok(file) = rx.try_recv() {
-            results.push(file);
+            // Process entries in parallel, every sender is dropped once all entries are done
+            pool.install(|| {
+                entries.par_iter().for_each_with(tx, |tx, entry| {
+                    // Update progress
+                    if let Some(observer) = observer {
+                        observer.on_scanned(entry.path());
+                    }
+
+                    if let Some(large_file) = plugin_for_scan.process_entry(entry.clone()) {
+                        if let Some(observer) = observer {
+                            observer.on_found();
+                        }
+                        let _ = tx.send(large_file);
+                    }
+                });
+            });
+
+            collector.join()
+        })
+        // A panic while collecting loses results, which must not look like a clean scan
+        .map_err(|_| PluginError::Scan("Collecting the scan results failed".to_string()))?;
+
+        if let Some(observer) = observer {
+            observer.on_finish();
         }
 
-        // Finish progress bar
-        progress.finish();
+        // Sort by size (largest first), by path if equal since the collection order varies
+        results.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
 
-        // Sort by size (largest first)
-        results.sort_by(|a, b| b.size.cmp(&a.size));
+        // Followed links can lead to the same file twice, which sorts next to itself
+        results.dedup_by(|a, b| a.path == b.path);
+
+        // Keep only the largest files if requested
+        if let Some(top) = self.top {
+            results.truncate(top);
+        }
 
         Ok(results)
     }
+
+    /// Delete the selected files with the given function
+    ///
+    /// Transient failures are retried, so a file only counts as failed
+    /// if every attempt to delete it failed.
+    ///
+    /// # Arguments
+    /// * `selected` - The files to delete
+    /// * `remove`   - Deletes a single file, `fs::remove_file` outside of tests
+    fn clean_with<F>(
+        &self,
+        selected: Vec<ScanResult>,
+        mut remove: F,
+    ) -> Result<CleanupReport, PluginError>
+    where
+        F: FnMut(&Path) -> std::io::Result<()>,
+    {
+        // Refuse the whole batch before touching anything if a single path is off-limits
+        for result in &selected {
+            safety::check_deletion(&result.path, self.allow_system_paths)?;
+        }
+
+        let filter = self
+            .filter
+            .lock()
+            .map_err(|e| PluginError::LockError(format!("Failed to lock filter: {}", e)))?;
+
+        let mut report = CleanupReport::default();
+
+        if self.shred && !selected.is_empty() {
+            output::println(
+                "Warning",
+                Color::Yellow,
+                "Shredding can't guarantee the data is gone on SSDs or copy-on-write filesystems",
+            );
+        }
+
+        // A manifest that can't be written is no reason to keep the files
+        if let (Some(path), false) = (&self.manifest_path, selected.is_empty()) {
+            if let Err(e) = DeletionManifest::new(&selected).save(path) {
+                output::println(
+                    "Warning",
+                    Color::Yellow,
+                    &format!(
+                        "Could not record the files to delete in {}: {}",
+                        path.display(),
+                        e
+                    ),
+                );
+            }
+        }
+
+        let progress = if self.quiet {
+            CleanupProgress::with_quiet(selected.len() as u64, true)
+        } else {
+            CleanupProgress::new(selected.len() as u64)
+        };
+
+        for result in selected {
+            // Protected files are never deleted, and vanished files need no deleting.
+            // Look at the path itself so broken symlinks still count as existing.
+            let link_metadata = match fs::symlink_metadata(&result.path) {
+                Ok(metadata) if !filter.is_protected(&result.path) => metadata,
+                _ => {
+                    report.items_skipped += 1;
+                    continue;
+                }
+            };
+
+            // Directories, such as those loaded from a saved scan, go file by file,
+            // unless anything in them is protected
+            if link_metadata.is_dir() {
+                let protects_contents = walkdir::WalkDir::new(&result.path)
+                    .into_iter()
+                    .filter_map(Result::ok)
+                    .any(|entry| filter.is_protected(entry.path()));
+                if protects_contents {
+                    report.items_skipped += 1;
+                    continue;
+                }
+
+                let (freed, errors) = dirs::remove_dir_tree(&result.path, &progress);
+                report.space_freed += freed;
+                if errors.is_empty() {
+                    report.items_cleaned += 1;
+                } else {
+                    report.errors.extend(errors);
+                }
+                continue;
+            }
+
+            // The file may have changed since the scan, so bill its current size.
+            // For symlinks this is the size of the link, not of its target.
+            let freed = link_metadata.len();
+            if grew_substantially(result.size, freed) {
+                progress.suspend(|| {
+                    output::println(
+                        "Warning",
+                        Color::Yellow,
+                        &format!(
+                            "{} grew from {} to {} since it was scanned",
+                            result.path.display(),
+                            utils::format_size(result.size),
+                            utils::format_size(freed)
+                        ),
+                    )
+                });
+            }
+
+            // Overwriting through a symlink would destroy its target instead
+            if self.shred && link_metadata.file_type().is_file() {
+                if let Err(e) = utils::shred_file(&result.path, &link_metadata) {
+                    report.errors.push(CleanupError {
+                        path: result.path,
+                        message: format!("Could not shred file: {}", e),
+                    });
+                    continue;
+                }
+            }
+
+            match utils::remove_with_retry(&result.path, &mut remove) {
+                Ok(()) => {
+                    progress.file_deleted(&result.path, freed);
+                    report.items_cleaned += 1;
+                    report.space_freed += freed;
+                }
+                Err(e) => report.errors.push(CleanupError {
+                    path: result.path,
+                    message: e.to_string(),
+                }),
+            }
+        }
+
+        progress.finish();
+        Ok(report)
+    }
+}
+
+/// Check if a file grew by more than half its size between the scan and the cleanup
+fn grew_substantially(scanned: u64, current: u64) -> bool {
+    current > scanned.saturating_add(scanned / 2)
 }
 
 impl Plugin for LargeFilePlugin {
@@ -211,15 +789,95 @@ impl Plugin for LargeFilePlugin {
     }
 
     fn configure(&mut self, settings: &Settings) -> Result<(), PluginError> {
-        // Parse size threshold
-        self.size_threshold_bytes = super::utils::parse_size_string(&settings.size_threshold)?;
+        // Parse size bounds
+        self.si_units = settings.si;
+        let parse_size = if settings.si {
+            super::utils::parse_size_string_si
+        } else {
+            super::utils::parse_size_string
+        };
+        self.size_threshold_bytes = parse_size(&settings.size_threshold)?;
+        self.max_size_bytes = settings.max_size.as_deref().map(parse_size).transpose()?;
+
+        if let Some(max) = self.max_size_bytes {
+            if max < self.size_threshold_bytes {
+                return Err(PluginError::Configuration(format!(
+                    "--max-size ({}) must not be smaller than --min-size ({})",
+                    settings.max_size.as_deref().unwrap_or(""),
+                    settings.size_threshold
+                )));
+            }
+        }
+
+        // Set risk filter if provided
+        self.max_risk = settings.max_risk;
+        self.exclude_types = settings.exclude_types.clone();
 
         // Set age filter if provided
         self.older_than_days = settings.older_than_days;
+        self.modified_before = settings.modified_before;
+        self.accessed_before = settings.accessed_before;
 
         // Set git tracking preference
         self.include_git_tracked = settings.include_git_tracked;
 
+        // Set safety override
+        self.allow_system_paths = settings.i_know_what_im_doing;
+
+        // Set symlink handling
+        self.follow_symlinks = settings.follow_symlinks;
+
+        // Skip hidden files and directories unless requested
+        self.include_hidden = settings.include_hidden;
+        self.include_remote = settings.include_remote;
+
+        // Overwrite files before deleting them if requested
+        self.shred = settings.shred;
+
+        // Limit the results to the largest files if requested
+        self.top = settings.top;
+
+        // Record what a cleanup deletes so `swp undo-report` can show it
+        self.manifest_path = settings
+            .manifest_path
+            .clone()
+            .or_else(super::manifest::default_path);
+
+        // Set recursion limit, unlimited if not set
+        self.max_depth = settings.max_depth;
+
+        // Set thread count, 0 means all cores
+        self.threads = settings.threads.unwrap_or(0);
+
+        // Hide the progress bar if requested
+        self.quiet = settings.quiet;
+
+        // Set ignore pattern if provided
+        self.ignore_patterns = settings.ignore.clone();
+        self.exclude_paths = settings.exclude.clone();
+
+        // Add user-defined protected patterns
+        let mut filter = self
+            .filter
+            .lock()
+            .map_err(|e| PluginError::LockError(format!("Failed to lock filter: {}", e)))?;
+        for pattern in &settings.protected_patterns {
+            filter.add_protected_pattern(pattern);
+        }
+
+        // Replace the default IDE settings directories if configured
+        if let Some(dirs) = &settings.ide_dirs {
+            filter.set_ide_dirs(dirs.clone());
+        }
+        filter.set_size_threshold(self.size_threshold_bytes);
+        filter.set_use_ignore_rules(!settings.no_gitignore);
+        filter.set_case_insensitive(settings.ignores_name_case());
+        filter.set_age_thresholds(AgeThresholds::from_days(
+            settings.risk_recent_days,
+            settings.risk_week_days,
+            settings.risk_month_days,
+        )?);
+
         Ok(())
     }
 
@@ -229,76 +887,79 @@ impl Plugin for LargeFilePlugin {
     }
 }
 
+/// Turns the outcome of the interactive UI into a selection
+///
+/// Without a terminal nothing is selected, so automated runs finish instead of failing.
+pub(super) fn selection_from_ui(
+    outcome: std::io::Result<Vec<ScanResult>>,
+) -> Result<Vec<ScanResult>, PluginError> {
+    match outcome {
+        Ok(selected) => Ok(selected),
+        Err(e) if super::ui::is_no_terminal(&e) => {
+            output::println(
+                "Warning",
+                Color::Yellow,
+                "No terminal available for the interactive selection, nothing was selected",
+            );
+            Ok(vec![])
+        }
+        Err(e) => Err(PluginError::Configuration(format!("UI error: {}", e))),
+    }
+}
+
+/// Check if an entry is hidden or inside a hidden directory below the scan root
+///
+/// Only the components below the root are checked, so scanning a hidden
+/// directory directly still works.
+fn is_hidden(entry: &DirEntry) -> bool {
+    entry
+        .path()
+        .components()
+        .rev()
+        .take(entry.depth())
+        .any(|component| component.as_os_str().to_string_lossy().starts_with('.'))
+}
+
 impl FeaturePlugin for LargeFilePlugin {
     fn scan(&self, path: &Path) -> Result<Vec<ScanResult>, PluginError> {
-        // Check if path exists
-        if !path.exists() {
-            return Err(PluginError::Scan(format!(
-                "Path does not exist: {:?}",
-                path
-            )));
+        if self.quiet {
+            return self.scan_with_observer(path, None);
         }
 
-        // Perform parallel scan
-        let large_files = self.scan_parallel(path)?;
-
-        // Convert to ScanResult with enhanced information
-        let results: Vec<ScanResult> = large_files
-            .into_iter()
-            .map(|file| {
-                let size_str = super::utils::format_size(file.size);
-                let age_days =
-                    if let Ok(modified) = SystemTime::now().duration_since(file.last_modified) {
-                        modified.as_secs() / (24 * 60 * 60)
-                    } else {
-                        0
-                    };
-
-                let type_str = format!("{:?}", file.file_type);
-                let git_str = format!("{:?}", file.git_status);
+        self.scan_with_observer(path, Some(&ScanProgress::indeterminate()))
+    }
 
-                ScanResult {
-                    path: file.path,
-                    size: file.size,
-                    description: format!(
-                        "{} | {} days old | Type: {} | Git: {}",
-                        size_str, age_days, type_str, git_str
-                    ),
-                    risk_level: file.risk_level,
-                }
-            })
-            .collect();
+    fn scan_progress(&self) -> ScanProgress {
+        if self.quiet {
+            return ScanProgress::indeterminate_with_quiet(true);
+        }
 
-        Ok(results)
+        ScanProgress::indeterminate()
     }
 
-    fn interactive_select(&self, results: Vec<ScanResult>) -> Result<Vec<ScanResult>, PluginError> {
-        if results.is_empty() {
-            return Ok(results);
-        }
+    fn scan_streaming(
+        &self,
+        path: &Path,
+        progress: &ScanProgress,
+        on_result: &(dyn Fn(&ScanResult) + Sync),
+    ) -> Result<Vec<ScanResult>, PluginError> {
+        let stream = ResultStream::new(Some(progress), on_result);
+        self.scan_with_observer(path, Some(&stream))
+    }
 
-        // Use the interactive UI for selection
-        let mut selector = super::ui::InteractiveSelector::new(results);
-        match selector.run() {
-            Ok(selected) => Ok(selected),
-            Err(e) => Err(PluginError::Configuration(format!("UI error: {}", e))),
-        }
+    fn scan_listed(&self, paths: &[PathBuf]) -> Result<Vec<ScanResult>, PluginError> {
+        self.scan_paths(paths)
     }
 
-    fn clean(&self, _selected: Vec<ScanResult>) -> Result<CleanupReport, PluginError> {
-        // TODO: Implement cleanup logic
-        // This is a placeholder implementation
-        Ok(CleanupReport {
-            items_cleaned: 0,
-            space_freed: 0,
-            errors: vec![],
-        })
+    fn clean(&self, selected: Vec<ScanResult>) -> Result<CleanupReport, PluginError> {
+        self.clean_with(selected, |path| fs::remove_file(path))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::output::{ColorChoice, OutputFormat};
 
     #[test]
     fn test_large_file_plugin_creation() {
@@ -314,17 +975,70 @@ mod tests {
 
         let settings = Settings {
             paths: vec![],
+            command: None,
             all: false,
-            ignore: None,
+            ignore: vec![],
+            exclude: vec![],
+            paths_from: None,
+            null_separated: false,
             force: false,
+            yes: false,
+            select_safe: false,
+            interactive: false,
+            quiet: false,
+            color: ColorChoice::Auto,
+            format: OutputFormat::Text,
+            print0: false,
+            max_depth: None,
+            follow_symlinks: false,
+            include_hidden: false,
+            include_remote: false,
+            detect_mime: false,
+            threads: None,
             enable_large_files: true,
+            enable_go: false,
+            include_go_module_cache: false,
+            enable_dotnet: false,
+            enable_largest_dirs: false,
             enable_python: false,
             enable_java: false,
             enable_javascript: false,
             enable_rust: false,
             older_than_days: Some(30),
+            modified_before: None,
+            accessed_before: None,
+            risk_month_days: None,
+            risk_week_days: None,
+            risk_recent_days: None,
             size_threshold: "500MB".to_string(),
+            max_size: None,
+            si: false,
+            max_risk: None,
+            exclude_types: vec![],
             include_git_tracked: true,
+            no_gitignore: false,
+            save_scan: None,
+            load_scan: None,
+            post_clean_hook: None,
+            export_script: None,
+            confirm_over: None,
+            when_full: None,
+            watch: None,
+            prune_empty_dirs: false,
+            stats: false,
+            stats_by_dir: false,
+            summary_only: false,
+            shred: false,
+            top: None,
+            per_dir_limit: None,
+            sort: None,
+            reverse: false,
+            i_know_what_im_doing: false,
+            protected_patterns: vec![],
+            case_insensitive: false,
+            case_sensitive: false,
+            ide_dirs: None,
+            manifest_path: None,
         };
 
         assert!(plugin.is_enabled(&settings));
@@ -342,6 +1056,68 @@ mod tests {
         assert_eq!(plugin.older_than_days, Some(60));
     }
 
+    #[test]
+    fn test_ignore_files_are_loaded_in_parallel_like_sequentially() {
+        let temp_dir = tempdir::TempDir::new("gitignore_test").unwrap();
+        let root = dunce::canonicalize(temp_dir.path()).unwrap();
+        let mut files = Vec::new();
+        for package in 0..40 {
+            let dir = root
+                .join(format!("packages/group_{}", package % 4))
+                .join(format!("package_{}", package));
+            fs::create_dir_all(dir.join("dist")).unwrap();
+            // Every package ignores its own build output and one other name
+            fs::write(
+                dir.join(".gitignore"),
+                format!("dist/\n*.tmp\n!keep.tmp\nname_{}.bin\n", package),
+            )
+            .unwrap();
+            if package % 5 == 0 {
+                fs::write(dir.join(".ignore"), "*.bin\n").unwrap();
+            }
+            for name in [
+                "dist/bundle.js".to_string(),
+                "cache.tmp".to_string(),
+                "keep.tmp".to_string(),
+                format!("name_{}.bin", package),
+                format!("name_{}.bin", package + 1),
+                "src.rs".to_string(),
+            ] {
+                files.push(dir.join(name));
+            }
+        }
+
+        let plugin = LargeFilePlugin::new();
+        plugin.initialize_filters(&root).unwrap();
+        let parallel = plugin.filter.lock().unwrap();
+
+        let mut sequential = SmartFilter::new();
+        for entry in WalkDir::new(&root).max_depth(5) {
+            let entry = entry.unwrap();
+            if entry.file_name() == ".gitignore" || entry.file_name() == ".ignore" {
+                sequential
+                    .load_ignore_files(entry.path().parent().unwrap())
+                    .unwrap();
+            }
+        }
+
+        assert_eq!(parallel.ignore_rules_count(), 40);
+        for file in &files {
+            assert_eq!(
+                parallel.is_gitignored(file),
+                sequential.is_gitignored(file),
+                "{}",
+                file.display()
+            );
+        }
+        // The first package also ignores every .bin file, the second only its own name
+        assert!(parallel.is_gitignored(&files[0]));
+        assert!(!parallel.is_gitignored(&files[2]));
+        assert!(parallel.is_gitignored(&files[4]));
+        assert!(parallel.is_gitignored(&files[9]));
+        assert!(!parallel.is_gitignored(&files[10]));
+    }
+
     #[test]
     fn test_filter_integration() {
         let plugin = LargeFilePlugin::new();
@@ -351,6 +1127,350 @@ mod tests {
         assert!(filter.is_protected(Path::new(".env")));
         assert!(filter.is_test_data(Path::new("test-data.json")));
     }
+
+    #[test]
+    fn test_clean_deletes_selected_files() {
+        let temp_dir = tempdir::TempDir::new("clean_test").unwrap();
+        let file = temp_dir.path().join("large.bin");
+        fs::write(&file, vec![0u8; 2048]).unwrap();
+
+        let plugin = LargeFilePlugin::new();
+        let report = plugin
+            .clean(vec![ScanResult {
+                path: file.clone(),
+                size: 2048,
+                description: String::new(),
+                risk_level: RiskLevel::Safe,
+                last_modified: None,
+                mime_type: None,
+            }])
+            .unwrap();
+
+        assert_eq!(report.items_cleaned, 1);
+        assert_eq!(report.space_freed, 2048);
+        assert!(!file.exists());
+    }
+
+    #[test]
+    fn test_clean_deletes_directories_with_their_contents() {
+        let temp_dir = tempdir::TempDir::new("clean_test").unwrap();
+        let cache = temp_dir.path().join("node_modules");
+        fs::create_dir_all(cache.join("left-pad").join("lib")).unwrap();
+        fs::write(cache.join("left-pad").join("index.js"), vec![0u8; 1500]).unwrap();
+        fs::write(
+            cache.join("left-pad").join("lib").join("pad.js"),
+            vec![0u8; 700],
+        )
+        .unwrap();
+        fs::write(cache.join(".package-lock.json"), vec![0u8; 300]).unwrap();
+
+        // Anything protected inside keeps the whole directory
+        let config = temp_dir.path().join("config");
+        fs::create_dir(&config).unwrap();
+        fs::write(config.join(".env"), "TOKEN=secret").unwrap();
+
+        let dir_result = |path: &Path| ScanResult {
+            path: path.to_path_buf(),
+            size: utils::dir_size(path),
+            description: String::new(),
+            risk_level: RiskLevel::Low,
+            last_modified: None,
+            mime_type: None,
+        };
+        let plugin = LargeFilePlugin::new();
+        let report = plugin
+            .clean(vec![dir_result(&cache), dir_result(&config)])
+            .unwrap();
+
+        assert_eq!(report.items_cleaned, 1);
+        assert_eq!(report.items_skipped, 1);
+        assert_eq!(report.space_freed, 1500 + 700 + 300);
+        assert!(report.errors.is_empty());
+        assert!(!cache.exists());
+        assert!(config.join(".env").exists());
+    }
+
+    #[test]
+    fn test_clean_writes_manifest_first() {
+        let temp_dir = tempdir::TempDir::new("clean_test").unwrap();
+        let file = temp_dir.path().join("large.bin");
+        fs::write(&file, vec![0u8; 2048]).unwrap();
+        let manifest_path = temp_dir.path().join("state").join("last-run.json");
+
+        let plugin = LargeFilePlugin {
+            manifest_path: Some(manifest_path.clone()),
+            ..LargeFilePlugin::new()
+        };
+        let selected = vec![ScanResult {
+            path: file.clone(),
+            size: 2048,
+            description: String::new(),
+            risk_level: RiskLevel::Low,
+            last_modified: None,
+            mime_type: None,
+        }];
+        let report = plugin
+            .clean_with(selected, |path| {
+                // The manifest lists the file before it is removed
+                let manifest = DeletionManifest::load(&manifest_path).unwrap();
+                assert_eq!(manifest.entries[0].path, path);
+                fs::remove_file(path)
+            })
+            .unwrap();
+        assert_eq!(report.items_cleaned, 1);
+
+        let manifest = DeletionManifest::load(&manifest_path).unwrap();
+        assert_eq!(manifest.entries.len(), 1);
+        assert_eq!(manifest.entries[0].size, 2048);
+        assert_eq!(manifest.entries[0].risk_level, RiskLevel::Low);
+    }
+
+    #[test]
+    fn test_clean_proceeds_if_manifest_fails() {
+        let temp_dir = tempdir::TempDir::new("clean_test").unwrap();
+        let file = temp_dir.path().join("large.bin");
+        fs::write(&file, vec![0u8; 2048]).unwrap();
+
+        // The manifest directory can't be created below a file
+        let plugin = LargeFilePlugin {
+            manifest_path: Some(file.join("state").join("last-run.json")),
+            quiet: true,
+            ..LargeFilePlugin::new()
+        };
+        let report = plugin
+            .clean(vec![ScanResult {
+                path: file.clone(),
+                size: 2048,
+                description: String::new(),
+                risk_level: RiskLevel::Safe,
+                last_modified: None,
+                mime_type: None,
+            }])
+            .unwrap();
+
+        assert_eq!(report.items_cleaned, 1);
+        assert!(!file.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_clean_refuses_system_paths() {
+        let temp_dir = tempdir::TempDir::new("clean_test").unwrap();
+        let file = temp_dir.path().join("large.bin");
+        fs::write(&file, vec![0u8; 2048]).unwrap();
+
+        let plugin = LargeFilePlugin::new();
+        let selected = vec![
+            ScanResult {
+                path: file.clone(),
+                size: 2048,
+                description: String::new(),
+                risk_level: RiskLevel::Safe,
+                last_modified: None,
+                mime_type: None,
+            },
+            ScanResult {
+                path: PathBuf::from("/etc/hosts"),
+                size: 1,
+                description: String::new(),
+                risk_level: RiskLevel::Safe,
+                last_modified: None,
+                mime_type: None,
+            },
+        ];
+
+        match plugin.clean(selected) {
+            Err(PluginError::Configuration(msg)) => assert!(msg.contains("/etc")),
+            other => panic!("Expected a configuration error, got {:?}", other),
+        }
+
+        // Nothing in the batch should have been deleted
+        assert!(file.exists());
+    }
+
+    #[test]
+    fn test_clean_skips_protected_and_missing_files() {
+        let temp_dir = tempdir::TempDir::new("clean_test").unwrap();
+        let protected = temp_dir.path().join("secrets.bin");
+        fs::write(&protected, vec![0u8; 2048]).unwrap();
+
+        let plugin = LargeFilePlugin::new();
+        let report = plugin
+            .clean(vec![
+                ScanResult {
+                    path: protected.clone(),
+                    size: 2048,
+                    description: String::new(),
+                    risk_level: RiskLevel::Critical,
+                    last_modified: None,
+                    mime_type: None,
+                },
+                ScanResult {
+                    path: temp_dir.path().join("vanished.bin"),
+                    size: 2048,
+                    description: String::new(),
+                    risk_level: RiskLevel::Safe,
+                    last_modified: None,
+                    mime_type: None,
+                },
+            ])
+            .unwrap();
+
+        assert_eq!(report.items_cleaned, 0);
+        assert_eq!(report.items_skipped, 2);
+        assert_eq!(report.space_freed, 0);
+        assert!(report.errors.is_empty());
+        assert!(protected.exists());
+    }
+
+    #[test]
+    fn test_clean_skips_files_deleted_after_scan() {
+        let temp_dir = tempdir::TempDir::new("clean_test").unwrap();
+        let file = temp_dir.path().join("large.bin");
+        fs::write(&file, vec![0u8; 4096]).unwrap();
+
+        let plugin = LargeFilePlugin {
+            size_threshold_bytes: 1024,
+            ..LargeFilePlugin::new()
+        };
+        let results = plugin.scan_with_observer(temp_dir.path(), None).unwrap();
+        assert_eq!(results.len(), 1);
+
+        fs::remove_file(&file).unwrap();
+
+        let report = plugin.clean(results).unwrap();
+        assert_eq!(report.items_cleaned, 0);
+        assert_eq!(report.items_skipped, 1);
+        assert_eq!(report.space_freed, 0);
+        assert!(report.errors.is_empty());
+    }
+
+    #[test]
+    fn test_clean_reports_current_size() {
+        let temp_dir = tempdir::TempDir::new("clean_test").unwrap();
+        let shrunk = temp_dir.path().join("shrunk.bin");
+        let grown = temp_dir.path().join("grown.bin");
+        fs::write(&shrunk, vec![0u8; 4096]).unwrap();
+        fs::write(&grown, vec![0u8; 4096]).unwrap();
+
+        let plugin = LargeFilePlugin {
+            size_threshold_bytes: 1024,
+            ..LargeFilePlugin::new()
+        };
+        let results = plugin.scan_with_observer(temp_dir.path(), None).unwrap();
+        assert_eq!(results.len(), 2);
+
+        fs::write(&shrunk, vec![0u8; 1000]).unwrap();
+        fs::write(&grown, vec![0u8; 10000]).unwrap();
+
+        let report = plugin.clean(results).unwrap();
+        assert_eq!(report.items_cleaned, 2);
+        assert_eq!(report.space_freed, 11000);
+    }
+
+    #[test]
+    fn test_grew_substantially() {
+        assert!(!grew_substantially(1000, 1000));
+        assert!(!grew_substantially(1000, 1500));
+        assert!(grew_substantially(1000, 1501));
+        assert!(!grew_substantially(u64::MAX, u64::MAX));
+    }
+
+    #[test]
+    fn test_no_terminal_selects_nothing() {
+        let no_terminal = std::io::Error::new(std::io::ErrorKind::Unsupported, "no terminal");
+        assert_eq!(selection_from_ui(Err(no_terminal)).unwrap(), vec![]);
+
+        #[cfg(unix)]
+        {
+            let not_a_tty = std::io::Error::from_raw_os_error(25);
+            assert_eq!(selection_from_ui(Err(not_a_tty)).unwrap(), vec![]);
+        }
+
+        // Other failures are still errors
+        let broken = std::io::Error::new(std::io::ErrorKind::BrokenPipe, "broken pipe");
+        assert!(matches!(
+            selection_from_ui(Err(broken)),
+            Err(PluginError::Configuration(_))
+        ));
+    }
+
+    #[test]
+    fn test_clean_shreds_before_deleting() {
+        let temp_dir = tempdir::TempDir::new("clean_test").unwrap();
+        let file = temp_dir.path().join("dump.sql.bak");
+        let content = b"password=hunter2\n".repeat(200);
+        fs::write(&file, &content).unwrap();
+
+        // Reads the file back right before it's unlinked
+        let mut contents_at_delete = Vec::new();
+        let fake_remove = |path: &Path| {
+            contents_at_delete = fs::read(path)?;
+            fs::remove_file(path)
+        };
+
+        let plugin = LargeFilePlugin {
+            shred: true,
+            ..LargeFilePlugin::new()
+        };
+        let report = plugin
+            .clean_with(
+                vec![ScanResult {
+                    path: file.clone(),
+                    size: content.len() as u64,
+                    description: String::new(),
+                    risk_level: RiskLevel::Safe,
+                    last_modified: None,
+                    mime_type: None,
+                }],
+                fake_remove,
+            )
+            .unwrap();
+
+        assert_eq!(report.items_cleaned, 1);
+        assert_eq!(contents_at_delete.len(), content.len());
+        assert!(contents_at_delete.iter().all(|&b| b == 0));
+        assert!(!file.exists());
+    }
+
+    #[test]
+    fn test_clean_retries_transient_errors() {
+        let temp_dir = tempdir::TempDir::new("clean_test").unwrap();
+        let file = temp_dir.path().join("in-use.bin");
+        fs::write(&file, vec![0u8; 2048]).unwrap();
+
+        // Fails twice as if another process held the file open, then deletes it
+        let mut attempts = 0;
+        let fake_remove = |path: &Path| {
+            attempts += 1;
+            if attempts <= 2 {
+                Err(std::io::Error::from(std::io::ErrorKind::ResourceBusy))
+            } else {
+                fs::remove_file(path)
+            }
+        };
+
+        let plugin = LargeFilePlugin::new();
+        let report = plugin
+            .clean_with(
+                vec![ScanResult {
+                    path: file.clone(),
+                    size: 2048,
+                    description: String::new(),
+                    risk_level: RiskLevel::Safe,
+                    last_modified: None,
+                    mime_type: None,
+                }],
+                fake_remove,
+            )
+            .unwrap();
+
+        assert_eq!(attempts, 3);
+        assert_eq!(report.items_cleaned, 1);
+        assert_eq!(report.space_freed, 2048);
+        assert!(report.errors.is_empty());
+        assert!(!file.exists());
+    }
 }
 
 // Include scanner tests module
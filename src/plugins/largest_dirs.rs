@@ -0,0 +1,384 @@
+/// Finds the directories taking the most space, like a sorted `du -sh *`
+use super::filter::{AgeThresholds, SmartFilter, CASE_INSENSITIVE_FILESYSTEM};
+use super::mounts::RemoteFilter;
+use super::{dirs, sort, utils};
+use super::{CleanupReport, FeaturePlugin, Plugin, PluginError, RiskLevel, ScanResult};
+use crate::settings::Settings;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+/// How deep directories are listed without `--max-depth`, 1 being those directly in the scanned path
+const DEFAULT_DEPTH: usize = 1;
+
+/// Plugin listing the largest directories, rated by their contents
+#[derive(Debug)]
+pub struct LargestDirsPlugin {
+    max_depth: usize,
+    min_size: u64,
+    older_than_days: Option<u64>,
+    include_git_tracked: bool,
+    use_ignore_rules: bool,
+    protected_patterns: Vec<String>,
+    case_insensitive: bool,
+    age_thresholds: AgeThresholds,
+    include_remote: bool,
+    allow_system_paths: bool,
+    quiet: bool,
+}
+
+impl LargestDirsPlugin {
+    /// Create a new largest directories plugin with default settings
+    pub fn new() -> Self {
+        LargestDirsPlugin {
+            max_depth: DEFAULT_DEPTH,
+            min_size: 0,
+            older_than_days: None,
+            include_git_tracked: false,
+            use_ignore_rules: true,
+            protected_patterns: Vec::new(),
+            case_insensitive: CASE_INSENSITIVE_FILESYSTEM,
+            age_thresholds: AgeThresholds::default(),
+            include_remote: false,
+            allow_system_paths: false,
+            quiet: false,
+        }
+    }
+
+    /// Build the filter that rates the files below a scan root
+    fn filter_for(&self, root: &Path, dirs: &[PathBuf]) -> Result<SmartFilter, PluginError> {
+        let mut filter = SmartFilter::new();
+        for pattern in &self.protected_patterns {
+            filter.add_protected_pattern(pattern);
+        }
+        filter.set_case_insensitive(self.case_insensitive);
+        filter.set_use_ignore_rules(self.use_ignore_rules);
+        filter.set_age_thresholds(self.age_thresholds);
+
+        // Repositories deeper down are caught by their `.git` in `measure`
+        filter.discover_git_repos(root)?;
+        for dir in dirs {
+            filter.discover_git_repos(dir)?;
+        }
+        filter.snapshot_git_statuses();
+        Ok(filter)
+    }
+
+    /// Adds up the size, the newest modification and the risk of every listed directory
+    ///
+    /// The scan root is walked once, and everything in it counts towards each
+    /// listed directory it is in. A directory holding a git repository is
+    /// Critical. Every file is rated the way the large file plugin rates files,
+    /// so a directory with a git-tracked, protected or lock file is Critical too.
+    fn measure(
+        &self,
+        root: &Path,
+        listed: &[PathBuf],
+        filter: &SmartFilter,
+        remote: &mut RemoteFilter,
+    ) -> HashMap<PathBuf, DirContents> {
+        let mut contents: HashMap<PathBuf, DirContents> = listed
+            .iter()
+            .map(|dir| (dir.clone(), DirContents::default()))
+            .collect();
+
+        for entry in WalkDir::new(root)
+            .min_depth(1)
+            .into_iter()
+            .filter_entry(|entry| !remote.skips_entry(entry))
+            .filter_map(Result::ok)
+        {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            // A repository is critical as a whole, so the files inside are only counted
+            let risk = if entry.file_name() == ".git" {
+                Some(RiskLevel::Critical)
+            } else if metadata.is_file() && !is_in_repository_dir(root, entry.path()) {
+                Some(filter.calculate_risk_level(entry.path(), &metadata, self.include_git_tracked))
+            } else {
+                None
+            };
+
+            for dir in entry.path().ancestors() {
+                let Some(totals) = contents.get_mut(dir) else {
+                    continue;
+                };
+                if metadata.is_file() {
+                    totals.size += metadata.len();
+                }
+                totals.last_modified = totals.last_modified.max(metadata.modified().ok());
+                totals.risk = totals.risk.max(risk.unwrap_or(RiskLevel::Safe));
+            }
+        }
+
+        contents
+    }
+}
+
+/// Checks if a path is inside a `.git` directory below the root
+fn is_in_repository_dir(root: &Path, path: &Path) -> bool {
+    path.strip_prefix(root)
+        .is_ok_and(|relative| relative.components().any(|c| c.as_os_str() == ".git"))
+}
+
+/// What a listed directory holds, as far as the plugin cares
+#[derive(Debug)]
+struct DirContents {
+    /// The combined size of the regular files, like `utils::dir_size`
+    size: u64,
+    last_modified: Option<SystemTime>,
+    /// The risk of the riskiest thing in it
+    risk: RiskLevel,
+}
+
+impl Default for DirContents {
+    fn default() -> Self {
+        DirContents {
+            size: 0,
+            last_modified: None,
+            risk: RiskLevel::Safe,
+        }
+    }
+}
+
+impl Default for LargestDirsPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Plugin for LargestDirsPlugin {
+    fn name(&self) -> &str {
+        "largest-dirs"
+    }
+
+    fn version(&self) -> &str {
+        "1.0.0"
+    }
+
+    fn is_enabled(&self, settings: &Settings) -> bool {
+        settings.enable_largest_dirs
+    }
+
+    fn configure(&mut self, settings: &Settings) -> Result<(), PluginError> {
+        if let Some(days) = settings.older_than_days {
+            self.apply_age_filter(days)?;
+        }
+
+        let parse_size = if settings.si {
+            utils::parse_size_string_si
+        } else {
+            utils::parse_size_string
+        };
+        self.min_size = parse_size(&settings.size_threshold)?;
+        self.max_depth = settings.max_depth.unwrap_or(DEFAULT_DEPTH).max(1);
+        self.include_git_tracked = settings.include_git_tracked;
+        self.use_ignore_rules = !settings.no_gitignore;
+        self.protected_patterns = settings.protected_patterns.clone();
+        self.case_insensitive = settings.ignores_name_case();
+        self.age_thresholds = AgeThresholds::from_days(
+            settings.risk_recent_days,
+            settings.risk_week_days,
+            settings.risk_month_days,
+        )?;
+        self.include_remote = settings.include_remote;
+        self.allow_system_paths = settings.i_know_what_im_doing;
+        self.quiet = settings.quiet;
+        Ok(())
+    }
+
+    fn apply_age_filter(&mut self, days: u64) -> Result<(), PluginError> {
+        self.older_than_days = Some(days);
+        Ok(())
+    }
+}
+
+impl FeaturePlugin for LargestDirsPlugin {
+    fn scan(&self, path: &Path) -> Result<Vec<ScanResult>, PluginError> {
+        let root = dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let mut remote = RemoteFilter::new(&root, self.include_remote, self.quiet);
+        let found: Vec<PathBuf> = WalkDir::new(&root)
+            .min_depth(1)
+            .max_depth(self.max_depth)
+            .into_iter()
+            // The history of a repository is no directory to clean up
+            .filter_entry(|entry| entry.file_name() != ".git" && !remote.skips_entry(entry))
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_dir())
+            .map(|entry| entry.into_path())
+            .collect();
+
+        let filter = self.filter_for(&root, &found)?;
+        let mut results: Vec<ScanResult> = self
+            .measure(&root, &found, &filter, &mut remote)
+            .into_iter()
+            .filter(|(_, contents)| contents.size >= self.min_size)
+            .filter(|(_, contents)| {
+                dirs::is_old_enough(contents.last_modified, self.older_than_days)
+            })
+            .map(|(path, contents)| ScanResult {
+                path,
+                size: contents.size,
+                description: "Directory with everything in it".to_string(),
+                risk_level: contents.risk,
+                last_modified: contents.last_modified,
+                mime_type: None,
+            })
+            .collect();
+
+        results.sort_by(sort::largest_first);
+        Ok(results)
+    }
+
+    fn clean(&self, selected: Vec<ScanResult>) -> Result<CleanupReport, PluginError> {
+        // Repositories and directories with tracked or protected files are only listed
+        let (critical, selected): (Vec<ScanResult>, Vec<ScanResult>) = selected
+            .into_iter()
+            .partition(|result| result.risk_level == RiskLevel::Critical);
+
+        let mut report = dirs::clean_dirs(selected, self.allow_system_paths, self.quiet)?;
+        report.items_skipped += critical.len();
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempdir::TempDir;
+
+    /// Creates a file of `size` bytes, and the directories it is in
+    fn create_file(path: &Path, size: usize) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, vec![0; size]).unwrap();
+    }
+
+    fn ranking(results: &[ScanResult], root: &Path) -> Vec<(String, u64)> {
+        results
+            .iter()
+            .map(|result| {
+                let relative = result.path.strip_prefix(root).unwrap();
+                (relative.to_string_lossy().replace('\\', "/"), result.size)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_directories_are_ranked_by_recursive_size() {
+        let temp_dir = TempDir::new("largest_dirs_test").unwrap();
+        let root = dunce::canonicalize(temp_dir.path()).unwrap();
+        create_file(&root.join("videos").join("a.mp4"), 3000);
+        create_file(&root.join("videos").join("2023").join("b.mp4"), 4000);
+        create_file(&root.join("downloads").join("setup.iso"), 5000);
+        create_file(&root.join("notes").join("todo.txt"), 10);
+        create_file(&root.join("tiny.txt"), 9000);
+        fs::create_dir(root.join("empty")).unwrap();
+
+        let mut plugin = LargestDirsPlugin::new();
+        let results = plugin.scan(&root).unwrap();
+        assert_eq!(
+            ranking(&results, &root),
+            [
+                ("videos".to_string(), 7000),
+                ("downloads".to_string(), 5000),
+                ("notes".to_string(), 10),
+                ("empty".to_string(), 0),
+            ]
+        );
+
+        // Deeper levels list the directories inside as well
+        plugin.max_depth = 2;
+        plugin.min_size = 4000;
+        let results = plugin.scan(&root).unwrap();
+        assert_eq!(
+            ranking(&results, &root),
+            [
+                ("videos".to_string(), 7000),
+                ("downloads".to_string(), 5000),
+                ("videos/2023".to_string(), 4000),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_risk_reflects_the_contents() {
+        let temp_dir = TempDir::new("largest_dirs_test").unwrap();
+        let root = dunce::canonicalize(temp_dir.path()).unwrap();
+        create_file(&root.join("repo").join("src").join("main.rs"), 100);
+        git2::Repository::init(root.join("repo")).unwrap();
+        create_file(&root.join("secrets").join(".env"), 100);
+        create_file(&root.join("cache").join("blob.bin"), 100);
+
+        let results = LargestDirsPlugin::new().scan(&root).unwrap();
+        let risk_of = |name: &str| {
+            results
+                .iter()
+                .find(|result| result.path == root.join(name))
+                .map(|result| result.risk_level)
+        };
+        assert_eq!(risk_of("repo"), Some(RiskLevel::Critical));
+        assert_eq!(risk_of("secrets"), Some(RiskLevel::Critical));
+        assert!(risk_of("cache").is_some_and(|risk| risk < RiskLevel::Critical));
+
+        // Critical directories are never deleted
+        let report = LargestDirsPlugin::new().clean(results).unwrap();
+        assert_eq!(report.items_cleaned, 1);
+        assert_eq!(report.items_skipped, 2);
+        assert!(root.join("repo").join("src").join("main.rs").exists());
+        assert!(root.join("secrets").join(".env").exists());
+        assert!(!root.join("cache").exists());
+    }
+
+    #[test]
+    fn test_projects_with_lockfiles_are_listed_as_critical() {
+        let temp_dir = TempDir::new("largest_dirs_test").unwrap();
+        let root = dunce::canonicalize(temp_dir.path()).unwrap();
+        create_file(&root.join("proj").join("Cargo.lock"), 100);
+        create_file(&root.join("proj").join("target").join("app"), 3000);
+        create_file(&root.join("media").join("clip.mp4"), 1000);
+
+        let mut plugin = LargestDirsPlugin::new();
+        plugin.max_depth = 2;
+        let results = plugin.scan(&root).unwrap();
+        assert_eq!(
+            ranking(&results, &root),
+            [
+                ("proj".to_string(), 3100),
+                ("proj/target".to_string(), 3000),
+                ("media".to_string(), 1000),
+            ]
+        );
+        assert_eq!(results[0].risk_level, RiskLevel::Critical);
+        assert!(results[1].risk_level < RiskLevel::Critical);
+    }
+
+    #[test]
+    fn test_git_tracked_directories_are_critical() {
+        let temp_dir = TempDir::new("largest_dirs_test").unwrap();
+        let root = dunce::canonicalize(temp_dir.path()).unwrap();
+        let repo = git2::Repository::init(&root).unwrap();
+        create_file(&root.join("assets").join("logo.png"), 100);
+        create_file(&root.join("build").join("out.bin"), 100);
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("assets/logo.png")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Add logo", &tree, &[])
+            .unwrap();
+
+        let results = LargestDirsPlugin::new().scan(&root).unwrap();
+        let risk_of = |name: &str| {
+            results
+                .iter()
+                .find(|result| result.path == root.join(name))
+                .map(|result| result.risk_level)
+        };
+        assert_eq!(risk_of(".git"), None);
+        assert_eq!(risk_of("assets"), Some(RiskLevel::Critical));
+        assert!(risk_of("build").is_some_and(|risk| risk < RiskLevel::Critical));
+    }
+}
@@ -0,0 +1,150 @@
+/// Records what a cleanup is about to delete, so `swp undo-report` can show what was removed
+use super::{safety, utils, PluginError, RiskLevel, ScanResult};
+use crate::output;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use yansi::Color;
+
+/// A file that was about to be deleted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    pub risk_level: RiskLevel,
+}
+
+/// The files a cleanup was about to delete, written before the first one is removed
+///
+/// Every file the cleanup was asked to delete is listed, including those it
+/// then skipped or failed to delete, so the manifest is what could be gone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeletionManifest {
+    pub created: SystemTime,
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl DeletionManifest {
+    /// Create a manifest of the files that are about to be deleted
+    pub fn new(selected: &[ScanResult]) -> Self {
+        DeletionManifest {
+            created: SystemTime::now(),
+            entries: selected
+                .iter()
+                .map(|result| ManifestEntry {
+                    path: result.path.clone(),
+                    size: result.size,
+                    risk_level: result.risk_level,
+                })
+                .collect(),
+        }
+    }
+
+    /// Write the manifest to a JSON file, creating its directory if needed
+    pub fn save(&self, path: &Path) -> Result<(), PluginError> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| {
+            PluginError::Configuration(format!("Failed to serialize the manifest: {}", e))
+        })?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Read a manifest from a JSON file written by `save`
+    pub fn load(path: &Path) -> Result<DeletionManifest, PluginError> {
+        let json = fs::read_to_string(path)?;
+
+        serde_json::from_str(&json).map_err(|e| {
+            PluginError::Configuration(format!("Invalid manifest {}: {}", path.display(), e))
+        })
+    }
+
+    /// The combined size of the listed files
+    pub fn total_size(&self) -> u64 {
+        self.entries.iter().map(|entry| entry.size).sum()
+    }
+}
+
+/// Gets the path the manifest of the last cleanup is kept at, if the state directory is known
+pub fn default_path() -> Option<PathBuf> {
+    let dir = std::env::var_os("XDG_STATE_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| safety::home_dir().map(|home| home.join(".local").join("state")))?;
+
+    Some(dir.join("sweep").join("last-run.json"))
+}
+
+/// Prints the manifest of the last cleanup, for `swp undo-report`
+pub fn run_undo_report() -> Result<(), PluginError> {
+    let path = default_path().ok_or_else(|| {
+        PluginError::Configuration("Could not find the home directory".to_string())
+    })?;
+
+    if !path.is_file() {
+        output::println_info("No cleanup has been recorded yet");
+        return Ok(());
+    }
+
+    let manifest = DeletionManifest::load(&path)?;
+    let age = SystemTime::now()
+        .duration_since(manifest.created)
+        .unwrap_or_default();
+    output::println_info(format!(
+        "The last cleanup, {}, was going to delete {} files ({})",
+        utils::humanize_age(age),
+        manifest.entries.len(),
+        utils::format_size(manifest.total_size())
+    ));
+
+    for entry in &manifest.entries {
+        output::println(
+            format!("{:?}", entry.risk_level),
+            Color::Red,
+            format!(
+                "{} ({})",
+                entry.path.display(),
+                utils::format_size(entry.size)
+            ),
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::tests::risky_result;
+    use tempdir::TempDir;
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let temp_dir = TempDir::new("manifest_test").unwrap();
+        let manifest = DeletionManifest::new(&[
+            risky_result(temp_dir.path().join("a.dat"), 1024, RiskLevel::Safe),
+            risky_result(temp_dir.path().join("b.dat"), 2048, RiskLevel::High),
+        ]);
+        assert_eq!(manifest.total_size(), 3072);
+        assert_eq!(manifest.entries[1].risk_level, RiskLevel::High);
+
+        // The state directory does not have to exist yet
+        let file = temp_dir.path().join("state").join("last-run.json");
+        manifest.save(&file).unwrap();
+
+        assert_eq!(DeletionManifest::load(&file).unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_load_invalid_manifest() {
+        let temp_dir = TempDir::new("manifest_test").unwrap();
+        let file = temp_dir.path().join("last-run.json");
+        fs::write(&file, "{}").unwrap();
+
+        assert!(DeletionManifest::load(&file).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+/// Sniffs MIME types from the first bytes of files for `--detect-mime`
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use super::ScanResult;
+
+/// How many bytes of a file are read, enough for the `ustar` marker of tar archives
+const HEAD_LEN: usize = 262;
+
+/// A magic number at a fixed offset and the MIME type it identifies
+struct Signature {
+    offset: usize,
+    magic: &'static [u8],
+    mime_type: &'static str,
+}
+
+const fn signature(offset: usize, magic: &'static [u8], mime_type: &'static str) -> Signature {
+    Signature {
+        offset,
+        magic,
+        mime_type,
+    }
+}
+
+/// Known signatures, checked in order
+///
+/// Containers such as RIFF are listed by the bytes that tell their contents apart.
+const SIGNATURES: &[Signature] = &[
+    signature(0, b"\x89PNG\r\n\x1a\n", "image/png"),
+    signature(0, b"\xff\xd8\xff", "image/jpeg"),
+    signature(0, b"GIF87a", "image/gif"),
+    signature(0, b"GIF89a", "image/gif"),
+    signature(8, b"WEBP", "image/webp"),
+    signature(8, b"WAVE", "audio/wav"),
+    signature(8, b"AVI ", "video/x-msvideo"),
+    signature(4, b"ftypqt", "video/quicktime"),
+    signature(4, b"ftyp", "video/mp4"),
+    signature(0, b"\x1a\x45\xdf\xa3", "video/x-matroska"),
+    signature(0, b"ID3", "audio/mpeg"),
+    signature(0, b"fLaC", "audio/flac"),
+    signature(0, b"OggS", "audio/ogg"),
+    signature(0, b"%PDF-", "application/pdf"),
+    signature(0, b"PK\x03\x04", "application/zip"),
+    signature(0, b"PK\x05\x06", "application/zip"),
+    signature(0, b"\x1f\x8b", "application/gzip"),
+    signature(0, b"BZh", "application/x-bzip2"),
+    signature(0, b"\xfd7zXZ\x00", "application/x-xz"),
+    signature(0, b"\x28\xb5\x2f\xfd", "application/zstd"),
+    signature(0, b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+    signature(0, b"Rar!\x1a\x07", "application/vnd.rar"),
+    signature(257, b"ustar", "application/x-tar"),
+    signature(0, b"SQLite format 3\x00", "application/vnd.sqlite3"),
+    signature(0, b"\x7fELF", "application/x-executable"),
+    signature(0, b"MZ", "application/vnd.microsoft.portable-executable"),
+    signature(0, b"\x00asm", "application/wasm"),
+];
+
+/// Finds the MIME type identified by the first bytes of a file
+///
+/// # Returns
+/// `None` if no known signature matches
+pub fn sniff(head: &[u8]) -> Option<&'static str> {
+    SIGNATURES
+        .iter()
+        .find(|sig| {
+            head.get(sig.offset..sig.offset + sig.magic.len())
+                .is_some_and(|bytes| bytes == sig.magic)
+        })
+        .map(|sig| sig.mime_type)
+}
+
+/// Reads the head of a file and sniffs its MIME type
+///
+/// # Returns
+/// `None` if the file could not be read or no known signature matches
+pub fn detect(path: &Path) -> Option<&'static str> {
+    let mut head = Vec::with_capacity(HEAD_LEN);
+    File::open(path)
+        .ok()?
+        .take(HEAD_LEN as u64)
+        .read_to_end(&mut head)
+        .ok()?;
+    sniff(&head)
+}
+
+/// Sets the MIME type of every file among the results that does not have one yet
+///
+/// Only the head of each file is read, but that is still one read per
+/// file, so this is meant for the results that are about to be shown.
+pub fn detect_mime_types(results: &mut [ScanResult]) {
+    for result in results.iter_mut().filter(|r| r.mime_type.is_none()) {
+        if result.path.is_file() {
+            result.mime_type = detect(&result.path).map(str::to_string);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::tests::result;
+    use std::fs;
+    use tempdir::TempDir;
+
+    #[test]
+    fn test_png_and_gzip_are_detected() {
+        let temp_dir = TempDir::new("mime_test").unwrap();
+        let png = temp_dir.path().join("screenshot.dat");
+        let gzip = temp_dir.path().join("backup");
+        let text = temp_dir.path().join("notes.png");
+        fs::write(&png, b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR").unwrap();
+        fs::write(&gzip, b"\x1f\x8b\x08\x00\x00\x00\x00\x00").unwrap();
+        fs::write(&text, b"not an image").unwrap();
+
+        // The contents decide, not the extension
+        let mut results = vec![result(&png, 0), result(&gzip, 0), result(&text, 0)];
+        detect_mime_types(&mut results);
+        assert_eq!(results[0].mime_type.as_deref(), Some("image/png"));
+        assert_eq!(results[1].mime_type.as_deref(), Some("application/gzip"));
+        assert_eq!(results[2].mime_type, None);
+    }
+
+    #[test]
+    fn test_sniff_offsets() {
+        let mut tar = vec![0u8; 300];
+        tar[257..262].copy_from_slice(b"ustar");
+        assert_eq!(sniff(&tar), Some("application/x-tar"));
+        assert_eq!(sniff(b"RIFF\x24\x00\x00\x00WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff(b"\x00\x00\x00\x18ftypisom"), Some("video/mp4"));
+        assert_eq!(sniff(b"ustar"), None);
+        assert_eq!(sniff(b""), None);
+    }
+}
@@ -1,7 +1,11 @@
 use std::fmt::Debug;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
 
 use crate::settings::Settings;
+use progress::ScanProgress;
 
 /// Base trait for all plugins (language and feature plugins)
 #[allow(dead_code)]
@@ -28,8 +32,47 @@ pub trait FeaturePlugin: Plugin {
     /// Scan for items this plugin can clean
     fn scan(&self, path: &Path) -> Result<Vec<ScanResult>, PluginError>;
 
+    /// Create the progress bar a scan reports to
+    ///
+    /// Plugins that don't report progress get a hidden one, which still times the scan.
+    fn scan_progress(&self) -> ScanProgress {
+        ScanProgress::indeterminate_with_quiet(true)
+    }
+
+    /// Scan like [`FeaturePlugin::scan`], passing each result to `on_result` as soon as it is found
+    ///
+    /// Progress goes to `progress`, if the plugin reports any. Plugins that
+    /// only have their results at the end of a scan pass them all at once.
+    fn scan_streaming(
+        &self,
+        path: &Path,
+        progress: &ScanProgress,
+        on_result: &(dyn Fn(&ScanResult) + Sync),
+    ) -> Result<Vec<ScanResult>, PluginError> {
+        let _ = progress;
+        let results = self.scan(path)?;
+        results.iter().for_each(on_result);
+        Ok(results)
+    }
+
+    /// Classify exactly the given paths instead of scanning, for `--paths-from`
+    fn scan_listed(&self, paths: &[PathBuf]) -> Result<Vec<ScanResult>, PluginError> {
+        let _ = paths;
+        Err(PluginError::Configuration(format!(
+            "The {} plugin does not support --paths-from",
+            self.name()
+        )))
+    }
+
     /// Present interactive selection to user
-    fn interactive_select(&self, results: Vec<ScanResult>) -> Result<Vec<ScanResult>, PluginError>;
+    fn interactive_select(&self, results: Vec<ScanResult>) -> Result<Vec<ScanResult>, PluginError> {
+        if results.is_empty() {
+            return Ok(results);
+        }
+
+        let mut selector = ui::InteractiveSelector::new(results);
+        large_files::selection_from_ui(selector.run())
+    }
 
     /// Clean selected items
     fn clean(&self, selected: Vec<ScanResult>) -> Result<CleanupReport, PluginError>;
@@ -37,17 +80,23 @@ pub trait FeaturePlugin: Plugin {
 
 /// Result of a plugin scan
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ScanResult {
     pub path: std::path::PathBuf,
     pub size: u64,
     pub description: String,
     pub risk_level: RiskLevel,
+    /// When the item was last modified, if known
+    #[serde(default)]
+    pub last_modified: Option<SystemTime>,
+    /// The MIME type sniffed from the first bytes of the file, with `--detect-mime`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
 }
 
-/// Risk level for cleanup operations
+/// Risk level for cleanup operations, ordered from `Safe` (lowest) to `Critical` (highest)
 #[allow(dead_code)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum RiskLevel {
     Safe,
     Low,
@@ -56,13 +105,111 @@ pub enum RiskLevel {
     Critical,
 }
 
+impl RiskLevel {
+    /// Every risk level, from `Safe` to `Critical`
+    pub const ALL: [RiskLevel; 5] = [
+        RiskLevel::Safe,
+        RiskLevel::Low,
+        RiskLevel::Medium,
+        RiskLevel::High,
+        RiskLevel::Critical,
+    ];
+
+    /// Name of the risk level as flags accept it, e.g. "medium"
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskLevel::Safe => "safe",
+            RiskLevel::Low => "low",
+            RiskLevel::Medium => "medium",
+            RiskLevel::High => "high",
+            RiskLevel::Critical => "critical",
+        }
+    }
+}
+
+impl std::str::FromStr for RiskLevel {
+    type Err = String;
+
+    /// Parses a risk level name ignoring case, also accepting `med` and `crit`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "safe" => Ok(RiskLevel::Safe),
+            "low" => Ok(RiskLevel::Low),
+            "med" | "medium" => Ok(RiskLevel::Medium),
+            "high" => Ok(RiskLevel::High),
+            "crit" | "critical" => Ok(RiskLevel::Critical),
+            _ => Err(format!(
+                "Invalid risk level '{}', expected one of safe, low, medium, high or critical",
+                s
+            )),
+        }
+    }
+}
+
 /// Report after cleanup operations
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct CleanupReport {
     pub items_cleaned: usize,
+    /// Items that were protected or no longer existed at delete time
+    pub items_skipped: usize,
     pub space_freed: u64,
-    pub errors: Vec<String>,
+    /// Directories removed by `--prune-empty-dirs` because the cleanup left them empty
+    pub dirs_pruned: usize,
+    pub errors: Vec<CleanupError>,
+}
+
+/// A single item that could not be cleaned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanupError {
+    pub path: std::path::PathBuf,
+    pub message: String,
+}
+
+impl std::fmt::Display for CleanupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+impl CleanupReport {
+    /// Combine two reports, e.g. from different plugins, into one
+    pub fn merge(mut self, mut other: CleanupReport) -> CleanupReport {
+        self.items_cleaned += other.items_cleaned;
+        self.items_skipped += other.items_skipped;
+        self.space_freed += other.space_freed;
+        self.dirs_pruned += other.dirs_pruned;
+        self.errors.append(&mut other.errors);
+        self
+    }
+}
+
+impl std::fmt::Display for CleanupReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} items cleaned, {} freed",
+            self.items_cleaned,
+            utils::format_size(self.space_freed)
+        )?;
+
+        if self.items_skipped > 0 {
+            write!(f, ", {} skipped", self.items_skipped)?;
+        }
+
+        if self.dirs_pruned > 0 {
+            write!(f, ", {} empty directories pruned", self.dirs_pruned)?;
+        }
+
+        if !self.errors.is_empty() {
+            write!(f, ", {} errors", self.errors.len())?;
+            for error in &self.errors {
+                write!(f, "\n  {}", error)?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Plugin-specific errors
@@ -96,11 +243,28 @@ impl std::fmt::Display for PluginError {
 
 impl std::error::Error for PluginError {}
 
+pub mod dirs;
+pub mod dotnet;
+pub mod explain;
 pub mod filter;
+pub mod format;
+pub mod go;
+pub mod hook;
 pub mod large_files;
+pub mod largest_dirs;
+pub mod manifest;
+pub mod mime;
+pub mod mounts;
 pub mod progress;
+pub mod report;
+pub mod runner;
+pub mod safety;
+pub mod script;
+pub mod session;
+pub mod sort;
 pub mod ui;
 pub mod utils;
+pub mod watch;
 
 #[cfg(test)]
 mod integration_tests;
@@ -0,0 +1,274 @@
+/// Detects directories on network filesystems, which scans skip unless `--include-remote` is set
+use std::fs;
+use std::path::Path;
+
+use yansi::Color;
+
+use crate::output;
+
+/// Filesystem types that are reached over the network
+///
+/// FUSE filesystems are listed without their `fuse.` prefix.
+const REMOTE_FS_TYPES: [&str; 16] = [
+    "afs",
+    "ceph",
+    "cifs",
+    "coda",
+    "davfs",
+    "glusterfs",
+    "gpfs",
+    "lustre",
+    "ncpfs",
+    "nfs",
+    "nfs4",
+    "rclone",
+    "smb",
+    "smb2",
+    "smbfs",
+    "sshfs",
+];
+
+/// Check if a filesystem type name, as `mount` prints it, is a network filesystem
+pub fn is_remote_fs_type(fs_type: &str) -> bool {
+    let fs_type = fs_type.to_lowercase();
+    let fs_type = fs_type.strip_prefix("fuse.").unwrap_or(&fs_type);
+    REMOTE_FS_TYPES.contains(&fs_type)
+}
+
+/// Decides which directories of a scan to skip because they are on a network filesystem
+///
+/// The scanned root itself is never skipped, since naming a network path
+/// means it is meant to be scanned. Only directories below it that are on
+/// another filesystem are checked, and each filesystem only once.
+#[derive(Debug)]
+pub struct RemoteFilter {
+    include_remote: bool,
+    quiet: bool,
+    #[cfg(unix)]
+    root_device: Option<u64>,
+    /// The filesystems seen so far, with their type if they are remote
+    #[cfg(unix)]
+    checked: std::collections::HashMap<u64, Option<String>>,
+}
+
+impl RemoteFilter {
+    /// Create a filter for a scan of `root`
+    ///
+    /// # Arguments
+    /// * `root`           - The path the scan starts at
+    /// * `include_remote` - Scan network filesystems instead of skipping them
+    /// * `quiet`          - Skip without printing a note
+    pub fn new(root: &Path, include_remote: bool, quiet: bool) -> Self {
+        #[cfg(not(unix))]
+        let _ = root;
+
+        RemoteFilter {
+            include_remote,
+            quiet,
+            #[cfg(unix)]
+            root_device: fs::metadata(root)
+                .ok()
+                .map(|metadata| std::os::unix::fs::MetadataExt::dev(&metadata)),
+            #[cfg(unix)]
+            checked: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Check if the scan should not descend into a directory, printing a note if so
+    ///
+    /// # Arguments
+    /// * `path`     - The directory
+    /// * `metadata` - The metadata of the directory
+    pub fn skips(&mut self, path: &Path, metadata: &fs::Metadata) -> bool {
+        if self.include_remote || !metadata.is_dir() {
+            return false;
+        }
+
+        match self.remote_fs_type(path, metadata) {
+            Some(fs_type) => {
+                if !self.quiet {
+                    output::println(
+                        "Skipping",
+                        Color::Yellow,
+                        &format!(
+                            "{} is on a network filesystem ({}), use --include-remote to scan it",
+                            path.display(),
+                            fs_type
+                        ),
+                    );
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Check if a walk should not descend into an entry, printing a note if so
+    pub fn skips_entry(&mut self, entry: &walkdir::DirEntry) -> bool {
+        entry.file_type().is_dir()
+            && entry
+                .metadata()
+                .is_ok_and(|metadata| self.skips(entry.path(), &metadata))
+    }
+
+    /// The type of the filesystem a directory is on, if it is a network filesystem
+    #[cfg(unix)]
+    fn remote_fs_type(&mut self, path: &Path, metadata: &fs::Metadata) -> Option<String> {
+        let device = std::os::unix::fs::MetadataExt::dev(metadata);
+        if Some(device) == self.root_device {
+            return None;
+        }
+
+        self.checked
+            .entry(device)
+            .or_insert_with(|| filesystem_type(path).filter(|fs_type| is_remote_fs_type(fs_type)))
+            .clone()
+    }
+
+    /// The type of the filesystem a directory is on, if it is a network filesystem
+    ///
+    /// Another volume can only start at a mount point, which is a reparse point.
+    #[cfg(windows)]
+    fn remote_fs_type(&mut self, path: &Path, metadata: &fs::Metadata) -> Option<String> {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+
+        if metadata.file_attributes() & FILE_ATTRIBUTE_REPARSE_POINT == 0 {
+            return None;
+        }
+        is_network_drive(path).then(|| "network drive".to_string())
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn remote_fs_type(&mut self, _path: &Path, _metadata: &fs::Metadata) -> Option<String> {
+        None
+    }
+}
+
+/// The name of the filesystem type a path is on, from `statfs`
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "dragonfly"
+))]
+fn filesystem_type(path: &Path) -> Option<String> {
+    let stat = statfs(path)?;
+    // SAFETY: the kernel fills the name with a NUL terminated string
+    let name = unsafe { std::ffi::CStr::from_ptr(stat.f_fstypename.as_ptr()) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+/// The name of the filesystem type a path is on, from the magic number `statfs` reports
+///
+/// FUSE filesystems share one magic number, so `sshfs` and the like are not recognized.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn filesystem_type(path: &Path) -> Option<String> {
+    // The width of `f_type` differs between architectures
+    #[allow(clippy::unnecessary_cast)]
+    let magic = statfs(path)?.f_type as u32;
+    let name = match magic {
+        0x6969 => "nfs",
+        0x517b => "smb",
+        0xff53_4d42 => "cifs",
+        0xfe53_4d42 => "smb2",
+        0x5346_414f => "afs",
+        0x7375_7245 => "coda",
+        0x564c => "ncpfs",
+        0x00c3_6400 => "ceph",
+        0x4750_4653 => "gpfs",
+        0x0bd0_0bd0 => "lustre",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "dragonfly"
+    ))
+))]
+fn filesystem_type(_path: &Path) -> Option<String> {
+    None
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "dragonfly"
+))]
+fn statfs(path: &Path) -> Option<libc::statfs> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat = std::mem::MaybeUninit::<libc::statfs>::uninit();
+    // SAFETY: the path is NUL terminated and `stat` is only read if the call succeeded
+    if unsafe { libc::statfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    Some(unsafe { stat.assume_init() })
+}
+
+/// Check if a directory is the root of a network drive or a mounted network share
+#[cfg(windows)]
+fn is_network_drive(path: &Path) -> bool {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::um::fileapi::GetDriveTypeW;
+    use winapi::um::winbase::DRIVE_REMOTE;
+
+    // The root has to end with a backslash
+    let mut root: Vec<u16> = path.as_os_str().encode_wide().collect();
+    if root.last() != Some(&(b'\\' as u16)) {
+        root.push(b'\\' as u16);
+    }
+    root.push(0);
+    // SAFETY: the path is NUL terminated
+    unsafe { GetDriveTypeW(root.as_ptr()) == DRIVE_REMOTE }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn test_remote_filesystem_types() {
+        for remote in ["nfs", "nfs4", "cifs", "smbfs", "NFS", "fuse.sshfs", "afs"] {
+            assert!(is_remote_fs_type(remote), "{}", remote);
+        }
+        for local in [
+            "ext4",
+            "apfs",
+            "btrfs",
+            "tmpfs",
+            "ntfs",
+            "fuse",
+            "fuse.gocryptfs",
+            "",
+        ] {
+            assert!(!is_remote_fs_type(local), "{}", local);
+        }
+    }
+
+    #[test]
+    fn test_local_directories_are_not_skipped() {
+        let temp_dir = TempDir::new("mounts_test").unwrap();
+        let dir = temp_dir.path().join("dir");
+        fs::create_dir(&dir).unwrap();
+
+        let mut filter = RemoteFilter::new(temp_dir.path(), false, true);
+        assert!(!filter.skips(&dir, &fs::metadata(&dir).unwrap()));
+    }
+}
@@ -1,34 +1,188 @@
 /// Progress indicator for long-running operations
-use indicatif::{ProgressBar, ProgressStyle};
+use super::utils::{format_duration, format_size};
+use super::{PluginError, ScanResult};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::io::{self, IsTerminal};
 use std::path::Path;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::time::Duration;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Receives progress events while a scan is running
+///
+/// Implement this to show scan progress in your own way. The callbacks are
+/// invoked from multiple threads at once.
+pub trait ScanObserver: Send + Sync {
+    /// Called once the number of entries to scan is known
+    fn on_start(&self, _total: u64) {}
+
+    /// Called for every directory entry that was scanned
+    fn on_scanned(&self, path: &Path);
+
+    /// Called for every large file that was found
+    fn on_found(&self);
+
+    /// Called with every large file that was found, as it will be reported
+    ///
+    /// Unlike the other callbacks this is called from one thread at a time,
+    /// in the order the files were found, before they are sorted.
+    fn on_result(&self, _result: &ScanResult) {}
+
+    /// Check if [`ScanObserver::on_result`] does anything, so results are only built if needed
+    fn wants_results(&self) -> bool {
+        true
+    }
+
+    /// Called when the scan is complete
+    fn on_finish(&self);
+}
+
+/// The default template of the scan progress bar, once the number of entries is known
+///
+/// The total is known after the entries are collected, so the bar can estimate
+/// how long the rest of the scan takes.
+pub const DEFAULT_BAR_TEMPLATE: &str = "[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} files \
+     ({per_sec}, {eta_precise} left) | Found: {msg}";
+
+/// The default template of the scan spinner, while the number of entries is unknown
+///
+/// Without a total there is nothing to estimate the remaining time from, so it has no ETA.
+pub const DEFAULT_SPINNER_TEMPLATE: &str =
+    "[{elapsed_precise}] {spinner:.cyan} {pos} files ({per_sec}) | Found: {msg}";
+
+/// The indicatif templates a [`ScanProgress`] is drawn with
+///
+/// `{msg}` is the number of large files found so far and `{prefix}` the file
+/// being scanned. See indicatif's `ProgressStyle` for the other placeholders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanTemplates {
+    /// Used once the number of entries to scan is known
+    pub bar: String,
+    /// Used while the number of entries is unknown
+    pub spinner: String,
+}
+
+impl Default for ScanTemplates {
+    fn default() -> Self {
+        ScanTemplates {
+            bar: DEFAULT_BAR_TEMPLATE.to_string(),
+            spinner: DEFAULT_SPINNER_TEMPLATE.to_string(),
+        }
+    }
+}
 
 /// Progress tracker for file scanning operations
 pub struct ScanProgress {
     bar: ProgressBar,
+    /// The style the spinner switches to once the total is known
+    bar_style: ProgressStyle,
     found_count: AtomicUsize,
     scanned_count: AtomicUsize,
+    quiet: bool,
+    started: Instant,
 }
 
 impl ScanProgress {
     /// Create a new progress bar for scanning
+    ///
+    /// The bar is hidden automatically when stderr is not a terminal.
     pub fn new(estimated_files: u64) -> Self {
-        let bar = ProgressBar::new(estimated_files);
-        bar.set_style(
-            ProgressStyle::default_bar()
-                .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} files | Found: {msg}")
-                .expect("Invalid progress bar template")
-                .progress_chars("##-"),
-        );
+        Self::with_quiet(estimated_files, !io::stderr().is_terminal())
+    }
 
-        bar.enable_steady_tick(Duration::from_millis(100));
+    /// Create a new progress bar for scanning that never draws if `quiet` is set
+    pub fn with_quiet(estimated_files: u64, quiet: bool) -> Self {
+        Self::build(Some(estimated_files), quiet)
+    }
 
-        Self {
+    /// Create a spinner with a running count for when the number of entries is unknown
+    ///
+    /// The spinner turns into a regular bar once a total is reported through
+    /// [`ScanObserver::on_start`].
+    pub fn indeterminate() -> Self {
+        Self::indeterminate_with_quiet(!io::stderr().is_terminal())
+    }
+
+    /// Create a spinner for an unknown number of entries that never draws if `quiet` is set
+    pub fn indeterminate_with_quiet(quiet: bool) -> Self {
+        Self::build(None, quiet)
+    }
+
+    /// Create a progress bar drawn with custom templates, e.g. by programs embedding the scanner
+    ///
+    /// # Arguments
+    /// * `estimated_files` - The number of entries to scan, or `None` to start with a spinner
+    /// * `quiet`           - Never draw the bar
+    /// * `templates`       - The templates to draw it with
+    ///
+    /// # Errors
+    /// Returns an error if a template is invalid.
+    pub fn with_templates(
+        estimated_files: Option<u64>,
+        quiet: bool,
+        templates: &ScanTemplates,
+    ) -> Result<Self, PluginError> {
+        let style = |style: ProgressStyle, template: &str| {
+            style.template(template).map_err(|e| {
+                PluginError::Configuration(format!(
+                    "Invalid progress bar template '{}': {}",
+                    template, e
+                ))
+            })
+        };
+        let bar_style = style(ProgressStyle::default_bar(), &templates.bar)?.progress_chars("##-");
+        let spinner_style = style(ProgressStyle::default_spinner(), &templates.spinner)?;
+
+        let target = if quiet {
+            ProgressDrawTarget::hidden()
+        } else {
+            ProgressDrawTarget::stderr()
+        };
+
+        let bar = match estimated_files {
+            Some(total) => {
+                ProgressBar::with_draw_target(Some(total), target).with_style(bar_style.clone())
+            }
+            None => ProgressBar::with_draw_target(None, target).with_style(spinner_style),
+        };
+
+        if !quiet {
+            bar.enable_steady_tick(Duration::from_millis(100));
+        }
+
+        Ok(Self {
             bar,
+            bar_style,
             found_count: AtomicUsize::new(0),
             scanned_count: AtomicUsize::new(0),
-        }
+            quiet,
+            started: Instant::now(),
+        })
+    }
+
+    fn build(estimated_files: Option<u64>, quiet: bool) -> Self {
+        Self::with_templates(estimated_files, quiet, &ScanTemplates::default())
+            .expect("Invalid progress bar template")
+    }
+
+    /// Check if the total number of entries is unknown
+    pub fn is_indeterminate(&self) -> bool {
+        self.bar.length().is_none()
+    }
+
+    /// Check if the progress bar is hidden
+    pub fn is_quiet(&self) -> bool {
+        self.quiet
+    }
+
+    /// Get the time since the scan started
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Get the number of entries scanned per second so far
+    pub fn throughput(&self) -> f64 {
+        let scanned = self.scanned_count.load(Ordering::SeqCst);
+        files_per_second(scanned, self.elapsed())
     }
 
     /// Update progress with current file being scanned
@@ -56,9 +210,14 @@ impl ScanProgress {
         let found = self.found_count.load(Ordering::SeqCst);
         let scanned = self.scanned_count.load(Ordering::SeqCst);
 
+        let elapsed = self.elapsed();
+
         self.bar.finish_with_message(format!(
-            "Complete! Found {} large files in {} files scanned",
-            found, scanned
+            "Complete! Found {} large files in {} files scanned in {} ({:.0} files/s)",
+            found,
+            scanned,
+            format_duration(elapsed),
+            files_per_second(scanned, elapsed)
         ));
     }
 
@@ -69,6 +228,92 @@ impl ScanProgress {
     }
 }
 
+/// Compute a rate, or zero if no time has passed
+fn files_per_second(count: usize, elapsed: Duration) -> f64 {
+    let seconds = elapsed.as_secs_f64();
+    if seconds > 0.0 {
+        count as f64 / seconds
+    } else {
+        0.0
+    }
+}
+
+impl ScanObserver for ScanProgress {
+    fn on_start(&self, total: u64) {
+        if self.is_indeterminate() {
+            self.bar.set_style(self.bar_style.clone());
+        }
+        self.bar.set_length(total);
+    }
+
+    fn on_scanned(&self, path: &Path) {
+        self.update(path);
+    }
+
+    fn on_found(&self) {
+        self.found_file();
+    }
+
+    fn wants_results(&self) -> bool {
+        false
+    }
+
+    fn on_finish(&self) {
+        self.finish();
+    }
+}
+
+/// Passes every result of a scan to a function as soon as it is found
+///
+/// The progress still goes to another observer, if one is given.
+pub struct ResultStream<'a> {
+    progress: Option<&'a dyn ScanObserver>,
+    on_result: &'a (dyn Fn(&ScanResult) + Sync),
+}
+
+impl<'a> ResultStream<'a> {
+    /// Create a stream that calls `on_result` for every result and reports progress to `progress`
+    pub fn new(
+        progress: Option<&'a dyn ScanObserver>,
+        on_result: &'a (dyn Fn(&ScanResult) + Sync),
+    ) -> Self {
+        ResultStream {
+            progress,
+            on_result,
+        }
+    }
+}
+
+impl ScanObserver for ResultStream<'_> {
+    fn on_start(&self, total: u64) {
+        if let Some(progress) = self.progress {
+            progress.on_start(total);
+        }
+    }
+
+    fn on_scanned(&self, path: &Path) {
+        if let Some(progress) = self.progress {
+            progress.on_scanned(path);
+        }
+    }
+
+    fn on_found(&self) {
+        if let Some(progress) = self.progress {
+            progress.on_found();
+        }
+    }
+
+    fn on_result(&self, result: &ScanResult) {
+        (self.on_result)(result);
+    }
+
+    fn on_finish(&self) {
+        if let Some(progress) = self.progress {
+            progress.on_finish();
+        }
+    }
+}
+
 impl Drop for ScanProgress {
     fn drop(&mut self) {
         if !self.bar.is_finished() {
@@ -78,18 +323,28 @@ impl Drop for ScanProgress {
 }
 
 /// Progress tracker for cleanup operations
-#[allow(dead_code)]
 pub struct CleanupProgress {
     bar: ProgressBar,
-    space_freed: AtomicUsize,
+    space_freed: AtomicU64,
 }
 
 impl CleanupProgress {
     /// Create a new progress bar for cleanup
-    #[allow(dead_code)]
+    ///
+    /// The bar is hidden automatically when stderr is not a terminal.
     pub fn new(total_files: u64) -> Self {
-        let bar = ProgressBar::new(total_files);
-        bar.set_style(
+        Self::with_quiet(total_files, !io::stderr().is_terminal())
+    }
+
+    /// Create a new progress bar for cleanup that never draws if `quiet` is set
+    pub fn with_quiet(total_files: u64, quiet: bool) -> Self {
+        let target = if quiet {
+            ProgressDrawTarget::hidden()
+        } else {
+            ProgressDrawTarget::stderr()
+        };
+
+        let bar = ProgressBar::with_draw_target(Some(total_files), target).with_style(
             ProgressStyle::default_bar()
                 .template("[{elapsed_precise}] {bar:40.green/red} {pos}/{len} | Space freed: {msg}")
                 .expect("Invalid progress bar template")
@@ -98,29 +353,57 @@ impl CleanupProgress {
 
         Self {
             bar,
-            space_freed: AtomicUsize::new(0),
+            space_freed: AtomicU64::new(0),
         }
     }
 
     /// Update progress when a file is deleted
-    #[allow(dead_code)]
     pub fn file_deleted(&self, path: &Path, size: u64) {
-        let freed = self.space_freed.fetch_add(size as usize, Ordering::SeqCst) + size as usize;
+        let freed = self.space_freed.fetch_add(size, Ordering::SeqCst) + size;
 
         self.bar.inc(1);
-        self.bar.set_message(format_size(freed as u64));
+        self.bar.set_message(format_size(freed));
+
+        if let Some(file_name) = path.file_name() {
+            self.bar
+                .set_prefix(format!("Deleted: {}", file_name.to_string_lossy()));
+        }
+    }
+
+    /// Update progress when a file inside a directory that is being deleted is removed
+    ///
+    /// The directory only counts as deleted once [`CleanupProgress::dir_finished`] is called.
+    pub fn part_deleted(&self, path: &Path, size: u64) {
+        let freed = self.space_freed.fetch_add(size, Ordering::SeqCst) + size;
 
+        self.bar.set_message(format_size(freed));
         if let Some(file_name) = path.file_name() {
             self.bar
                 .set_prefix(format!("Deleted: {}", file_name.to_string_lossy()));
         }
     }
 
+    /// Update progress when the deletion of a directory is over, whether or not all of it went
+    pub fn dir_finished(&self) {
+        self.bar.inc(1);
+    }
+
+    /// Get the space freed by the deletions so far, in bytes
+    pub fn space_freed(&self) -> u64 {
+        self.space_freed.load(Ordering::SeqCst)
+    }
+
+    /// Hides the progress bar while `f` prints, so the output doesn't garble it
+    pub fn suspend<F: FnOnce() -> R, R>(&self, f: F) -> R {
+        self.bar.suspend(f)
+    }
+
     /// Mark cleanup as complete
     pub fn finish(&self) {
-        let freed = self.space_freed.load(Ordering::SeqCst);
-        self.bar
-            .finish_with_message(format!("Complete! Freed {}", format_size(freed as u64)));
+        self.bar.finish_with_message(format!(
+            "Complete! Freed {}",
+            format_size(self.space_freed())
+        ));
     }
 }
 
@@ -132,21 +415,44 @@ impl Drop for CleanupProgress {
     }
 }
 
-/// Format bytes as human-readable size
-fn format_size(size: u64) -> String {
-    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
-    let mut size = size as f64;
-    let mut unit_index = 0;
+/// Spinner shown while git repositories and ignore rules are indexed, before a scan starts
+pub struct IndexingProgress {
+    bar: ProgressBar,
+}
+
+impl IndexingProgress {
+    /// Start the spinner, which never draws if `quiet` is set or stderr is not a terminal
+    pub fn new(quiet: bool) -> Self {
+        let target = if quiet || !io::stderr().is_terminal() {
+            ProgressDrawTarget::hidden()
+        } else {
+            ProgressDrawTarget::stderr()
+        };
+
+        let bar = ProgressBar::with_draw_target(None, target).with_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.cyan} {msg}")
+                .expect("Invalid progress bar template"),
+        );
+        bar.set_message("Indexing git repositories and ignore rules…");
+        if !bar.is_hidden() {
+            bar.enable_steady_tick(Duration::from_millis(100));
+        }
 
-    while size >= 1024.0 && unit_index < UNITS.len() - 1 {
-        size /= 1024.0;
-        unit_index += 1;
+        Self { bar }
     }
 
-    if unit_index == 0 {
-        format!("{} {}", size as u64, UNITS[unit_index])
-    } else {
-        format!("{:.2} {}", size, UNITS[unit_index])
+    /// Clear the spinner, since the scan progress takes its place
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+    }
+}
+
+impl Drop for IndexingProgress {
+    fn drop(&mut self) {
+        if !self.bar.is_finished() {
+            self.finish();
+        }
     }
 }
 
@@ -157,6 +463,7 @@ mod tests {
     use std::thread;
     use std::time::Duration;
 
+    /// The progress bars must show sizes exactly like the file list
     #[test]
     fn test_format_size() {
         assert_eq!(format_size(0), "0 B");
@@ -164,6 +471,7 @@ mod tests {
         assert_eq!(format_size(1024), "1.00 KB");
         assert_eq!(format_size(1024 * 1024), "1.00 MB");
         assert_eq!(format_size(1536 * 1024), "1.50 MB");
+        assert_eq!(format_size(150 * 1024 * 1024), "150 MB");
         assert_eq!(format_size(1024 * 1024 * 1024), "1.00 GB");
     }
 
@@ -187,6 +495,106 @@ mod tests {
         progress.finish();
     }
 
+    #[test]
+    fn test_scan_progress_timing() {
+        let progress = ScanProgress::with_quiet(100, true);
+        let test_path = PathBuf::from("/test/file.txt");
+
+        for _ in 0..20 {
+            progress.update(&test_path);
+        }
+        thread::sleep(Duration::from_millis(50));
+
+        let elapsed = progress.elapsed();
+        assert!(elapsed >= Duration::from_millis(50));
+
+        // At most 20 files in the time measured above
+        let throughput = progress.throughput();
+        assert!(throughput > 0.0);
+        assert!(throughput <= 20.0 / elapsed.as_secs_f64());
+
+        progress.finish();
+    }
+
+    #[test]
+    fn test_files_per_second() {
+        assert_eq!(files_per_second(100, Duration::from_secs(4)), 25.0);
+        assert_eq!(files_per_second(100, Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn test_indeterminate_scan_progress() {
+        let progress = ScanProgress::indeterminate_with_quiet(true);
+        let test_path = PathBuf::from("/test/file.txt");
+
+        for _ in 0..5 {
+            progress.update(&test_path);
+        }
+
+        assert!(progress.is_indeterminate());
+        assert_eq!(progress.bar.length(), None);
+        assert_eq!(progress.bar.position(), 5);
+
+        // Reporting a total turns the spinner into a bar
+        progress.on_start(10);
+        assert!(!progress.is_indeterminate());
+        assert_eq!(progress.bar.length(), Some(10));
+
+        progress.finish();
+    }
+
+    #[test]
+    fn test_default_bar_estimates_the_remaining_time() {
+        assert!(DEFAULT_BAR_TEMPLATE.contains("{eta_precise}"));
+        assert!(!DEFAULT_SPINNER_TEMPLATE.contains("{eta"));
+        assert!(ProgressStyle::with_template(DEFAULT_BAR_TEMPLATE).is_ok());
+        assert!(ProgressStyle::with_template(DEFAULT_SPINNER_TEMPLATE).is_ok());
+
+        let progress = ScanProgress::with_quiet(50, true);
+        for i in 0..50 {
+            progress.update(&PathBuf::from(format!("/test/{}.txt", i)));
+        }
+        assert_eq!(progress.bar.position(), 50);
+        progress.finish();
+    }
+
+    #[test]
+    fn test_scan_progress_with_custom_templates() {
+        let templates = ScanTemplates {
+            bar: "{wide_bar} {pos}/{len} at {per_sec}, {eta} left: {msg}".to_string(),
+            spinner: "{spinner} {prefix} {per_sec}".to_string(),
+        };
+        let progress = ScanProgress::with_templates(None, true, &templates).unwrap();
+        progress.update(&PathBuf::from("/test/file.txt"));
+        progress.on_start(10);
+        assert_eq!(progress.bar.length(), Some(10));
+        progress.finish();
+
+        assert!(ScanProgress::with_templates(Some(10), true, &ScanTemplates::default()).is_ok());
+
+        let invalid = ScanTemplates {
+            bar: "{pos:x}".to_string(),
+            ..ScanTemplates::default()
+        };
+        let err = ScanProgress::with_templates(Some(10), true, &invalid)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Invalid progress bar template"));
+    }
+
+    #[test]
+    fn test_quiet_scan_progress() {
+        let progress = ScanProgress::with_quiet(100, true);
+
+        progress.update(&PathBuf::from("/test/file.txt"));
+        progress.found_file();
+        progress.finish();
+
+        assert!(progress.is_quiet());
+        assert!(progress.bar.is_hidden());
+        assert_eq!(progress.scanned_count.load(Ordering::SeqCst), 1);
+    }
+
     #[test]
     fn test_cleanup_progress() {
         let progress = CleanupProgress::new(5);
@@ -196,11 +604,56 @@ mod tests {
         progress.file_deleted(&test_path, 1024 * 1024 * 100); // 100MB
         progress.file_deleted(&test_path, 1024 * 1024 * 50); // 50MB
 
-        assert_eq!(
-            progress.space_freed.load(Ordering::SeqCst),
-            1024 * 1024 * 150
-        );
+        assert_eq!(progress.space_freed(), 1024 * 1024 * 150);
 
         progress.finish();
     }
+
+    #[test]
+    fn test_cleanup_progress_adds_up_deletions() {
+        let sizes = [4096, 0, 5 * 1024 * 1024 * 1024, 1, 777];
+        let progress = CleanupProgress::with_quiet(sizes.len() as u64, true);
+
+        for (i, size) in sizes.iter().enumerate() {
+            progress.file_deleted(&PathBuf::from(format!("/data/{}.bin", i)), *size);
+        }
+        progress.finish();
+
+        assert!(progress.bar.is_hidden());
+        assert!(progress.bar.is_finished());
+        assert_eq!(progress.bar.position(), sizes.len() as u64);
+        assert_eq!(progress.space_freed(), sizes.iter().sum::<u64>());
+    }
+
+    #[test]
+    fn test_only_result_streams_want_results() {
+        let progress = ScanProgress::indeterminate_with_quiet(true);
+        assert!(!progress.wants_results());
+
+        let on_result = |_: &ScanResult| {};
+        assert!(ResultStream::new(Some(&progress), &on_result).wants_results());
+    }
+
+    #[test]
+    fn test_indexing_progress_is_hidden_when_quiet() {
+        let indexing = IndexingProgress::new(true);
+        assert!(indexing.bar.is_hidden());
+
+        indexing.finish();
+        assert!(indexing.bar.is_finished());
+    }
+
+    #[test]
+    fn test_cleanup_progress_counts_a_directory_once() {
+        let progress = CleanupProgress::with_quiet(2, true);
+
+        progress.part_deleted(Path::new("/data/target/a.o"), 100);
+        progress.part_deleted(Path::new("/data/target/b.o"), 200);
+        assert_eq!(progress.bar.position(), 0);
+        progress.dir_finished();
+        progress.file_deleted(Path::new("/data/core.dump"), 50);
+
+        assert_eq!(progress.bar.position(), 2);
+        assert_eq!(progress.space_freed(), 350);
+    }
 }
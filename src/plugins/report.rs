@@ -0,0 +1,335 @@
+/// Summaries of scan results for the `--stats`, `--stats-by-dir` and `--summary-only` overviews
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use yansi::Color;
+
+use super::filter::{FileType, SmartFilter};
+use super::{RiskLevel, ScanResult};
+use crate::output;
+
+/// Number and total size of the scan results of one file type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeStats {
+    pub file_type: FileType,
+    pub count: usize,
+    pub total_size: u64,
+}
+
+/// Number and total size of the scan results of one risk level
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RiskStats {
+    pub risk_level: RiskLevel,
+    pub count: usize,
+    pub total_size: u64,
+}
+
+/// Totals of a scan, overall and per risk level
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Summary {
+    pub count: usize,
+    pub total_size: u64,
+    /// Only the risk levels that were found, from safe to critical
+    pub by_risk: Vec<RiskStats>,
+}
+
+/// Adds up the scan results, overall and per risk level
+///
+/// # Arguments
+/// * `results` - The scan results to summarise
+pub fn summarize(results: &[ScanResult]) -> Summary {
+    let mut by_risk: Vec<RiskStats> = Vec::new();
+
+    for result in results {
+        match by_risk
+            .iter_mut()
+            .find(|s| s.risk_level == result.risk_level)
+        {
+            Some(entry) => {
+                entry.count += 1;
+                entry.total_size += result.size;
+            }
+            None => by_risk.push(RiskStats {
+                risk_level: result.risk_level,
+                count: 1,
+                total_size: result.size,
+            }),
+        }
+    }
+
+    by_risk.sort_by_key(|s| s.risk_level);
+    Summary {
+        count: results.len(),
+        total_size: results.iter().map(|r| r.size).sum(),
+        by_risk,
+    }
+}
+
+/// Prints how much space could be freed, overall and per risk level
+///
+/// # Arguments
+/// * `results`     - The scan results to summarise
+/// * `format_size` - Formats a size in bytes for display
+pub fn print_summary(results: &[ScanResult], format_size: fn(u64) -> String) {
+    let summary = summarize(results);
+    output::println(
+        "Summary",
+        Color::Blue,
+        &format!(
+            "You could free {} across {} files",
+            format_size(summary.total_size),
+            summary.count
+        ),
+    );
+
+    for stats in summary.by_risk {
+        output::println_info(format!(
+            "{:<16}{:>6} files {:>10}",
+            format!("{:?}", stats.risk_level),
+            stats.count,
+            format_size(stats.total_size)
+        ));
+    }
+}
+
+/// Groups scan results by their file type
+///
+/// # Arguments
+/// * `results` - The scan results to group
+///
+/// # Returns
+/// The count and total size per file type, largest total first
+pub fn by_file_type(results: &[ScanResult]) -> Vec<TypeStats> {
+    let filter = SmartFilter::new();
+    let mut stats: Vec<TypeStats> = Vec::new();
+
+    for result in results {
+        let file_type = file_type_of(&filter, result);
+
+        match stats.iter_mut().find(|s| s.file_type == file_type) {
+            Some(entry) => {
+                entry.count += 1;
+                entry.total_size += result.size;
+            }
+            None => stats.push(TypeStats {
+                file_type,
+                count: 1,
+                total_size: result.size,
+            }),
+        }
+    }
+
+    stats.sort_by_key(|s| std::cmp::Reverse(s.total_size));
+    stats
+}
+
+/// Prints a table with the count and total size per file type
+///
+/// # Arguments
+/// * `results`     - The scan results to summarise
+/// * `format_size` - Formats a size in bytes for display
+pub fn print_stats(results: &[ScanResult], format_size: fn(u64) -> String) {
+    output::println("Stats", Color::Blue, "Results by file type");
+
+    for stats in by_file_type(results) {
+        output::println_info(format!(
+            "{:<16}{:>6} files {:>10}",
+            stats.file_type,
+            stats.count,
+            format_size(stats.total_size)
+        ));
+    }
+}
+
+/// Groups scan results by the directory directly under the scan root they are in
+///
+/// Files right in the root are attributed to the root itself, and results
+/// outside of it are left out.
+///
+/// # Arguments
+/// * `root`    - The scan path
+/// * `results` - The scan results to group
+///
+/// # Returns
+/// The directory, total size and count of each group, largest total first
+pub fn by_top_level(root: &Path, results: &[ScanResult]) -> Vec<(PathBuf, u64, usize)> {
+    let mut stats: Vec<(PathBuf, u64, usize)> = Vec::new();
+
+    for result in results {
+        let Ok(relative) = result.path.strip_prefix(root) else {
+            continue;
+        };
+        let mut components = relative.components();
+        let dir = match (components.next(), components.next()) {
+            (Some(first), Some(_)) => root.join(first),
+            _ => root.to_path_buf(),
+        };
+
+        match stats.iter_mut().find(|(path, _, _)| *path == dir) {
+            Some((_, total_size, count)) => {
+                *total_size += result.size;
+                *count += 1;
+            }
+            None => stats.push((dir, result.size, 1)),
+        }
+    }
+
+    stats.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    stats
+}
+
+/// Prints a table with the count and total size per top-level directory of every scan path
+///
+/// # Arguments
+/// * `roots`       - The scan paths
+/// * `results`     - The scan results to summarise
+/// * `format_size` - Formats a size in bytes for display
+pub fn print_stats_by_dir(
+    roots: &[PathBuf],
+    results: &[ScanResult],
+    format_size: fn(u64) -> String,
+) {
+    for root in roots {
+        output::println(
+            "Stats",
+            Color::Blue,
+            &format!("Results by directory in {}", root.display()),
+        );
+
+        for (dir, total_size, count) in by_top_level(root, results) {
+            output::println_info(format!(
+                "{:>10} {:>6} files  {}",
+                format_size(total_size),
+                count,
+                dir.display()
+            ));
+        }
+    }
+}
+
+/// Detects the file type of a scan result
+fn file_type_of(filter: &SmartFilter, result: &ScanResult) -> FileType {
+    let is_link = fs::symlink_metadata(&result.path)
+        .map(|metadata| metadata.file_type().is_symlink())
+        .unwrap_or(false);
+
+    if is_link && !result.path.exists() {
+        FileType::BrokenSymlink
+    } else {
+        filter.detect_file_type(&result.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::tests::{result, risky_result};
+
+    #[test]
+    fn test_summarize() {
+        let results = vec![
+            risky_result("/data/a.iso", 5000, RiskLevel::High),
+            risky_result("/data/b.log", 100, RiskLevel::Safe),
+            risky_result("/data/c.tmp", 250, RiskLevel::Safe),
+            risky_result("/data/d.bin", 1000, RiskLevel::Medium),
+            risky_result("/data/e.iso", 3000, RiskLevel::High),
+        ];
+
+        let summary = summarize(&results);
+        assert_eq!(summary.count, 5);
+        assert_eq!(summary.total_size, 9350);
+        assert_eq!(
+            summary.by_risk,
+            vec![
+                RiskStats {
+                    risk_level: RiskLevel::Safe,
+                    count: 2,
+                    total_size: 350,
+                },
+                RiskStats {
+                    risk_level: RiskLevel::Medium,
+                    count: 1,
+                    total_size: 1000,
+                },
+                RiskStats {
+                    risk_level: RiskLevel::High,
+                    count: 2,
+                    total_size: 8000,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_summarize_empty() {
+        let summary = summarize(&[]);
+        assert_eq!(summary.count, 0);
+        assert_eq!(summary.total_size, 0);
+        assert!(summary.by_risk.is_empty());
+    }
+
+    #[test]
+    fn test_by_file_type() {
+        let results = vec![
+            result("/data/app.log", 3000),
+            result("/data/backup.zip", 1000),
+            result("/data/server.log", 2000),
+            result("/data/movie.mkv", 4500),
+            result("/data/blob", 10),
+        ];
+
+        let stats = by_file_type(&results);
+        assert_eq!(
+            stats,
+            vec![
+                TypeStats {
+                    file_type: FileType::Log,
+                    count: 2,
+                    total_size: 5000,
+                },
+                TypeStats {
+                    file_type: FileType::Media,
+                    count: 1,
+                    total_size: 4500,
+                },
+                TypeStats {
+                    file_type: FileType::Archive,
+                    count: 1,
+                    total_size: 1000,
+                },
+                TypeStats {
+                    file_type: FileType::Unknown,
+                    count: 1,
+                    total_size: 10,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_by_top_level() {
+        let results = vec![
+            result("/home/me/projects/app/target/debug/app", 3000),
+            result("/home/me/videos/trip.mp4", 5000),
+            result("/home/me/projects/site/node_modules/big.js", 4000),
+            result("/home/me/disk.img", 1500),
+            result("/home/me/videos/old.mkv", 1000),
+            result("/srv/data/dump.sql", 9000),
+        ];
+
+        assert_eq!(
+            by_top_level(Path::new("/home/me"), &results),
+            vec![
+                (PathBuf::from("/home/me/projects"), 7000, 2),
+                (PathBuf::from("/home/me/videos"), 6000, 2),
+                (PathBuf::from("/home/me"), 1500, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_by_file_type_empty() {
+        assert!(by_file_type(&[]).is_empty());
+    }
+}
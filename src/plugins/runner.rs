@@ -0,0 +1,761 @@
+/// Orchestrates the scan, select, confirm and clean steps of the feature plugins
+use super::dotnet::DotNetPlugin;
+use super::filter::SmartFilter;
+use super::format::{self, NdjsonWriter};
+use super::go::GoPlugin;
+use super::hook;
+use super::large_files::LargeFilePlugin;
+use super::largest_dirs::LargestDirsPlugin;
+use super::mime;
+use super::progress::ScanProgress;
+use super::report;
+use super::script;
+use super::session::{self, ScanSession};
+use super::sort;
+use super::watch;
+use super::{safety, utils, CleanupReport, FeaturePlugin, PluginError, RiskLevel, ScanResult};
+use crate::output::{self, OutputFormat};
+use crate::settings::Settings;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use yansi::Color;
+
+/// Checks if a run may delete anything, which lists, summaries and `--watch` never do
+fn may_delete(settings: &Settings) -> bool {
+    !settings.lists_only() && !settings.summary_only && settings.watch.is_none()
+}
+
+/// Creates an instance of every feature plugin
+fn available_plugins() -> Vec<Box<dyn FeaturePlugin>> {
+    vec![
+        Box::new(LargeFilePlugin::new()),
+        Box::new(GoPlugin::new()),
+        Box::new(DotNetPlugin::new()),
+        Box::new(LargestDirsPlugin::new()),
+    ]
+}
+
+/// Checks if any feature plugin was enabled from the command line
+pub fn any_enabled(settings: &Settings) -> bool {
+    available_plugins()
+        .iter()
+        .any(|plugin| plugin.is_enabled(settings))
+}
+
+/// Runs all enabled feature plugins on the configured paths
+///
+/// Each plugin scans every path, lets the user select the items to remove
+/// and cleans them after confirmation.
+///
+/// # Arguments
+/// `settings` - The application settings struct
+pub fn run_plugins(settings: &Settings) -> Result<(), PluginError> {
+    // Refuse dangerous roots before spending any time scanning them
+    for path in &settings.paths {
+        safety::check_scan_root(path, settings.i_know_what_im_doing)?;
+    }
+
+    // Only runs that may delete something need to ask about the home directory
+    if may_delete(settings)
+        && !safety::confirm_home_scan(
+            &settings.paths,
+            safety::home_dir().as_deref(),
+            settings.i_know_what_im_doing,
+            safety::confirmation(settings.yes, settings.force, true),
+        )
+    {
+        return Ok(());
+    }
+
+    let plugins = available_plugins()
+        .into_iter()
+        .filter(|plugin| plugin.is_enabled(settings));
+
+    if let Some(interval) = &settings.watch {
+        let interval = utils::parse_duration_string(interval)?;
+        return watch::watch(plugins.collect(), settings, interval);
+    }
+
+    let parse_size = if settings.si {
+        utils::parse_size_string_si
+    } else {
+        utils::parse_size_string
+    };
+    let confirm_over = settings
+        .confirm_over
+        .as_deref()
+        .map(parse_size)
+        .transpose()?;
+
+    // Measure the volumes of the scan roots to show the real impact of the cleanup
+    let volumes = utils::distinct_volumes(&settings.paths);
+    let free_space = || -> Option<u64> {
+        volumes
+            .iter()
+            .map(|volume| utils::available_space(volume).ok())
+            .sum()
+    };
+    let free_before = free_space();
+
+    let format_size = if settings.si {
+        utils::format_size_si
+    } else {
+        utils::format_size
+    };
+
+    // Messages are left out of the JSON formats, which only list the results
+    let machine_readable = settings.lists_only();
+    let ndjson = (settings.format == OutputFormat::Ndjson && !settings.print0)
+        .then(|| NdjsonWriter::new(io::stdout()));
+    // Sorting and limiting need all results, so those are only printed once the scan is done
+    let stream = ndjson.as_ref().filter(|_| {
+        settings.top.is_none() && settings.per_dir_limit.is_none() && settings.sort.is_none()
+    });
+    let mut json_results: Vec<ScanResult> = Vec::new();
+    let scanned_at = SystemTime::now();
+
+    // Results saved by an earlier run replace the scan of the plugin that found them
+    let loaded = match &settings.load_scan {
+        Some(path) => {
+            let session = ScanSession::load(path)?;
+            if !machine_readable {
+                output::println(
+                    "Loaded",
+                    Color::Blue,
+                    &format!("{} results from {}", session.results.len(), path.display()),
+                );
+            }
+            Some(session)
+        }
+        None => None,
+    };
+
+    // A list of paths replaces the scan of every path
+    let listed = settings
+        .paths_from
+        .as_deref()
+        .map(|source| read_listed_paths(source, settings.null_separated))
+        .transpose()?;
+
+    let mut report: Option<CleanupReport> = None;
+    let mut exported: Vec<ScanResult> = Vec::new();
+
+    for mut plugin in plugins {
+        plugin.configure(settings)?;
+
+        let session = loaded
+            .as_ref()
+            .filter(|session| session.plugin == plugin.name());
+
+        let mut scan_duration = None;
+        let mut results = match session {
+            Some(session) => {
+                let mut results = session.results.clone();
+                if settings.detect_mime {
+                    mime::detect_mime_types(&mut results);
+                }
+                results
+            }
+            None => {
+                let mut results = Vec::new();
+                let mut took = Duration::ZERO;
+                // Streamed results are sniffed as they are written, once
+                let sniffed = Mutex::new(HashMap::new());
+                match &listed {
+                    Some(paths) => {
+                        // Listed paths are classified without a progress bar
+                        let progress = ScanProgress::indeterminate_with_quiet(true);
+                        results = plugin.scan_listed(paths)?;
+                        took = progress.elapsed();
+                    }
+                    None => {
+                        let print = |result: &ScanResult| {
+                            if let Some(writer) = stream {
+                                let mut result = result.clone();
+                                if settings.detect_mime {
+                                    mime::detect_mime_types(std::slice::from_mut(&mut result));
+                                    sniffed
+                                        .lock()
+                                        .unwrap()
+                                        .insert(result.path.clone(), result.mime_type.clone());
+                                }
+                                // A closed pipe is reported by the final write
+                                let _ = writer.write(&result);
+                            }
+                        };
+                        // Every path gets a progress bar of its own
+                        for path in &settings.paths {
+                            let progress = plugin.scan_progress();
+                            results.append(&mut plugin.scan_streaming(path, &progress, &print)?);
+                            took += progress.elapsed();
+                        }
+                    }
+                }
+                utils::dedup_by_path(&mut results);
+                scan_duration = Some(took);
+
+                if let Some(limit) = settings.per_dir_limit {
+                    let hidden = utils::limit_per_dir(&mut results, limit);
+                    if !machine_readable {
+                        for (dir, count) in hidden {
+                            output::println(
+                                "Hidden",
+                                Color::Yellow,
+                                &format!(
+                                    "{} more files in {}, raise --per-dir-limit to see them",
+                                    count,
+                                    dir.display()
+                                ),
+                            );
+                        }
+                    }
+                }
+
+                // Each path is limited on its own, so limit the combined results too
+                if let Some(top) = settings.top {
+                    results.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+                    results.truncate(top);
+                }
+
+                // Only the results that are kept are worth reading
+                if settings.detect_mime {
+                    let mut sniffed = sniffed.into_inner().unwrap();
+                    for result in &mut results {
+                        match sniffed.remove(&result.path) {
+                            Some(mime_type) => result.mime_type = mime_type,
+                            None => mime::detect_mime_types(std::slice::from_mut(result)),
+                        }
+                    }
+                }
+
+                if let Some(path) = &settings.save_scan {
+                    ScanSession::new(plugin.name(), &settings.paths, results.clone()).save(path)?;
+                    if !machine_readable {
+                        output::println_info(format!("Saved scan results to {}", path.display()));
+                    }
+                }
+
+                results
+            }
+        };
+
+        if let Some(sort_by) = settings.sort {
+            sort::sort_results(&mut results, sort_by, settings.reverse);
+        }
+
+        // A plain list of paths replaces any format
+        if settings.print0 {
+            format::write_print0(io::stdout().lock(), &results)?;
+            continue;
+        }
+
+        match settings.format {
+            OutputFormat::Text => {}
+            // Results that were streamed during the scan are not written again
+            OutputFormat::Ndjson => {
+                if let Some(writer) = &ndjson {
+                    writer.write_all(&results)?;
+                }
+                continue;
+            }
+            OutputFormat::Json => {
+                json_results.append(&mut results);
+                continue;
+            }
+        }
+
+        if results.is_empty() {
+            output::println_plain(Some(Color::Yellow), "Nothing to clean up");
+            continue;
+        }
+
+        let total_size: u64 = results.iter().map(|r| r.size).sum();
+        let took = scan_duration
+            .map(|duration| format!(" in {}", utils::format_duration(duration)))
+            .unwrap_or_default();
+        output::println(
+            "Found",
+            Color::Green,
+            &format!(
+                "{} items ({}) from {}{}",
+                results.len(),
+                format_size(total_size),
+                plugin.name(),
+                took
+            ),
+        );
+
+        if settings.stats {
+            report::print_stats(&results, format_size);
+        }
+
+        if settings.stats_by_dir {
+            report::print_stats_by_dir(&settings.paths, &results, format_size);
+        }
+
+        if settings.summary_only {
+            report::print_summary(&results, format_size);
+            continue;
+        }
+
+        let mut selected = select_results(plugin.as_ref(), results, settings)?;
+        if selected.is_empty() {
+            output::println_info("Nothing selected");
+            continue;
+        }
+
+        // The interactive selector has its own order
+        if let Some(sort_by) = settings.sort {
+            sort::sort_results(&mut selected, sort_by, settings.reverse);
+        }
+
+        for result in &selected {
+            let path = result.path.to_str().unwrap_or("");
+            match &result.mime_type {
+                Some(mime_type) => output::println_info(format!("{} ({})", path, mime_type)),
+                None => output::println_info(path),
+            }
+        }
+
+        // Files may have changed since the saved scan was made
+        if session.is_some() {
+            for warning in session::stale_results(&selected) {
+                output::println("Warning", Color::Yellow, &warning);
+            }
+        }
+
+        // Hand the deletion over to the user instead of doing it, in one script for all plugins
+        if settings.export_script.is_some() {
+            exported.append(&mut selected);
+            continue;
+        }
+
+        let selected_size: u64 = selected.iter().map(|r| r.size).sum();
+        if exceeds_limit(selected_size, confirm_over) {
+            output::println(
+                "Warning",
+                Color::Yellow,
+                &format!(
+                    "{} selected, more than --confirm-over {}",
+                    format_size(selected_size),
+                    settings.confirm_over.as_deref().unwrap_or("")
+                ),
+            );
+        }
+
+        let confirmation = safety::confirmation(
+            settings.yes,
+            settings.force,
+            exceeds_limit(selected_size, confirm_over),
+        );
+        if confirmation == safety::Confirmation::Refuse {
+            output::println_info("Not deleting them with --yes alone, add --force to delete them");
+            continue;
+        }
+        if confirmation == safety::Confirmation::Ask {
+            println!(
+                "{}{} {}",
+                " ".repeat(output::LABEL_WIDTH - 8),
+                yansi::Paint::white(" DANGER ").bold().bg(Color::Red),
+                yansi::Paint::red("Above files will be permanently deleted").bold()
+            );
+
+            if !output::confirm("Continue?") {
+                continue;
+            }
+        }
+
+        let selected_paths: Vec<PathBuf> = selected.iter().map(|r| r.path.clone()).collect();
+        let mut plugin_report = plugin.clean(selected)?;
+
+        if settings.prune_empty_dirs {
+            // Only paths that are gone can have left an empty directory behind
+            let deleted: Vec<PathBuf> = selected_paths
+                .into_iter()
+                .filter(|path| fs::symlink_metadata(path).is_err())
+                .collect();
+
+            plugin_report.dirs_pruned = utils::prune_empty_dirs(
+                &deleted,
+                &settings.paths,
+                &protection_filter(settings),
+                settings.i_know_what_im_doing,
+            );
+        }
+        report = Some(report.unwrap_or_default().merge(plugin_report));
+    }
+
+    if let (Some(path), false) = (&settings.export_script, exported.is_empty()) {
+        script::export_script(path, &exported).map_err(|e| {
+            PluginError::Cleanup(format!("Could not write {}: {}", path.display(), e))
+        })?;
+        output::println(
+            "Exported",
+            Color::Green,
+            &format!("{} delete commands to {}", exported.len(), path.display()),
+        );
+    }
+
+    // One document for the results of all plugins
+    if settings.format == OutputFormat::Json && !settings.print0 {
+        format::write_json(
+            io::stdout().lock(),
+            &settings.paths,
+            scanned_at,
+            &json_results,
+        )?;
+    }
+
+    if let Some(report) = report {
+        let summary = report.to_string();
+        let mut lines = summary.lines();
+        output::println("Deleted", Color::Green, lines.next().unwrap_or(""));
+        for line in lines {
+            output::println_info(line.trim());
+        }
+
+        let free_after = free_space();
+        output::println_info(space_summary(
+            report.space_freed,
+            free_before,
+            free_after,
+            format_size,
+        ));
+
+        if let Some(command) = &settings.post_clean_hook {
+            match hook::run_post_clean_hook(command, &report) {
+                Ok(status) if status.success() => {
+                    output::println_info(hook::describe_status(&status))
+                }
+                Ok(status) => {
+                    output::println("Warning", Color::Yellow, &hook::describe_status(&status))
+                }
+                Err(err) => output::error(format!("Could not run post-clean hook: {}", err)),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the `--paths-from` list from a file, or from stdin if the file is `-`
+fn read_listed_paths(source: &Path, nul: bool) -> Result<Vec<PathBuf>, PluginError> {
+    let paths = if source == Path::new("-") {
+        utils::read_path_list(io::stdin().lock(), nul)
+    } else {
+        fs::File::open(source).and_then(|file| utils::read_path_list(io::BufReader::new(file), nul))
+    };
+
+    paths.map_err(|e| {
+        PluginError::Configuration(format!(
+            "Could not read paths from {}: {}",
+            source.display(),
+            e
+        ))
+    })
+}
+
+/// Check if the selected files are more than the `--confirm-over` limit, if one was given
+fn exceeds_limit(selected_size: u64, confirm_over: Option<u64>) -> bool {
+    confirm_over.is_some_and(|limit| selected_size > limit)
+}
+
+/// Lets the user pick the results to clean, unless `--select-safe` picks them
+///
+/// `--interactive` shows the selection in any case, so the safe results
+/// can still be reviewed before they are cleaned.
+///
+/// # Arguments
+/// * `plugin`   - The plugin that found the results
+/// * `results`  - The results to choose from
+/// * `settings` - The application settings struct
+fn select_results(
+    plugin: &dyn FeaturePlugin,
+    results: Vec<ScanResult>,
+    settings: &Settings,
+) -> Result<Vec<ScanResult>, PluginError> {
+    if !settings.select_safe {
+        return plugin.interactive_select(results);
+    }
+
+    let safe = safe_results(results);
+    output::println(
+        "Selected",
+        Color::Green,
+        &format!("{} safe items", safe.len()),
+    );
+    if settings.interactive {
+        plugin.interactive_select(safe)
+    } else {
+        Ok(safe)
+    }
+}
+
+/// Keeps only the results rated [`RiskLevel::Safe`] for `--select-safe`
+fn safe_results(results: Vec<ScanResult>) -> Vec<ScanResult> {
+    results
+        .into_iter()
+        .filter(|result| result.risk_level == RiskLevel::Safe)
+        .collect()
+}
+
+/// Creates a filter that protects the default and the user's protected patterns
+pub fn protection_filter(settings: &Settings) -> SmartFilter {
+    let mut filter = SmartFilter::new();
+    for pattern in &settings.protected_patterns {
+        filter.add_protected_pattern(pattern);
+    }
+    filter.set_case_insensitive(settings.ignores_name_case());
+    filter
+}
+
+/// Describes the space freed and, if known, how the free space on disk changed
+fn space_summary(
+    freed: u64,
+    free_before: Option<u64>,
+    free_after: Option<u64>,
+    format_size: fn(u64) -> String,
+) -> String {
+    match (free_before, free_after) {
+        (Some(before), Some(after)) => format!(
+            "Freed {}, disk free went from {} to {}",
+            format_size(freed),
+            format_size(before),
+            format_size(after)
+        ),
+        _ => format!("Freed {}", format_size(freed)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::risky_result;
+    use super::super::Plugin;
+    use super::*;
+    use structopt::StructOpt;
+
+    #[test]
+    fn test_only_deleting_runs_may_delete() {
+        let may = |args: &[&str]| {
+            let settings: Settings = StructOpt::from_iter([&["swp"], args].concat());
+            may_delete(&settings)
+        };
+
+        assert!(may(&[]));
+        assert!(may(&["--yes"]));
+        assert!(!may(&["--format", "json"]));
+        assert!(!may(&["--print0"]));
+        assert!(!may(&["--summary-only"]));
+        assert!(!may(&["--watch", "1h"]));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_export_script_lists_every_plugin() {
+        let temp_dir = tempdir::TempDir::new("export_test").unwrap();
+        let root = dunce::canonicalize(temp_dir.path()).unwrap();
+        fs::create_dir(root.join("data")).unwrap();
+        // Old logs are safe, so --select-safe selects them without asking
+        let log = root.join("data").join("server.log");
+        fs::write(&log, vec![0u8; 4096]).unwrap();
+        let year_ago = SystemTime::now() - std::time::Duration::from_secs(365 * 24 * 60 * 60);
+        fs::File::options()
+            .write(true)
+            .open(&log)
+            .unwrap()
+            .set_modified(year_ago)
+            .unwrap();
+        let script = root.join("clean.sh");
+
+        let mut settings: Settings = StructOpt::from_iter([
+            "swp",
+            "--large-files",
+            "--largest-dirs",
+            "--min-size",
+            "1KB",
+            "--select-safe",
+            "--yes",
+            "--export-script",
+            script.to_str().unwrap(),
+            root.to_str().unwrap(),
+        ]);
+        settings.quiet = true;
+        settings.validate().unwrap();
+        run_plugins(&settings).unwrap();
+
+        let script = fs::read_to_string(&script).unwrap();
+        assert!(script.contains("rm -i -- ") && script.contains("server.log"));
+        assert!(script.contains("rm -ri -- ") && script.contains("data'"));
+    }
+
+    #[test]
+    fn test_scan_progress_is_hidden_unless_reported() {
+        assert!(RecordingPlugin::default().scan_progress().is_quiet());
+
+        let settings: Settings = StructOpt::from_iter(["swp", "--quiet"]);
+        let mut plugin = LargeFilePlugin::new();
+        plugin.configure(&settings).unwrap();
+        assert!(plugin.scan_progress().is_quiet());
+    }
+
+    #[test]
+    fn test_confirm_over_limit() {
+        use safety::Confirmation::*;
+        let limit = Some(10 * 1024 * 1024 * 1024);
+        let decide = |yes: bool, force: bool, selected_size: u64, confirm_over: Option<u64>| {
+            safety::confirmation(yes, force, exceeds_limit(selected_size, confirm_over))
+        };
+
+        // Without --yes or --force the user is always asked
+        assert_eq!(decide(false, false, 0, None), Ask);
+        assert_eq!(decide(false, false, 1, limit), Ask);
+
+        // With --force only when over the limit
+        assert_eq!(decide(false, true, u64::MAX, None), Proceed);
+        assert_eq!(
+            decide(false, true, 10 * 1024 * 1024 * 1024 - 1, limit),
+            Proceed
+        );
+        assert_eq!(decide(false, true, 10 * 1024 * 1024 * 1024, limit), Proceed);
+        assert_eq!(decide(false, true, 10 * 1024 * 1024 * 1024 + 1, limit), Ask);
+
+        // With --yes the limit refuses, unless --force is given too
+        assert_eq!(decide(true, false, u64::MAX, None), Proceed);
+        assert_eq!(decide(true, false, 10 * 1024 * 1024 * 1024, limit), Proceed);
+        assert_eq!(
+            decide(true, false, 10 * 1024 * 1024 * 1024 + 1, limit),
+            Refuse
+        );
+        assert_eq!(
+            decide(true, true, 10 * 1024 * 1024 * 1024 + 1, limit),
+            Proceed
+        );
+    }
+
+    /// Plugin that remembers what it was asked to select from and selects all of it
+    #[derive(Debug, Default)]
+    struct RecordingPlugin {
+        offered: std::sync::Mutex<Option<Vec<ScanResult>>>,
+    }
+
+    impl Plugin for RecordingPlugin {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn version(&self) -> &str {
+            "1.0.0"
+        }
+
+        fn is_enabled(&self, _settings: &Settings) -> bool {
+            true
+        }
+
+        fn configure(&mut self, _settings: &Settings) -> Result<(), PluginError> {
+            Ok(())
+        }
+
+        fn apply_age_filter(&mut self, _days: u64) -> Result<(), PluginError> {
+            Ok(())
+        }
+    }
+
+    impl FeaturePlugin for RecordingPlugin {
+        fn scan(&self, _path: &Path) -> Result<Vec<ScanResult>, PluginError> {
+            Ok(Vec::new())
+        }
+
+        fn interactive_select(
+            &self,
+            results: Vec<ScanResult>,
+        ) -> Result<Vec<ScanResult>, PluginError> {
+            *self.offered.lock().unwrap() = Some(results.clone());
+            Ok(results)
+        }
+
+        fn clean(&self, _selected: Vec<ScanResult>) -> Result<CleanupReport, PluginError> {
+            Ok(CleanupReport::default())
+        }
+    }
+
+    #[test]
+    fn test_interactive_shows_the_selection_with_select_safe() {
+        let temp_dir = tempdir::TempDir::new("interactive_test").unwrap();
+        let dir = |name: &str, risk_level| {
+            let path = temp_dir.path().join(name);
+            fs::create_dir_all(&path).unwrap();
+            fs::write(path.join("output.bin"), [0u8; 512]).unwrap();
+            super::super::dirs::dir_result(&path, "Build output", risk_level, None).unwrap()
+        };
+        let results = vec![
+            dir("app/target", RiskLevel::Safe),
+            dir("lib/node_modules", RiskLevel::Safe),
+            dir("web/vendor", RiskLevel::Medium),
+        ];
+        let select = |args: &[&str]| {
+            let settings: Settings = StructOpt::from_iter([&["swp", "--force"], args].concat());
+            let plugin = RecordingPlugin::default();
+            let selected = select_results(&plugin, results.clone(), &settings).unwrap();
+            let offered = plugin.offered.lock().unwrap().take();
+            (selected, offered)
+        };
+
+        // Only --select-safe skips the selection
+        let (selected, offered) = select(&["--select-safe"]);
+        assert_eq!(selected, results[..2]);
+        assert_eq!(offered, None);
+
+        let (selected, offered) = select(&["--select-safe", "--interactive"]);
+        assert_eq!(selected, results[..2]);
+        let offered = offered.unwrap();
+        assert_eq!(offered, results[..2]);
+        assert!(offered.iter().all(|result| result.size == 512));
+
+        let (_, offered) = select(&["--interactive"]);
+        assert_eq!(offered.unwrap(), results);
+    }
+
+    #[test]
+    fn test_safe_results() {
+        let result = |name: &str, risk_level| risky_result(name, 1024, risk_level);
+        let results = vec![
+            result("a.log", RiskLevel::Safe),
+            result("b.dat", RiskLevel::Low),
+            result("c.tmp", RiskLevel::Safe),
+            result("d.bin", RiskLevel::Medium),
+            result("e.iso", RiskLevel::High),
+            result("f.env", RiskLevel::Critical),
+        ];
+
+        let paths: Vec<PathBuf> = safe_results(results)
+            .into_iter()
+            .map(|result| result.path)
+            .collect();
+        assert_eq!(paths, vec![PathBuf::from("a.log"), PathBuf::from("c.tmp")]);
+    }
+
+    #[test]
+    fn test_space_summary() {
+        assert_eq!(
+            space_summary(
+                1024,
+                Some(1024 * 1024),
+                Some(1024 * 1024 + 1024),
+                utils::format_size
+            ),
+            "Freed 1.00 KB, disk free went from 1.00 MB to 1.00 MB"
+        );
+        assert_eq!(
+            space_summary(2048, None, Some(1), utils::format_size),
+            "Freed 2.00 KB"
+        );
+        assert_eq!(
+            space_summary(2000, None, None, utils::format_size_si),
+            "Freed 2.00 KB"
+        );
+    }
+}
@@ -0,0 +1,395 @@
+/// Hard safety limits that apply to every plugin, regardless of risk level
+use super::PluginError;
+use crate::output;
+use std::path::{Path, PathBuf};
+use yansi::{Color, Paint};
+
+/// Flag that lifts the safety limits, mentioned in error messages
+const OVERRIDE_FLAG: &str = "--i-know-what-im-doing";
+
+/// System directories whose contents must never be scanned or deleted
+#[cfg(all(unix, not(target_os = "macos")))]
+const PROTECTED_SYSTEM_DIRS: &[&str] = &[
+    "/bin", "/boot", "/dev", "/etc", "/lib", "/lib32", "/lib64", "/proc", "/sbin", "/sys", "/usr",
+    "/var/lib",
+];
+
+/// System directories whose contents must never be scanned or deleted
+#[cfg(target_os = "macos")]
+const PROTECTED_SYSTEM_DIRS: &[&str] = &[
+    "/bin",
+    "/dev",
+    "/etc",
+    "/sbin",
+    "/usr",
+    "/System",
+    "/Library",
+    "/Applications",
+    "/private/etc",
+    "/private/var/db",
+];
+
+/// System directories whose contents must never be scanned or deleted
+#[cfg(windows)]
+const PROTECTED_SYSTEM_DIRS: &[&str] = &[
+    r"C:\Windows",
+    r"C:\Program Files",
+    r"C:\Program Files (x86)",
+    r"C:\ProgramData",
+];
+
+/// Gets the home directory of the current user from the environment
+pub fn home_dir() -> Option<PathBuf> {
+    #[cfg(windows)]
+    let home = std::env::var_os("USERPROFILE");
+    #[cfg(not(windows))]
+    let home = std::env::var_os("HOME");
+
+    home.filter(|h| !h.is_empty()).map(PathBuf::from)
+}
+
+/// Checks if a path is a protected system directory or is located inside one
+pub fn is_in_system_dir(path: &Path) -> Option<&'static str> {
+    let path = resolve(path);
+
+    PROTECTED_SYSTEM_DIRS
+        .iter()
+        .find(|dir| starts_with(&path, Path::new(dir)))
+        .copied()
+}
+
+/// Checks if a path is the root of a filesystem (`/`, `C:\`)
+pub fn is_filesystem_root(path: &Path) -> bool {
+    resolve(path).parent().is_none()
+}
+
+/// Checks if a path is the given home directory
+pub fn is_home_dir(path: &Path, home: &Path) -> bool {
+    equals(&resolve(path), &resolve(home))
+}
+
+/// Checks if a scan root is the given home directory or one of its parents
+///
+/// Scanning it turns up documents, photos and everything else the user keeps.
+pub fn contains_home_dir(path: &Path, home: &Path) -> bool {
+    starts_with(&resolve(home), &resolve(path))
+}
+
+/// How to deal with a question before sweep goes on, as `--yes` and `--force` decide
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confirmation {
+    /// Ask the user
+    Ask,
+    /// Go on as if the user answered yes
+    Proceed,
+    /// Stop as if the user answered no
+    Refuse,
+}
+
+/// Decides how to deal with a question before sweep goes on
+///
+/// `--yes` answers every question, but stops at a safety limit unless
+/// `--force` is given too. `--force` on its own only skips the questions
+/// that guard no safety limit, as it did before `--yes` existed.
+///
+/// | `--yes` | `--force` | Within the limits | Past a limit |
+/// |---------|-----------|-------------------|--------------|
+/// | no      | no        | Ask               | Ask          |
+/// | no      | yes       | Proceed           | Ask          |
+/// | yes     | no        | Proceed           | Refuse       |
+/// | yes     | yes       | Proceed           | Proceed      |
+///
+/// # Arguments
+/// * `yes`        - Whether `--yes` was given
+/// * `force`      - Whether `--force` was given
+/// * `past_limit` - Whether going on passes a safety limit, such as `--confirm-over`
+pub fn confirmation(yes: bool, force: bool, past_limit: bool) -> Confirmation {
+    match (yes, force, past_limit) {
+        (false, false, _) | (false, true, true) => Confirmation::Ask,
+        (true, false, true) => Confirmation::Refuse,
+        _ => Confirmation::Proceed,
+    }
+}
+
+/// Asks before scanning the home directory, unless the user lifted the safety limits
+///
+/// Scanning the home directory is past a safety limit, so the question is
+/// asked even with `--force`, and `--yes` alone refuses instead of asking.
+///
+/// # Arguments
+/// `paths`        - The scan roots
+/// `home`         - The home directory of the current user, see [`home_dir`]
+/// `allow`        - Whether the user explicitly lifted the safety limits
+/// `confirmation` - How to deal with the question, see [`confirmation`]
+///
+/// # Returns
+/// Whether the scan should go ahead
+pub fn confirm_home_scan(
+    paths: &[PathBuf],
+    home: Option<&Path>,
+    allow: bool,
+    confirmation: Confirmation,
+) -> bool {
+    let Some(home) = home else {
+        return true;
+    };
+    let Some(path) = paths.iter().find(|path| contains_home_dir(path, home)) else {
+        return true;
+    };
+    if allow || confirmation == Confirmation::Proceed {
+        return true;
+    }
+
+    println!(
+        "{}{} {}",
+        " ".repeat(output::LABEL_WIDTH - 9),
+        Paint::white(" WARNING ").bold().bg(Color::Red),
+        Paint::red(format!(
+            "{} holds your home directory, so the results can include documents and photos \
+             you want to keep",
+            path.display()
+        ))
+        .bold()
+    );
+    if confirmation == Confirmation::Refuse {
+        output::println_info(format!(
+            "Not scanning it with --yes alone, add --force or pass {} to scan it",
+            OVERRIDE_FLAG
+        ));
+        return false;
+    }
+    output::println_info(format!("Pass {} to skip this question", OVERRIDE_FLAG));
+
+    output::confirm("Scan it anyway?")
+}
+
+/// Refuses to scan a root that is a filesystem root or a system directory
+///
+/// # Arguments
+/// `path`  - The scan root
+/// `allow` - Whether the user explicitly lifted the safety limits
+pub fn check_scan_root(path: &Path, allow: bool) -> Result<(), PluginError> {
+    if allow {
+        return Ok(());
+    }
+
+    if is_filesystem_root(path) {
+        return Err(PluginError::Configuration(format!(
+            "Refusing to scan the filesystem root {}. Point sweep at a project or data directory \
+             instead, or pass {} if you really mean it.",
+            path.display(),
+            OVERRIDE_FLAG
+        )));
+    }
+
+    if let Some(dir) = is_in_system_dir(path) {
+        return Err(PluginError::Configuration(format!(
+            "Refusing to scan {} because it is inside the system directory {}. \
+             Pass {} if you really mean it.",
+            path.display(),
+            dir,
+            OVERRIDE_FLAG
+        )));
+    }
+
+    Ok(())
+}
+
+/// Refuses to delete a system path, a filesystem root or the home directory
+///
+/// # Arguments
+/// `path`  - The path about to be deleted
+/// `allow` - Whether the user explicitly lifted the safety limits
+pub fn check_deletion(path: &Path, allow: bool) -> Result<(), PluginError> {
+    if allow {
+        return Ok(());
+    }
+
+    if is_filesystem_root(path) || home_dir().is_some_and(|home| is_home_dir(path, &home)) {
+        return Err(PluginError::Configuration(format!(
+            "Refusing to delete {}. Pass {} if you really mean it.",
+            path.display(),
+            OVERRIDE_FLAG
+        )));
+    }
+
+    if let Some(dir) = is_in_system_dir(path) {
+        return Err(PluginError::Configuration(format!(
+            "Refusing to delete {} because it is inside the system directory {}. \
+             Pass {} if you really mean it.",
+            path.display(),
+            dir,
+            OVERRIDE_FLAG
+        )));
+    }
+
+    Ok(())
+}
+
+/// Resolves symlinks and `..` components so `/tmp/../etc` can't sneak past the checks
+fn resolve(path: &Path) -> PathBuf {
+    dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(windows)]
+fn starts_with(path: &Path, dir: &Path) -> bool {
+    let path = path.to_string_lossy().to_lowercase();
+    Path::new(&path).starts_with(dir.to_string_lossy().to_lowercase())
+}
+
+#[cfg(not(windows))]
+fn starts_with(path: &Path, dir: &Path) -> bool {
+    path.starts_with(dir)
+}
+
+#[cfg(windows)]
+fn equals(a: &Path, b: &Path) -> bool {
+    a.to_string_lossy().to_lowercase() == b.to_string_lossy().to_lowercase()
+}
+
+#[cfg(not(windows))]
+fn equals(a: &Path, b: &Path) -> bool {
+    a == b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempdir::TempDir;
+
+    #[test]
+    fn test_confirmation_matrix() {
+        use Confirmation::*;
+
+        let matrix = [
+            // yes, force, past_limit, expected
+            (false, false, false, Ask),
+            (false, false, true, Ask),
+            (false, true, false, Proceed),
+            (false, true, true, Ask),
+            (true, false, false, Proceed),
+            (true, false, true, Refuse),
+            (true, true, false, Proceed),
+            (true, true, true, Proceed),
+        ];
+        for (yes, force, past_limit, expected) in matrix {
+            assert_eq!(
+                confirmation(yes, force, past_limit),
+                expected,
+                "--yes {} --force {} past the limit {}",
+                yes,
+                force,
+                past_limit
+            );
+        }
+    }
+
+    #[test]
+    fn test_temp_dir_is_allowed() {
+        let temp_dir = TempDir::new("safety_test").unwrap();
+        let file = temp_dir.path().join("large.bin");
+
+        assert!(check_scan_root(temp_dir.path(), false).is_ok());
+        assert!(check_deletion(&file, false).is_ok());
+    }
+
+    #[test]
+    fn test_override_allows_everything() {
+        for dir in PROTECTED_SYSTEM_DIRS {
+            assert!(check_scan_root(Path::new(dir), true).is_ok());
+            assert!(check_deletion(Path::new(dir), true).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_error_mentions_override() {
+        let err = check_deletion(Path::new(PROTECTED_SYSTEM_DIRS[0]), false).unwrap_err();
+        assert!(err.to_string().contains(OVERRIDE_FLAG));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_system_paths_are_refused() {
+        assert!(check_scan_root(Path::new("/"), false).is_err());
+        assert!(check_scan_root(Path::new("/usr"), false).is_err());
+        assert!(check_deletion(Path::new("/"), false).is_err());
+        assert!(check_deletion(Path::new("/usr/bin/env"), false).is_err());
+        assert!(check_deletion(Path::new("/etc/hosts"), false).is_err());
+        assert!(check_deletion(Path::new("/tmp/../etc/passwd"), false).is_err());
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_macos_system_paths_are_refused() {
+        assert!(check_deletion(Path::new("/System/Library/Kernels/kernel"), false).is_err());
+        assert!(check_deletion(Path::new("/Applications/Safari.app"), false).is_err());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_windows_system_paths_are_refused() {
+        assert!(check_scan_root(Path::new(r"C:\"), false).is_err());
+        assert!(check_deletion(Path::new(r"C:\Windows\System32\kernel32.dll"), false).is_err());
+        assert!(check_deletion(Path::new(r"c:\windows\explorer.exe"), false).is_err());
+        assert!(check_deletion(Path::new(r"C:\Program Files\App\app.exe"), false).is_err());
+    }
+
+    #[test]
+    fn test_home_dir_itself_cannot_be_deleted() {
+        if let Some(home) = home_dir() {
+            assert!(check_deletion(&home, false).is_err());
+        }
+    }
+
+    #[test]
+    fn test_scan_roots_holding_the_home_dir() {
+        let temp_dir = TempDir::new("home_test").unwrap();
+        let home = temp_dir.path().join("Users").join("me");
+        let downloads = home.join("Downloads");
+        let other = temp_dir.path().join("Users").join("someone-else");
+        fs::create_dir_all(&downloads).unwrap();
+        fs::create_dir_all(&other).unwrap();
+
+        assert!(contains_home_dir(&home, &home));
+        assert!(contains_home_dir(&downloads.join(".."), &home));
+        assert!(contains_home_dir(&temp_dir.path().join("Users"), &home));
+        assert!(!contains_home_dir(&downloads, &home));
+        assert!(!contains_home_dir(&other, &home));
+        assert!(is_home_dir(&downloads.join(".."), &home));
+        assert!(!is_home_dir(&downloads, &home));
+
+        // Nothing to ask about or lifted limits don't ask
+        let home = Some(home.as_path());
+        assert!(confirm_home_scan(
+            &[downloads.clone(), other.clone()],
+            home,
+            false,
+            Confirmation::Ask
+        ));
+        assert!(confirm_home_scan(
+            &[other.clone(), temp_dir.path().to_path_buf()],
+            None,
+            false,
+            Confirmation::Refuse
+        ));
+        assert!(confirm_home_scan(
+            &[other.clone(), temp_dir.path().to_path_buf()],
+            home,
+            true,
+            Confirmation::Ask
+        ));
+        assert!(confirm_home_scan(
+            &[temp_dir.path().to_path_buf()],
+            home,
+            false,
+            Confirmation::Proceed
+        ));
+        assert!(!confirm_home_scan(
+            &[other, temp_dir.path().to_path_buf()],
+            home,
+            false,
+            Confirmation::Refuse
+        ));
+    }
+}
@@ -2,11 +2,14 @@
 #[cfg(test)]
 mod tests {
     use super::super::*;
-    use crate::plugins::{FeaturePlugin, Plugin};
+    use crate::output::{ColorChoice, OutputFormat};
+    use crate::plugins::progress::ScanObserver;
+    use crate::plugins::{FeaturePlugin, Plugin, RiskLevel};
     use crate::settings::Settings;
     use std::fs::{self, File};
     use std::io::Write;
     use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::time::{Duration, SystemTime};
     use tempdir::TempDir;
 
@@ -20,6 +23,19 @@ mod tests {
         Ok(())
     }
 
+    /// Helper to set the modification and access times of a file
+    fn set_times(path: &Path, modified: SystemTime, accessed: SystemTime) {
+        let times = fs::FileTimes::new()
+            .set_modified(modified)
+            .set_accessed(accessed);
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_times(times)
+            .unwrap();
+    }
+
     /// Helper to create a test directory structure
     fn setup_test_directory() -> TempDir {
         let temp_dir = TempDir::new("sweep_test").unwrap();
@@ -142,6 +158,69 @@ mod tests {
         assert_eq!(results.len(), 0);
     }
 
+    #[test]
+    fn test_scan_of_a_single_file() {
+        let temp_dir = setup_test_directory();
+        let file = temp_dir.path().join("large_file.dat");
+        let mut plugin = LargeFilePlugin::new();
+
+        let settings = create_test_settings(true, "100MB", false, None);
+        plugin.configure(&settings).unwrap();
+
+        let observer = CountingObserver::default();
+        let results = plugin.scan_with_observer(&file, Some(&observer)).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path, dunce::canonicalize(&file).unwrap());
+        assert_eq!(results[0].size, 150 * 1024 * 1024);
+        assert_eq!(observer.found.load(Ordering::SeqCst), 1);
+        assert_eq!(observer.finished.load(Ordering::SeqCst), 1);
+
+        // A file below the threshold is not reported just because it was named
+        let small = temp_dir.path().join("small_file.txt");
+        assert!(plugin.scan(&small).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scan_root_is_never_reported() {
+        let temp_dir = setup_size_window_directory();
+        let mut plugin = LargeFilePlugin::new();
+
+        // Even with no threshold, only the files below the root are results
+        let settings = create_test_settings(true, "0B", false, None);
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        assert!(!results.is_empty());
+        assert!(results.iter().all(|r| r.path.is_file()));
+    }
+
+    #[test]
+    fn test_indexing_succeeds_on_empty_directory() {
+        let temp_dir = TempDir::new("empty_test").unwrap();
+        let mut plugin = LargeFilePlugin::new();
+
+        let settings = create_test_settings(true, "100MB", false, None);
+        plugin.configure(&settings).unwrap();
+
+        plugin.index_filters(temp_dir.path()).unwrap();
+        assert_eq!(plugin.filter.lock().unwrap().ignore_rules_count(), 0);
+    }
+
+    #[test]
+    fn test_indexing_loads_ignore_rules() {
+        let temp_dir = TempDir::new("index_test").unwrap();
+        fs::write(temp_dir.path().join(".gitignore"), "*.log\n").unwrap();
+        let mut plugin = LargeFilePlugin::new();
+
+        let settings = create_test_settings(true, "100MB", false, None);
+        plugin.configure(&settings).unwrap();
+
+        plugin.index_filters(temp_dir.path()).unwrap();
+        let filter = plugin.filter.lock().unwrap();
+        assert_eq!(filter.ignore_rules_count(), 1);
+        assert!(filter.is_gitignored(&temp_dir.path().join("build.log")));
+    }
+
     #[test]
     fn test_scan_handles_nonexistent_path() {
         let mut plugin = LargeFilePlugin::new();
@@ -206,6 +285,746 @@ mod tests {
         assert!(duration < Duration::from_secs(2));
     }
 
+    /// Helper to create files of 1, 5 and 10 MB
+    fn setup_size_window_directory() -> TempDir {
+        let temp_dir = TempDir::new("size_window_test").unwrap();
+        for size in [1, 5, 10] {
+            create_file_with_size(&temp_dir.path().join(format!("{}mb.dat", size)), size).unwrap();
+        }
+        temp_dir
+    }
+
+    /// Helper to collect and sort the file names of scan results
+    fn result_names(results: &[crate::plugins::ScanResult]) -> Vec<String> {
+        let mut names: Vec<String> = results
+            .iter()
+            .map(|r| r.path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_fdignore_marks_files_safe() {
+        let temp_dir = TempDir::new("fdignore_test").unwrap();
+        create_file_with_size(&temp_dir.path().join("excluded.bin"), 2).unwrap();
+        create_file_with_size(&temp_dir.path().join("included.dat"), 2).unwrap();
+        fs::write(temp_dir.path().join(".fdignore"), "*.bin\n").unwrap();
+
+        let mut plugin = LargeFilePlugin::new();
+        let settings = create_test_settings(true, "1MB", false, None);
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        let risk_of = |name: &str| {
+            results
+                .iter()
+                .find(|r| r.path.ends_with(name))
+                .map(|r| r.risk_level)
+        };
+
+        assert_eq!(risk_of("excluded.bin"), Some(RiskLevel::Safe));
+        // Freshly created and not ignored
+        assert_eq!(risk_of("included.dat"), Some(RiskLevel::High));
+    }
+
+    #[test]
+    fn test_no_gitignore_computes_risk_of_ignored_files() {
+        let temp_dir = TempDir::new("no_gitignore_test").unwrap();
+        git2::Repository::init(temp_dir.path()).unwrap();
+        fs::write(temp_dir.path().join(".gitignore"), "*.bin\n").unwrap();
+        create_file_with_size(&temp_dir.path().join("ignored.bin"), 2).unwrap();
+
+        let scan_risk = |no_gitignore: bool| {
+            let mut plugin = LargeFilePlugin::new();
+            let mut settings = create_test_settings(true, "1MB", false, None);
+            settings.no_gitignore = no_gitignore;
+            plugin.configure(&settings).unwrap();
+            plugin.scan(temp_dir.path()).unwrap()[0].risk_level
+        };
+
+        assert_eq!(scan_risk(false), RiskLevel::Safe);
+        // Freshly created, so the age decides instead
+        assert_eq!(scan_risk(true), RiskLevel::High);
+    }
+
+    /// Helper to create a directory with hidden files next to a visible one
+    fn setup_hidden_directory() -> TempDir {
+        let temp_dir = TempDir::new("hidden_test").unwrap();
+        let base_path = temp_dir.path();
+
+        create_file_with_size(&base_path.join("visible.dat"), 2).unwrap();
+        create_file_with_size(&base_path.join(".hidden.dat"), 2).unwrap();
+
+        let cache_dir = base_path.join(".cache");
+        fs::create_dir(&cache_dir).unwrap();
+        create_file_with_size(&cache_dir.join("cached.dat"), 2).unwrap();
+
+        temp_dir
+    }
+
+    #[test]
+    fn test_hidden_files_are_excluded_by_default() {
+        let temp_dir = setup_hidden_directory();
+        let mut plugin = LargeFilePlugin::new();
+        let settings = create_test_settings(true, "1MB", false, None);
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        assert_eq!(result_names(&results), vec!["visible.dat"]);
+    }
+
+    #[test]
+    fn test_include_hidden() {
+        let temp_dir = setup_hidden_directory();
+        let mut plugin = LargeFilePlugin::new();
+        let mut settings = create_test_settings(true, "1MB", false, None);
+        settings.include_hidden = true;
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        assert_eq!(
+            result_names(&results),
+            vec![".hidden.dat", "cached.dat", "visible.dat"]
+        );
+    }
+
+    #[test]
+    fn test_hidden_scan_root_is_scanned() {
+        let temp_dir = setup_hidden_directory();
+        let mut plugin = LargeFilePlugin::new();
+        let settings = create_test_settings(true, "1MB", false, None);
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(&temp_dir.path().join(".cache")).unwrap();
+        assert_eq!(result_names(&results), vec!["cached.dat"]);
+    }
+
+    #[test]
+    fn test_excluded_paths_are_skipped() {
+        let temp_dir = TempDir::new("exclude_test").unwrap();
+        let base_path = temp_dir.path();
+        for dir in ["keep", "downloads/keep", "other"] {
+            fs::create_dir_all(base_path.join(dir)).unwrap();
+        }
+        for file in [
+            "keep/a.dat",
+            "downloads/keep/b.dat",
+            "downloads/c.dat",
+            "other/d.dat",
+            "other/e.dat",
+        ] {
+            create_file_with_size(&base_path.join(file), 2).unwrap();
+        }
+
+        let mut plugin = LargeFilePlugin::new();
+        let mut settings = create_test_settings(true, "1MB", false, None);
+        settings.exclude = vec![
+            base_path.join("keep"),
+            base_path.join("other/../downloads/keep"),
+        ];
+        settings.ignore = vec![regex::Regex::new(r"e\.dat$").unwrap()];
+        assert!(settings.validate().is_ok());
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(base_path).unwrap();
+        assert_eq!(result_names(&results), vec!["c.dat", "d.dat"]);
+    }
+
+    #[test]
+    fn test_listed_paths_match_direct_stats() {
+        let temp_dir = setup_test_directory();
+        let base_path = temp_dir.path();
+        let mut plugin = LargeFilePlugin::new();
+        let settings = create_test_settings(true, "50MB", false, None);
+        plugin.configure(&settings).unwrap();
+
+        // Small files, directories and missing paths are skipped, relative spellings resolved
+        let list = format!(
+            "{0}/large_file.dat\0{0}/small_file.txt\0{0}/nested\0{0}/missing.dat\0{0}/nested/../nested/nested_large.bin\0",
+            base_path.display()
+        );
+        let paths = crate::plugins::utils::read_path_list(list.as_bytes(), true).unwrap();
+        let results = plugin.scan_listed(&paths).unwrap();
+
+        let canonical = dunce::canonicalize(base_path).unwrap();
+        let expected = [
+            canonical.join("nested").join("nested_large.bin"),
+            canonical.join("large_file.dat"),
+        ];
+        assert_eq!(results.len(), expected.len());
+        for (result, path) in results.iter().zip(&expected) {
+            assert_eq!(&result.path, path);
+            assert_eq!(result.size, fs::metadata(path).unwrap().len());
+        }
+    }
+
+    #[test]
+    fn test_top_keeps_largest_files() {
+        let temp_dir = setup_size_window_directory();
+        let mut plugin = LargeFilePlugin::new();
+
+        let mut settings = create_test_settings(true, "1MB", false, None);
+        settings.top = Some(2);
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(result_names(&results), vec!["10mb.dat", "5mb.dat"]);
+        assert!(results[0].size > results[1].size);
+    }
+
+    #[test]
+    fn test_min_size_only() {
+        let temp_dir = setup_size_window_directory();
+        let mut plugin = LargeFilePlugin::new();
+
+        let settings = create_test_settings(true, "4MB", false, None);
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        assert_eq!(result_names(&results), vec!["10mb.dat", "5mb.dat"]);
+    }
+
+    #[test]
+    fn test_max_size_only() {
+        let temp_dir = setup_size_window_directory();
+        let mut plugin = LargeFilePlugin::new();
+
+        let mut settings = create_test_settings(true, "0B", false, None);
+        settings.max_size = Some("6MB".to_string());
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        assert_eq!(result_names(&results), vec!["1mb.dat", "5mb.dat"]);
+    }
+
+    #[test]
+    fn test_size_window() {
+        let temp_dir = setup_size_window_directory();
+        let mut plugin = LargeFilePlugin::new();
+
+        let mut settings = create_test_settings(true, "2MB", false, None);
+        settings.max_size = Some("8MB".to_string());
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        assert_eq!(result_names(&results), vec!["5mb.dat"]);
+    }
+
+    #[test]
+    fn test_max_size_smaller_than_min_size_is_rejected() {
+        let mut plugin = LargeFilePlugin::new();
+
+        let mut settings = create_test_settings(true, "100MB", false, None);
+        settings.max_size = Some("10MB".to_string());
+
+        assert!(plugin.configure(&settings).is_err());
+    }
+
+    /// Helper to create a Safe (gitignored), a High (fresh) and a Critical (protected) file
+    fn setup_risk_directory() -> TempDir {
+        let temp_dir = TempDir::new("max_risk_test").unwrap();
+        let base_path = temp_dir.path();
+
+        fs::write(base_path.join(".gitignore"), "ignored.dat\n").unwrap();
+        create_file_with_size(&base_path.join("ignored.dat"), 2).unwrap();
+        create_file_with_size(&base_path.join("fresh.dat"), 2).unwrap();
+        create_file_with_size(&base_path.join("secrets.dat"), 2).unwrap();
+
+        temp_dir
+    }
+
+    #[test]
+    fn test_no_max_risk_shows_all() {
+        let temp_dir = setup_risk_directory();
+        let mut plugin = LargeFilePlugin::new();
+
+        let settings = create_test_settings(true, "1MB", true, None);
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        assert_eq!(
+            result_names(&results),
+            vec!["fresh.dat", "ignored.dat", "secrets.dat"]
+        );
+    }
+
+    #[test]
+    fn test_max_risk_low_drops_riskier_files() {
+        let temp_dir = setup_risk_directory();
+        let mut plugin = LargeFilePlugin::new();
+
+        let mut settings = create_test_settings(true, "1MB", true, None);
+        settings.max_risk = Some("low".parse().unwrap());
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        assert_eq!(result_names(&results), vec!["ignored.dat"]);
+        assert!(results.iter().all(|r| r.risk_level <= RiskLevel::Low));
+    }
+
+    #[test]
+    fn test_max_risk_high_drops_critical_files() {
+        let temp_dir = setup_risk_directory();
+        let mut plugin = LargeFilePlugin::new();
+
+        let mut settings = create_test_settings(true, "1MB", true, None);
+        settings.max_risk = Some(RiskLevel::High);
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        assert_eq!(result_names(&results), vec!["fresh.dat", "ignored.dat"]);
+    }
+
+    #[test]
+    fn test_exclude_type_drops_media_files() {
+        let temp_dir = TempDir::new("exclude_type_test").unwrap();
+        let base_path = temp_dir.path();
+        for name in [
+            "movie.mp4",
+            "photo.png",
+            "backup.zip",
+            "data.bin",
+            "small.zip",
+        ] {
+            let size_mb = if name == "small.zip" { 1 } else { 2 };
+            create_file_with_size(&base_path.join(name), size_mb).unwrap();
+        }
+        let mut plugin = LargeFilePlugin::new();
+
+        let mut settings = create_test_settings(true, "2MB", true, None);
+        settings.exclude_types = vec!["media".parse().unwrap()];
+        settings.max_risk = Some(RiskLevel::High);
+        plugin.configure(&settings).unwrap();
+
+        // The size bound still applies, so the small archive is left out too
+        let results = plugin.scan(base_path).unwrap();
+        assert_eq!(result_names(&results), vec!["backup.zip", "data.bin"]);
+    }
+
+    #[test]
+    fn test_modified_before_keeps_files_older_than_the_cutoff() {
+        let temp_dir = TempDir::new("modified_before_test").unwrap();
+        let base_path = temp_dir.path();
+        let cutoff = utils::parse_date("2023-06-01").unwrap();
+        for (name, modified) in [
+            (
+                "year_before.dat",
+                cutoff - Duration::from_secs(365 * 24 * 60 * 60),
+            ),
+            ("second_before.dat", cutoff - Duration::from_secs(1)),
+            ("at_cutoff.dat", cutoff),
+            ("second_after.dat", cutoff + Duration::from_secs(1)),
+        ] {
+            let path = base_path.join(name);
+            create_file_with_size(&path, 2).unwrap();
+            set_times(&path, modified, modified);
+        }
+        let mut plugin = LargeFilePlugin::new();
+
+        let mut settings = create_test_settings(true, "1MB", true, None);
+        settings.modified_before = Some(cutoff);
+        settings.max_risk = Some(RiskLevel::Critical);
+        plugin.configure(&settings).unwrap();
+        assert_eq!(
+            result_names(&plugin.scan(base_path).unwrap()),
+            vec!["second_before.dat", "year_before.dat"]
+        );
+
+        // Files that were accessed since the cutoff are left out
+        set_times(
+            &base_path.join("second_before.dat"),
+            cutoff - Duration::from_secs(1),
+            cutoff + Duration::from_secs(60),
+        );
+        settings.accessed_before = Some(cutoff);
+        plugin.configure(&settings).unwrap();
+        assert_eq!(
+            result_names(&plugin.scan(base_path).unwrap()),
+            vec!["year_before.dat"]
+        );
+
+        // The relative age filter has to pass as well
+        settings.accessed_before = None;
+        settings.older_than_days = Some(36_500);
+        plugin.configure(&settings).unwrap();
+        assert!(plugin.scan(base_path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_all_matches_collected_past_channel_capacity() {
+        let temp_dir = TempDir::new("many_files_test").unwrap();
+        for dir in 0..10 {
+            let dir_path = temp_dir.path().join(format!("dir_{}", dir));
+            fs::create_dir(&dir_path).unwrap();
+            for file in 0..300 {
+                fs::write(dir_path.join(format!("file_{}.dat", file)), "12").unwrap();
+            }
+        }
+
+        // Two threads can only queue a fraction of the 3000 matches
+        let mut plugin = LargeFilePlugin::new();
+        let mut settings = create_test_settings(true, "2B", false, None);
+        settings.threads = Some(2);
+        plugin.configure(&settings).unwrap();
+
+        let observer = CountingObserver::default();
+        let results = plugin
+            .scan_with_observer(temp_dir.path(), Some(&observer))
+            .unwrap();
+
+        assert_eq!(results.len(), 3000);
+        assert_eq!(observer.found.load(Ordering::SeqCst), 3000);
+    }
+
+    #[test]
+    fn test_equal_sizes_are_sorted_by_path() {
+        let temp_dir = TempDir::new("equal_size_test").unwrap();
+        for name in ["d.dat", "b.dat", "e.dat", "a.dat", "c.dat"] {
+            create_file_with_size(&temp_dir.path().join(name), 1).unwrap();
+        }
+        create_file_with_size(&temp_dir.path().join("z_larger.dat"), 2).unwrap();
+
+        let mut plugin = LargeFilePlugin::new();
+        let settings = create_test_settings(true, "1MB", false, None);
+        plugin.configure(&settings).unwrap();
+
+        for _ in 0..5 {
+            let names: Vec<String> = plugin
+                .scan(temp_dir.path())
+                .unwrap()
+                .iter()
+                .map(|r| r.path.file_name().unwrap().to_string_lossy().to_string())
+                .collect();
+
+            assert_eq!(
+                names,
+                vec!["z_larger.dat", "a.dat", "b.dat", "c.dat", "d.dat", "e.dat"]
+            );
+        }
+    }
+
+    #[test]
+    fn test_single_thread_matches_multi_thread() {
+        let temp_dir = setup_test_directory();
+
+        let scan_with_threads = |threads: Option<usize>| {
+            let mut plugin = LargeFilePlugin::new();
+            let mut settings = create_test_settings(true, "50MB", false, None);
+            settings.threads = threads;
+            plugin.configure(&settings).unwrap();
+
+            plugin
+                .scan(temp_dir.path())
+                .unwrap()
+                .into_iter()
+                .map(|r| (r.path, r.size, r.risk_level))
+                .collect::<Vec<_>>()
+        };
+
+        let single = scan_with_threads(Some(1));
+        assert_eq!(single.len(), 3);
+        assert_eq!(single, scan_with_threads(Some(4)));
+        assert_eq!(single, scan_with_threads(None));
+    }
+
+    /// Helper to create a 2MB file at every depth from 1 to 4
+    fn setup_nested_directory() -> TempDir {
+        let temp_dir = TempDir::new("max_depth_test").unwrap();
+        let mut dir = temp_dir.path().to_path_buf();
+
+        for depth in 1..=4 {
+            create_file_with_size(&dir.join(format!("depth{}.dat", depth)), 2).unwrap();
+            dir = dir.join(format!("level{}", depth));
+            fs::create_dir(&dir).unwrap();
+        }
+
+        temp_dir
+    }
+
+    #[test]
+    fn test_max_depth_limits_recursion() {
+        let temp_dir = setup_nested_directory();
+        let mut plugin = LargeFilePlugin::new();
+
+        let mut settings = create_test_settings(true, "1MB", false, None);
+        settings.max_depth = Some(2);
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        assert_eq!(result_names(&results), vec!["depth1.dat", "depth2.dat"]);
+    }
+
+    #[test]
+    fn test_no_max_depth_is_unlimited() {
+        let temp_dir = setup_nested_directory();
+        let mut plugin = LargeFilePlugin::new();
+
+        let settings = create_test_settings(true, "1MB", false, None);
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        assert_eq!(
+            result_names(&results),
+            vec!["depth1.dat", "depth2.dat", "depth3.dat", "depth4.dat"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_to_large_file_is_not_followed_by_default() {
+        let temp_dir = TempDir::new("symlink_test").unwrap();
+        let target_dir = TempDir::new("symlink_target").unwrap();
+        create_file_with_size(&target_dir.path().join("target.dat"), 2).unwrap();
+        std::os::unix::fs::symlink(
+            target_dir.path().join("target.dat"),
+            temp_dir.path().join("link.dat"),
+        )
+        .unwrap();
+
+        let mut plugin = LargeFilePlugin::new();
+        let settings = create_test_settings(true, "1MB", false, None);
+        plugin.configure(&settings).unwrap();
+
+        assert!(plugin.scan(temp_dir.path()).unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_to_large_file_is_followed() {
+        let temp_dir = TempDir::new("symlink_test").unwrap();
+        let target_dir = TempDir::new("symlink_target").unwrap();
+        create_file_with_size(&target_dir.path().join("target.dat"), 2).unwrap();
+        std::os::unix::fs::symlink(
+            target_dir.path().join("target.dat"),
+            temp_dir.path().join("link.dat"),
+        )
+        .unwrap();
+
+        let mut plugin = LargeFilePlugin::new();
+        let mut settings = create_test_settings(true, "1MB", false, None);
+        settings.follow_symlinks = true;
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        assert_eq!(result_names(&results), vec!["link.dat"]);
+        assert_eq!(results[0].size, 2 * 1024 * 1024);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_broken_symlink_is_reported() {
+        let temp_dir = TempDir::new("symlink_test").unwrap();
+        std::os::unix::fs::symlink(
+            temp_dir.path().join("missing.dat"),
+            temp_dir.path().join("broken.dat"),
+        )
+        .unwrap();
+
+        let mut plugin = LargeFilePlugin::new();
+        let mut settings = create_test_settings(true, "1MB", false, None);
+        // Keep the manifest out of the real state directory
+        let state_dir = TempDir::new("state_test").unwrap();
+        settings.manifest_path = Some(state_dir.path().join("last-run.json"));
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        assert_eq!(result_names(&results), vec!["broken.dat"]);
+        assert_eq!(results[0].risk_level, RiskLevel::Low);
+        assert!(results[0].description.contains("Type: broken symlink"));
+
+        // Cleaning removes the link even though its target doesn't exist
+        let report = plugin.clean(results).unwrap();
+        assert_eq!(report.items_cleaned, 1);
+        assert!(fs::symlink_metadata(temp_dir.path().join("broken.dat")).is_err());
+        assert!(state_dir.path().join("last-run.json").is_file());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_loop_is_skipped() {
+        let temp_dir = TempDir::new("symlink_test").unwrap();
+        create_file_with_size(&temp_dir.path().join("file.dat"), 2).unwrap();
+        fs::create_dir(temp_dir.path().join("sub")).unwrap();
+        std::os::unix::fs::symlink(temp_dir.path(), temp_dir.path().join("sub").join("loop"))
+            .unwrap();
+        std::os::unix::fs::symlink("self", temp_dir.path().join("self")).unwrap();
+
+        let mut plugin = LargeFilePlugin::new();
+        let mut settings = create_test_settings(true, "1MB", false, None);
+        settings.follow_symlinks = true;
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan(temp_dir.path()).unwrap();
+        assert_eq!(result_names(&results), vec!["file.dat"]);
+    }
+
+    /// Observer that counts its callbacks
+    #[derive(Default)]
+    struct CountingObserver {
+        started: AtomicUsize,
+        scanned: AtomicUsize,
+        found: AtomicUsize,
+        finished: AtomicUsize,
+    }
+
+    impl ScanObserver for CountingObserver {
+        fn on_start(&self, total: u64) {
+            self.started.store(total as usize, Ordering::SeqCst);
+        }
+
+        fn on_scanned(&self, _path: &Path) {
+            self.scanned.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_found(&self) {
+            self.found.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_finish(&self) {
+            self.finished.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_hidden_directories_are_not_walked() {
+        let temp_dir = setup_hidden_directory();
+        let mut plugin = LargeFilePlugin::new();
+        let settings = create_test_settings(true, "1MB", false, None);
+        plugin.configure(&settings).unwrap();
+
+        let observer = CountingObserver::default();
+        plugin
+            .scan_with_observer(temp_dir.path(), Some(&observer))
+            .unwrap();
+
+        // Only the root and visible.dat are walked
+        assert_eq!(observer.started.load(Ordering::SeqCst), 2);
+    }
+
+    /// Observer that panics on the first result
+    struct PanickingObserver;
+
+    impl ScanObserver for PanickingObserver {
+        fn on_scanned(&self, _path: &Path) {}
+
+        fn on_found(&self) {}
+
+        fn on_result(&self, _result: &ScanResult) {
+            panic!("observer failed");
+        }
+
+        fn on_finish(&self) {}
+    }
+
+    #[test]
+    fn test_failed_collection_is_an_error() {
+        let temp_dir = setup_hidden_directory();
+        let mut plugin = LargeFilePlugin::new();
+        let settings = create_test_settings(true, "1MB", false, None);
+        plugin.configure(&settings).unwrap();
+
+        let result = plugin.scan_with_observer(temp_dir.path(), Some(&PanickingObserver));
+        assert!(matches!(result, Err(PluginError::Scan(_))));
+    }
+
+    #[test]
+    fn test_overlapping_roots_are_scanned_once() {
+        let temp_dir = TempDir::new("overlap_test").unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("Downloads")).unwrap();
+        create_file_with_size(&root.join("outer.bin"), 2).unwrap();
+        create_file_with_size(&root.join("Downloads").join("inner.bin"), 2).unwrap();
+
+        let mut settings = create_test_settings(true, "1MB", false, None);
+        settings.paths = vec![
+            root.join("Downloads"),
+            root.to_path_buf(),
+            root.join("Downloads"),
+        ];
+        settings.validate().unwrap();
+        assert_eq!(settings.paths, vec![dunce::canonicalize(root).unwrap()]);
+
+        let mut plugin = LargeFilePlugin::new();
+        plugin.configure(&settings).unwrap();
+
+        let observer = CountingObserver::default();
+        let mut results = Vec::new();
+        for path in &settings.paths {
+            results.append(&mut plugin.scan_with_observer(path, Some(&observer)).unwrap());
+        }
+
+        assert_eq!(result_names(&results), vec!["inner.bin", "outer.bin"]);
+        assert_eq!(observer.found.load(Ordering::SeqCst), 2);
+        // The root, the Downloads directory and the two files, each walked once
+        assert_eq!(observer.scanned.load(Ordering::SeqCst), 4);
+    }
+
+    /// std adds the `\\?\` prefix to long paths itself, so plain calls work on them
+    #[cfg(windows)]
+    #[test]
+    fn test_paths_longer_than_max_path_are_found() {
+        let temp_dir = TempDir::new("long_path_test").unwrap();
+        let root = dunce::canonicalize(temp_dir.path()).unwrap();
+        let deep = (0..20).fold(root.clone(), |dir, _| dir.join("node_modules_package"));
+        let file = deep.join("bundle.js");
+        assert!(file.as_os_str().len() > 260);
+
+        fs::create_dir_all(&deep).unwrap();
+        create_file_with_size(&file, 2).unwrap();
+
+        let mut plugin = LargeFilePlugin::new();
+        let mut settings = create_test_settings(true, "1MB", true, None);
+        let state_dir = TempDir::new("state_test").unwrap();
+        settings.manifest_path = Some(state_dir.path().join("last-run.json"));
+        plugin.configure(&settings).unwrap();
+
+        let observer = CountingObserver::default();
+        let results = plugin.scan_with_observer(&root, Some(&observer)).unwrap();
+
+        assert_eq!(result_names(&results), vec!["bundle.js"]);
+        assert_eq!(results[0].size, 2 * 1024 * 1024);
+        assert_eq!(observer.found.load(Ordering::SeqCst), 1);
+
+        let report = plugin.clean(results).unwrap();
+        assert_eq!(report.items_cleaned, 1);
+        assert!(!file.exists());
+    }
+
+    #[test]
+    fn test_scan_observer_callbacks() {
+        let temp_dir = setup_size_window_directory();
+        let mut plugin = LargeFilePlugin::new();
+
+        let settings = create_test_settings(true, "4MB", false, None);
+        plugin.configure(&settings).unwrap();
+
+        let observer = CountingObserver::default();
+        let results = plugin
+            .scan_with_observer(temp_dir.path(), Some(&observer))
+            .unwrap();
+
+        // Three files plus the scan root itself
+        assert_eq!(observer.started.load(Ordering::SeqCst), 4);
+        assert_eq!(observer.scanned.load(Ordering::SeqCst), 4);
+        assert_eq!(observer.found.load(Ordering::SeqCst), results.len());
+        assert_eq!(observer.found.load(Ordering::SeqCst), 2);
+        assert_eq!(observer.finished.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_scan_without_observer() {
+        let temp_dir = setup_size_window_directory();
+        let mut plugin = LargeFilePlugin::new();
+
+        let settings = create_test_settings(true, "4MB", false, None);
+        plugin.configure(&settings).unwrap();
+
+        let results = plugin.scan_with_observer(temp_dir.path(), None).unwrap();
+        assert_eq!(result_names(&results), vec!["10mb.dat", "5mb.dat"]);
+    }
+
     /// Helper function to create test settings
     fn create_test_settings(
         enable_large_files: bool,
@@ -215,17 +1034,70 @@ mod tests {
     ) -> Settings {
         Settings {
             paths: vec![],
+            command: None,
             all: false,
-            ignore: None,
+            ignore: vec![],
+            exclude: vec![],
+            paths_from: None,
+            null_separated: false,
             force: false,
+            yes: false,
+            select_safe: false,
+            interactive: false,
+            quiet: false,
+            color: ColorChoice::Auto,
+            format: OutputFormat::Text,
+            print0: false,
+            max_depth: None,
+            follow_symlinks: false,
+            include_hidden: false,
+            include_remote: false,
+            detect_mime: false,
+            threads: None,
             enable_large_files,
+            enable_go: false,
+            include_go_module_cache: false,
+            enable_dotnet: false,
+            enable_largest_dirs: false,
             enable_python: false,
             enable_java: false,
             enable_javascript: false,
             enable_rust: false,
             older_than_days,
+            modified_before: None,
+            accessed_before: None,
+            risk_month_days: None,
+            risk_week_days: None,
+            risk_recent_days: None,
             size_threshold: size_threshold.to_string(),
+            max_size: None,
+            si: false,
+            max_risk: None,
+            exclude_types: vec![],
             include_git_tracked,
+            no_gitignore: false,
+            save_scan: None,
+            load_scan: None,
+            post_clean_hook: None,
+            export_script: None,
+            confirm_over: None,
+            when_full: None,
+            watch: None,
+            prune_empty_dirs: false,
+            stats: false,
+            stats_by_dir: false,
+            summary_only: false,
+            shred: false,
+            top: None,
+            per_dir_limit: None,
+            sort: None,
+            reverse: false,
+            i_know_what_im_doing: false,
+            protected_patterns: vec![],
+            case_insensitive: false,
+            case_sensitive: false,
+            ide_dirs: None,
+            manifest_path: None,
         }
     }
 }
@@ -0,0 +1,191 @@
+/// Writes the selected files as a script of delete commands for `--export-script`
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::{utils, ScanResult};
+
+/// Builds a POSIX shell script that asks before deleting each file
+///
+/// Directories, such as those the project plugins find, are deleted recursively.
+///
+/// # Arguments
+/// * `results` - The files to delete
+pub fn shell_script(results: &[ScanResult]) -> String {
+    let mut script = String::from("#!/bin/sh\n");
+    script.push_str(&header("#", results));
+
+    for result in results {
+        let flags = if is_dir(&result.path) { "-ri" } else { "-i" };
+        script.push_str(&format!(
+            "rm {} -- {}  # {}\n",
+            flags,
+            shell_quote(&result.path.to_string_lossy()),
+            utils::format_size(result.size)
+        ));
+    }
+
+    script
+}
+
+/// Builds a PowerShell script that asks before deleting each file
+///
+/// Directories are deleted recursively.
+///
+/// # Arguments
+/// * `results` - The files to delete
+pub fn powershell_script(results: &[ScanResult]) -> String {
+    let mut script = header("#", results);
+
+    for result in results {
+        let recurse = if is_dir(&result.path) {
+            " -Recurse"
+        } else {
+            ""
+        };
+        script.push_str(&format!(
+            "Remove-Item -LiteralPath {}{} -Confirm  # {}\n",
+            powershell_quote(&result.path.to_string_lossy()),
+            recurse,
+            utils::format_size(result.size)
+        ));
+    }
+
+    script
+}
+
+/// Writes the delete script for the current platform to a file
+///
+/// A PowerShell script is written on Windows and a shell script elsewhere.
+///
+/// # Arguments
+/// * `path`    - The file to write the script to
+/// * `results` - The files to delete
+pub fn export_script(path: &Path, results: &[ScanResult]) -> io::Result<()> {
+    let script = if cfg!(windows) {
+        powershell_script(results)
+    } else {
+        shell_script(results)
+    };
+
+    fs::write(path, script)?;
+    make_executable(path)
+}
+
+/// Check if a result is a directory itself, not a link to one
+fn is_dir(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_dir())
+}
+
+/// Comment lines describing what the script deletes
+fn header(comment: &str, results: &[ScanResult]) -> String {
+    let total: u64 = results.iter().map(|r| r.size).sum();
+    format!(
+        "{} Generated by sweep: {} files, {} in total\n{} Review the list before running it\n\n",
+        comment,
+        results.len(),
+        utils::format_size(total),
+        comment
+    )
+}
+
+/// Quotes a string for a POSIX shell
+///
+/// Single quotes keep everything literal, so only single quotes themselves
+/// need escaping, by closing the quote and adding an escaped one.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Quotes a string for PowerShell, where single quotes are escaped by doubling them
+fn powershell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::tests::result;
+
+    #[test]
+    fn test_shell_script_escaping() {
+        let results = vec![
+            result("/data/my movies/holiday.mkv", 2048),
+            result("/data/it's \"quoted\".bin", 2048),
+            result("/data/$HOME `date`.log", 2048),
+        ];
+
+        let script = shell_script(&results);
+        let commands: Vec<&str> = script.lines().filter(|l| l.starts_with("rm")).collect();
+
+        assert!(script.starts_with("#!/bin/sh\n# Generated by sweep: 3 files, 6.00 KB in total"));
+        assert_eq!(
+            commands,
+            vec![
+                "rm -i -- '/data/my movies/holiday.mkv'  # 2.00 KB",
+                "rm -i -- '/data/it'\\''s \"quoted\".bin'  # 2.00 KB",
+                "rm -i -- '/data/$HOME `date`.log'  # 2.00 KB",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_powershell_script_escaping() {
+        let results = vec![result("C:\\Users\\me\\it's here.iso", 2048)];
+
+        let script = powershell_script(&results);
+        assert!(script.contains(
+            "Remove-Item -LiteralPath 'C:\\Users\\me\\it''s here.iso' -Confirm  # 2.00 KB\n"
+        ));
+    }
+
+    #[test]
+    fn test_directories_are_deleted_recursively() {
+        let temp_dir = tempdir::TempDir::new("script_test").unwrap();
+        let vendor = temp_dir.path().join("vendor");
+        fs::create_dir(&vendor).unwrap();
+        let results = vec![result(&vendor, 2048)];
+
+        assert!(shell_script(&results).contains(&format!(
+            "rm -ri -- {}  # 2.00 KB\n",
+            shell_quote(vendor.to_str().unwrap())
+        )));
+        assert!(powershell_script(&results).contains(&format!(
+            "Remove-Item -LiteralPath {} -Recurse -Confirm  # 2.00 KB\n",
+            powershell_quote(vendor.to_str().unwrap())
+        )));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exported_script_runs() {
+        let temp_dir = tempdir::TempDir::new("script_test").unwrap();
+        let file = temp_dir.path().join("it's a file.bin");
+        fs::write(&file, "data").unwrap();
+
+        let script = temp_dir.path().join("clean.sh");
+        export_script(&script, &[result(&file, 2048)]).unwrap();
+
+        // `rm -i` reads the answer from stdin
+        let mut child = std::process::Command::new(&script)
+            .stdin(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+        io::Write::write_all(child.stdin.as_mut().unwrap(), b"y\n").unwrap();
+
+        assert!(child.wait().unwrap().success());
+        assert!(!file.exists());
+    }
+}
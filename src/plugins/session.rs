@@ -0,0 +1,119 @@
+/// Saves scan results to a file so they can be reviewed later without rescanning
+use super::{PluginError, ScanResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Scan results of one plugin, along with where and when they were found
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanSession {
+    pub plugin: String,
+    pub roots: Vec<PathBuf>,
+    pub created: SystemTime,
+    pub results: Vec<ScanResult>,
+}
+
+impl ScanSession {
+    /// Create a session for results that were just scanned
+    pub fn new(plugin: &str, roots: &[PathBuf], results: Vec<ScanResult>) -> Self {
+        ScanSession {
+            plugin: plugin.to_string(),
+            roots: roots.to_vec(),
+            created: SystemTime::now(),
+            results,
+        }
+    }
+
+    /// Write the session to a JSON file
+    pub fn save(&self, path: &Path) -> Result<(), PluginError> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| {
+            PluginError::Configuration(format!("Failed to serialize scan results: {}", e))
+        })?;
+
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Read a session from a JSON file written by `save`
+    pub fn load(path: &Path) -> Result<ScanSession, PluginError> {
+        let json = fs::read_to_string(path)?;
+
+        serde_json::from_str(&json).map_err(|e| {
+            PluginError::Configuration(format!("Invalid scan file {}: {}", path.display(), e))
+        })
+    }
+}
+
+/// Lists loaded results whose file on disk no longer matches the saved scan
+///
+/// # Returns
+/// A warning for each result that no longer exists or changed size
+pub fn stale_results(results: &[ScanResult]) -> Vec<String> {
+    results
+        .iter()
+        .filter_map(|result| match fs::symlink_metadata(&result.path) {
+            Err(_) => Some(format!("{} no longer exists", result.path.display())),
+            Ok(metadata) if metadata.len() != result.size && !metadata.file_type().is_symlink() => {
+                Some(format!(
+                    "{} changed size since it was scanned",
+                    result.path.display()
+                ))
+            }
+            Ok(_) => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::tests::result;
+    use tempdir::TempDir;
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let temp_dir = TempDir::new("session_test").unwrap();
+        let session = ScanSession::new(
+            "large-files",
+            &[temp_dir.path().to_path_buf()],
+            vec![
+                result(temp_dir.path().join("a.dat"), 1024),
+                result(temp_dir.path().join("b.dat"), 2048),
+            ],
+        );
+
+        let file = temp_dir.path().join("scan.json");
+        session.save(&file).unwrap();
+
+        assert_eq!(ScanSession::load(&file).unwrap(), session);
+    }
+
+    #[test]
+    fn test_load_invalid_file() {
+        let temp_dir = TempDir::new("session_test").unwrap();
+        let file = temp_dir.path().join("scan.json");
+        fs::write(&file, "not json").unwrap();
+
+        assert!(ScanSession::load(&file).is_err());
+        assert!(ScanSession::load(&temp_dir.path().join("missing.json")).is_err());
+    }
+
+    #[test]
+    fn test_stale_results() {
+        let temp_dir = TempDir::new("session_test").unwrap();
+        fs::write(temp_dir.path().join("same.dat"), vec![0u8; 16]).unwrap();
+        fs::write(temp_dir.path().join("grown.dat"), vec![0u8; 32]).unwrap();
+
+        let results = vec![
+            result(temp_dir.path().join("same.dat"), 16),
+            result(temp_dir.path().join("grown.dat"), 16),
+            result(temp_dir.path().join("gone.dat"), 16),
+        ];
+
+        let warnings = stale_results(&results);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("grown.dat") && warnings[0].contains("changed size"));
+        assert!(warnings[1].contains("gone.dat") && warnings[1].contains("no longer exists"));
+    }
+}
@@ -0,0 +1,205 @@
+/// Orders scan results, shared by the interactive selector and `--sort`
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use super::ScanResult;
+
+/// The key results are ordered by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    /// Largest first
+    Size,
+    /// Least recently modified first
+    Age,
+    /// Riskiest first
+    Risk,
+    /// By file name
+    Name,
+}
+
+impl FromStr for SortBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "size" => Ok(SortBy::Size),
+            "age" => Ok(SortBy::Age),
+            "risk" => Ok(SortBy::Risk),
+            "name" => Ok(SortBy::Name),
+            _ => Err(format!(
+                "Invalid sort key '{}', expected size, age, name or risk",
+                s
+            )),
+        }
+    }
+}
+
+impl SortBy {
+    /// Every sort key, in the order the interactive selector cycles through them
+    pub const ALL: [SortBy; 4] = [SortBy::Size, SortBy::Age, SortBy::Risk, SortBy::Name];
+
+    /// Whether the order of [`SortBy::compare`] is ascending, which only holds for names
+    ///
+    /// The other keys put the results most worth deleting first: the largest,
+    /// the oldest and the riskiest.
+    pub fn is_ascending(self) -> bool {
+        self == SortBy::Name
+    }
+
+    /// Compares two results by this key
+    ///
+    /// Results that are equal by the key are ordered by size and then by path,
+    /// so the order never depends on the order the results were found in.
+    pub fn compare(self, a: &ScanResult, b: &ScanResult) -> Ordering {
+        match self {
+            SortBy::Size => largest_first(a, b),
+            SortBy::Age => oldest_first(a, b),
+            SortBy::Risk => riskiest_first(a, b),
+            SortBy::Name => by_name(a, b),
+        }
+    }
+}
+
+/// Orders results by size, largest first, and results of equal size by path
+pub fn largest_first(a: &ScanResult, b: &ScanResult) -> Ordering {
+    b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path))
+}
+
+/// Orders results by modification time, oldest first
+///
+/// Results without a known modification time come last.
+pub fn oldest_first(a: &ScanResult, b: &ScanResult) -> Ordering {
+    match (a.last_modified, b.last_modified) {
+        (Some(a_time), Some(b_time)) => a_time.cmp(&b_time),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+    .then_with(|| largest_first(a, b))
+}
+
+/// Orders results by risk level, critical first, then by size
+pub fn riskiest_first(a: &ScanResult, b: &ScanResult) -> Ordering {
+    b.risk_level
+        .cmp(&a.risk_level)
+        .then_with(|| largest_first(a, b))
+}
+
+/// Orders results by file name, then by the full path
+pub fn by_name(a: &ScanResult, b: &ScanResult) -> Ordering {
+    let name = |result: &ScanResult| result.path.file_name().map(|name| name.to_os_string());
+    name(a).cmp(&name(b)).then_with(|| a.path.cmp(&b.path))
+}
+
+/// Sorts results by a key, in the opposite order if `reverse` is set
+pub fn sort_results(results: &mut [ScanResult], sort_by: SortBy, reverse: bool) {
+    if reverse {
+        results.sort_by(|a, b| sort_by.compare(b, a));
+    } else {
+        results.sort_by(|a, b| sort_by.compare(a, b));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::tests::risky_result;
+    use crate::plugins::RiskLevel;
+    use std::time::{Duration, SystemTime};
+
+    fn result(path: &str, size: u64, risk_level: RiskLevel, days_old: Option<u64>) -> ScanResult {
+        ScanResult {
+            last_modified: days_old
+                .map(|days| SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60)),
+            ..risky_result(path, size, risk_level)
+        }
+    }
+
+    fn sample() -> Vec<ScanResult> {
+        vec![
+            result("/b/video.mp4", 300, RiskLevel::Low, Some(10)),
+            result("/a/config.toml", 10, RiskLevel::Critical, Some(1)),
+            result("/c/archive.zip", 300, RiskLevel::Medium, None),
+            result("/a/backup.tar", 50, RiskLevel::Medium, Some(400)),
+        ]
+    }
+
+    fn sorted(sort_by: SortBy, reverse: bool) -> Vec<String> {
+        let mut results = sample();
+        sort_results(&mut results, sort_by, reverse);
+        results
+            .iter()
+            .map(|r| r.path.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_by_size() {
+        assert_eq!(
+            sorted(SortBy::Size, false),
+            [
+                "/b/video.mp4",
+                "/c/archive.zip",
+                "/a/backup.tar",
+                "/a/config.toml"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_by_age() {
+        assert_eq!(
+            sorted(SortBy::Age, false),
+            [
+                "/a/backup.tar",
+                "/b/video.mp4",
+                "/a/config.toml",
+                "/c/archive.zip"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_by_risk() {
+        assert_eq!(
+            sorted(SortBy::Risk, false),
+            [
+                "/a/config.toml",
+                "/c/archive.zip",
+                "/a/backup.tar",
+                "/b/video.mp4"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_by_name() {
+        assert_eq!(
+            sorted(SortBy::Name, false),
+            [
+                "/c/archive.zip",
+                "/a/backup.tar",
+                "/a/config.toml",
+                "/b/video.mp4"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reverse_flips_the_order() {
+        for sort_by in [SortBy::Size, SortBy::Age, SortBy::Risk, SortBy::Name] {
+            let mut reversed = sorted(sort_by, true);
+            reversed.reverse();
+            assert_eq!(reversed, sorted(sort_by, false), "{:?}", sort_by);
+        }
+    }
+
+    #[test]
+    fn test_parse_sort_key() {
+        assert_eq!("size".parse(), Ok(SortBy::Size));
+        assert_eq!("Age".parse(), Ok(SortBy::Age));
+        assert_eq!("RISK".parse(), Ok(SortBy::Risk));
+        assert_eq!("name".parse(), Ok(SortBy::Name));
+        assert!("date".parse::<SortBy>().is_err());
+    }
+}
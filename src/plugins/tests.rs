@@ -1,5 +1,28 @@
 use super::*;
+use crate::output::{ColorChoice, OutputFormat};
 use crate::settings::Settings;
+use std::path::PathBuf;
+
+/// A low risk result without a description, for tests across the plugins
+pub(crate) fn result(path: impl Into<PathBuf>, size: u64) -> ScanResult {
+    risky_result(path, size, RiskLevel::Low)
+}
+
+/// Like [`result`], with the given risk level
+pub(crate) fn risky_result(
+    path: impl Into<PathBuf>,
+    size: u64,
+    risk_level: RiskLevel,
+) -> ScanResult {
+    ScanResult {
+        path: path.into(),
+        size,
+        description: String::new(),
+        risk_level,
+        last_modified: None,
+        mime_type: None,
+    }
+}
 
 /// Mock plugin for testing
 #[derive(Debug)]
@@ -53,17 +76,70 @@ fn test_plugin_basics() {
     // Test configuration
     let settings = Settings {
         paths: vec![],
+        command: None,
         all: false,
-        ignore: None,
+        ignore: vec![],
+        exclude: vec![],
+        paths_from: None,
+        null_separated: false,
         force: false,
+        yes: false,
+        select_safe: false,
+        interactive: false,
+        quiet: false,
+        color: ColorChoice::Auto,
+        format: OutputFormat::Text,
+        print0: false,
+        max_depth: None,
+        follow_symlinks: false,
+        include_hidden: false,
+        include_remote: false,
+        detect_mime: false,
+        threads: None,
         enable_large_files: false,
+        enable_go: false,
+        include_go_module_cache: false,
+        enable_dotnet: false,
+        enable_largest_dirs: false,
         enable_python: false,
         enable_java: false,
         enable_javascript: false,
         enable_rust: false,
         older_than_days: None,
+        modified_before: None,
+        accessed_before: None,
+        risk_month_days: None,
+        risk_week_days: None,
+        risk_recent_days: None,
         size_threshold: "100MB".to_string(),
+        max_size: None,
+        si: false,
+        max_risk: None,
+        exclude_types: vec![],
         include_git_tracked: false,
+        no_gitignore: false,
+        save_scan: None,
+        load_scan: None,
+        post_clean_hook: None,
+        export_script: None,
+        confirm_over: None,
+        when_full: None,
+        watch: None,
+        prune_empty_dirs: false,
+        stats: false,
+        stats_by_dir: false,
+        summary_only: false,
+        shred: false,
+        top: None,
+        per_dir_limit: None,
+        sort: None,
+        reverse: false,
+        i_know_what_im_doing: false,
+        protected_patterns: vec![],
+        case_insensitive: false,
+        case_sensitive: false,
+        ide_dirs: None,
+        manifest_path: None,
     };
 
     assert!(plugin.configure(&settings).is_ok());
@@ -81,6 +157,8 @@ fn test_scan_result() {
         size: 1024 * 1024 * 100, // 100MB
         description: "Large test file".to_string(),
         risk_level: RiskLevel::Low,
+        last_modified: None,
+        mime_type: None,
     };
 
     assert_eq!(result.size, 104857600);
@@ -91,28 +169,144 @@ fn test_scan_result() {
 fn test_cleanup_report() {
     let report = CleanupReport {
         items_cleaned: 5,
+        items_skipped: 1,
         space_freed: 1024 * 1024 * 500, // 500MB
+        dirs_pruned: 0,
         errors: vec![],
     };
 
     assert_eq!(report.items_cleaned, 5);
+    assert_eq!(report.items_skipped, 1);
     assert_eq!(report.space_freed, 524288000);
     assert!(report.errors.is_empty());
 }
 
+#[test]
+fn test_cleanup_report_merge() {
+    let first_error = CleanupError {
+        path: std::path::PathBuf::from("/test/first"),
+        message: "first error".to_string(),
+    };
+    let second_error = CleanupError {
+        path: std::path::PathBuf::from("/test/second"),
+        message: "second error".to_string(),
+    };
+
+    let first = CleanupReport {
+        items_cleaned: 2,
+        items_skipped: 1,
+        space_freed: 1024,
+        dirs_pruned: 0,
+        errors: vec![first_error.clone()],
+    };
+    let second = CleanupReport {
+        items_cleaned: 3,
+        items_skipped: 2,
+        space_freed: 2048,
+        dirs_pruned: 4,
+        errors: vec![second_error.clone()],
+    };
+
+    let merged = first.merge(second);
+    assert_eq!(merged.items_cleaned, 5);
+    assert_eq!(merged.items_skipped, 3);
+    assert_eq!(merged.space_freed, 3072);
+    assert_eq!(merged.dirs_pruned, 4);
+    assert_eq!(merged.errors, vec![first_error, second_error]);
+
+    let merged = merged.merge(CleanupReport::default());
+    assert_eq!(merged.items_cleaned, 5);
+    assert_eq!(merged.space_freed, 3072);
+}
+
+#[test]
+fn test_cleanup_report_display() {
+    let report = CleanupReport {
+        items_cleaned: 5,
+        items_skipped: 0,
+        space_freed: 1024 * 1024 * 500,
+        dirs_pruned: 0,
+        errors: vec![],
+    };
+    assert_eq!(report.to_string(), "5 items cleaned, 500 MB freed");
+
+    let report = CleanupReport {
+        items_cleaned: 1,
+        items_skipped: 2,
+        space_freed: 1024,
+        dirs_pruned: 3,
+        errors: vec![CleanupError {
+            path: std::path::PathBuf::from("/a"),
+            message: "Permission denied".to_string(),
+        }],
+    };
+    let display = report.to_string();
+    assert!(display.starts_with(
+        "1 items cleaned, 1.00 KB freed, 2 skipped, 3 empty directories pruned, 1 errors"
+    ));
+    assert!(display.contains("/a: Permission denied"));
+}
+
 #[test]
 fn test_risk_levels() {
     assert_ne!(RiskLevel::Safe, RiskLevel::Critical);
     assert_eq!(RiskLevel::Medium, RiskLevel::Medium);
 
-    // Test ordering (for potential sorting)
-    let levels = [
+    // Test ordering
+    assert!(RiskLevel::Critical > RiskLevel::Safe);
+    assert!(RiskLevel::Low < RiskLevel::Medium);
+
+    let mut levels = vec![
         RiskLevel::Critical,
         RiskLevel::Safe,
         RiskLevel::High,
         RiskLevel::Low,
         RiskLevel::Medium,
     ];
+    levels.sort();
+
+    assert_eq!(
+        levels,
+        vec![
+            RiskLevel::Safe,
+            RiskLevel::Low,
+            RiskLevel::Medium,
+            RiskLevel::High,
+            RiskLevel::Critical,
+        ]
+    );
+}
+
+#[test]
+fn test_risk_level_from_str() {
+    assert_eq!("safe".parse::<RiskLevel>(), Ok(RiskLevel::Safe));
+    assert_eq!("Medium".parse::<RiskLevel>(), Ok(RiskLevel::Medium));
+    assert_eq!("CRITICAL".parse::<RiskLevel>(), Ok(RiskLevel::Critical));
+    assert!("dangerous".parse::<RiskLevel>().is_err());
 
-    assert_eq!(levels.len(), 5);
+    // Short aliases, in any case
+    assert_eq!("low".parse::<RiskLevel>(), Ok(RiskLevel::Low));
+    assert_eq!("High".parse::<RiskLevel>(), Ok(RiskLevel::High));
+    assert_eq!("med".parse::<RiskLevel>(), Ok(RiskLevel::Medium));
+    assert_eq!("MED".parse::<RiskLevel>(), Ok(RiskLevel::Medium));
+    assert_eq!("crit".parse::<RiskLevel>(), Ok(RiskLevel::Critical));
+    assert_eq!("Crit".parse::<RiskLevel>(), Ok(RiskLevel::Critical));
+
+    let err = "severe".parse::<RiskLevel>().unwrap_err();
+    assert_eq!(
+        err,
+        "Invalid risk level 'severe', expected one of safe, low, medium, high or critical"
+    );
+    assert!("".parse::<RiskLevel>().is_err());
+    assert!("medi".parse::<RiskLevel>().is_err());
+}
+
+#[test]
+fn test_risk_level_as_str_round_trips() {
+    let names: Vec<&str> = RiskLevel::ALL.iter().map(RiskLevel::as_str).collect();
+    assert_eq!(names, ["safe", "low", "medium", "high", "critical"]);
+
+    for level in RiskLevel::ALL {
+        assert_eq!(level.as_str().parse::<RiskLevel>(), Ok(level));
+    }
 }
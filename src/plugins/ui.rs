@@ -1,11 +1,22 @@
+use super::sort;
+pub use super::sort::SortBy;
 use super::{RiskLevel, ScanResult};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+        MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use std::io::{self, stdout};
-use std::time::Duration;
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, stdout, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant, SystemTime};
 
 /// Guard that ensures terminal is cleaned up on panic or drop
 struct TerminalCleanupGuard;
@@ -17,7 +28,7 @@ impl TerminalCleanupGuard {
         std::panic::set_hook(Box::new(move |panic_info| {
             // Clean up terminal before panicking
             let _ = disable_raw_mode();
-            let _ = execute!(stdout(), LeaveAlternateScreen);
+            let _ = execute!(stdout(), DisableMouseCapture, LeaveAlternateScreen);
             // Call the original panic hook
             original_hook(panic_info);
         }));
@@ -29,31 +40,62 @@ impl Drop for TerminalCleanupGuard {
     fn drop(&mut self) {
         // Ensure terminal is cleaned up when guard is dropped
         let _ = disable_raw_mode();
-        let _ = execute!(stdout(), LeaveAlternateScreen);
+        let _ = execute!(stdout(), DisableMouseCapture, LeaveAlternateScreen);
     }
 }
 
 use tui::{
     backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Span, Spans},
     widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
     Frame, Terminal,
 };
 
+/// Number of items to move with PgUp/PgDn
+const PAGE_SIZE: usize = 10;
+
+/// Columns taken by the list borders and the highlight symbol
+const LIST_CHROME_WIDTH: usize = 4;
+
+/// Number of rows the mouse wheel moves per step
+const SCROLL_LINES: usize = 3;
+
+/// How long a status message stays in the footer if no key is pressed
+const STATUS_TIMEOUT: Duration = Duration::from_secs(3);
+
 #[derive(Debug, Clone)]
 pub struct SelectableItem {
     pub scan_result: ScanResult,
     pub selected: bool,
+    /// Whether the result is a whole directory, such as a `target` directory
+    pub is_dir: bool,
 }
 
+/// How the items are laid out in the list
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum SortBy {
-    Size,
-    Age,
-    Risk,
-    Name,
+pub enum View {
+    /// One row per file
+    Flat,
+    /// Files grouped under a header row for their parent directory
+    Tree,
+}
+
+/// Files in the same parent directory, for the tree view
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DirGroup {
+    dir: PathBuf,
+    /// Indices into `InteractiveSelector::items`, in list order
+    items: Vec<usize>,
+    total_size: u64,
+}
+
+/// A visible row of the list
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Row {
+    Dir(DirGroup),
+    Item(usize),
 }
 
 #[derive(Debug)]
@@ -61,7 +103,20 @@ pub struct InteractiveSelector {
     items: Vec<SelectableItem>,
     list_state: ListState,
     sort_by: SortBy,
+    /// Whether the items are in ascending order of the sort key, e.g. smallest first
+    sort_ascending: bool,
     show_help: bool,
+    /// The highlighted entry of the sort menu, if it is open
+    sort_menu: Option<usize>,
+    view: View,
+    /// Directories whose files are hidden in the tree view
+    collapsed: HashSet<PathBuf>,
+    /// Whether the screen is out of date and has to be drawn again
+    needs_redraw: bool,
+    /// The result of the last action, shown in the footer until the next key press or it expires
+    status: Option<(String, Instant)>,
+    /// Where the file list was last drawn, to find the row under the mouse
+    list_area: Cell<Rect>,
 }
 
 impl InteractiveSelector {
@@ -69,13 +124,14 @@ impl InteractiveSelector {
         let mut items: Vec<SelectableItem> = scan_results
             .into_iter()
             .map(|result| SelectableItem {
+                is_dir: fs::symlink_metadata(&result.path).is_ok_and(|m| m.is_dir()),
                 scan_result: result,
                 selected: false,
             })
             .collect();
 
         // Default sort by size (largest first)
-        items.sort_by(|a, b| b.scan_result.size.cmp(&a.scan_result.size));
+        items.sort_by(|a, b| sort::largest_first(&a.scan_result, &b.scan_result));
 
         let mut list_state = ListState::default();
         if !items.is_empty() {
@@ -86,7 +142,14 @@ impl InteractiveSelector {
             items,
             list_state,
             sort_by: SortBy::Size,
+            sort_ascending: SortBy::Size.is_ascending(),
             show_help: false,
+            sort_menu: None,
+            view: View::Flat,
+            collapsed: HashSet::new(),
+            needs_redraw: true,
+            status: None,
+            list_area: Cell::new(Rect::default()),
         }
     }
 
@@ -95,13 +158,24 @@ impl InteractiveSelector {
             return Ok(vec![]);
         }
 
+        // Raw mode needs a terminal on both ends, which CI and plain SSH sessions lack.
+        // Keys are read from /dev/tty when stdin is redirected, e.g. by `--paths-from -`.
+        let has_input =
+            io::stdin().is_terminal() || (cfg!(unix) && std::fs::File::open("/dev/tty").is_ok());
+        if !has_input || !io::stdout().is_terminal() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "no terminal available",
+            ));
+        }
+
         // Create cleanup guard to ensure terminal is restored even on panic
         let _guard = TerminalCleanupGuard::new();
 
         // Setup terminal
         enable_raw_mode()?;
         let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen)?;
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
         let backend = CrosstermBackend::new(stdout);
         let mut terminal = Terminal::new(backend)?;
 
@@ -109,7 +183,11 @@ impl InteractiveSelector {
 
         // Restore terminal (guard will also handle this if we panic)
         disable_raw_mode()?;
-        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        execute!(
+            terminal.backend_mut(),
+            DisableMouseCapture,
+            LeaveAlternateScreen
+        )?;
         terminal.show_cursor()?;
 
         result
@@ -120,67 +198,189 @@ impl InteractiveSelector {
         terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     ) -> io::Result<Vec<ScanResult>> {
         loop {
-            terminal.draw(|f| self.draw(f))?;
+            // Drawing resizes the terminal buffer, which recomputes the path widths
+            if self.needs_redraw {
+                terminal.draw(|f| self.draw(f))?;
+                self.needs_redraw = false;
+            }
 
             if event::poll(Duration::from_millis(250))? {
-                if let Event::Key(key) = event::read()? {
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => {
-                            return Ok(vec![]); // User cancelled
-                        }
-                        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                            return Ok(vec![]); // User cancelled with Ctrl+C
-                        }
-                        KeyCode::Enter => {
-                            // Return selected items
-                            return Ok(self.get_selected_items());
-                        }
-                        KeyCode::Char(' ') => {
-                            self.toggle_current_item();
-                        }
-                        KeyCode::Char('a') => {
-                            self.toggle_all_items();
-                        }
-                        KeyCode::Char('s') => {
-                            self.cycle_sort();
-                        }
-                        KeyCode::Char('h') | KeyCode::Char('?') => {
-                            self.show_help = !self.show_help;
-                        }
-                        KeyCode::Up => {
-                            self.previous_item();
-                        }
-                        KeyCode::Down => {
-                            self.next_item();
-                        }
-                        KeyCode::Home => {
-                            if !self.items.is_empty() {
-                                self.list_state.select(Some(0));
-                            }
-                        }
-                        KeyCode::End => {
-                            if !self.items.is_empty() {
-                                self.list_state.select(Some(self.items.len() - 1));
-                            }
-                        }
-                        KeyCode::PageUp => {
-                            self.page_up();
-                        }
-                        KeyCode::PageDown => {
-                            self.page_down();
-                        }
-                        _ => {}
+                if let Some(result) = self.handle_event(event::read()?) {
+                    return Ok(result);
+                }
+            } else {
+                self.expire_status(Instant::now());
+            }
+        }
+    }
+
+    /// Apply a terminal event, returning the final selection if the UI should close
+    fn handle_event(&mut self, event: Event) -> Option<Vec<ScanResult>> {
+        match event {
+            Event::Key(key) => {
+                self.needs_redraw = true;
+                self.status = None;
+                self.handle_key(key)
+            }
+            Event::Resize(_, _) => {
+                self.needs_redraw = true;
+                None
+            }
+            Event::Mouse(mouse) => {
+                self.handle_mouse(mouse);
+                None
+            }
+        }
+    }
+
+    /// Apply a key press while the sort menu is open
+    fn handle_sort_menu_key(&mut self, key: KeyEvent) {
+        let Some(highlighted) = self.sort_menu else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.sort_menu = Some(highlighted.saturating_sub(1));
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.sort_menu = Some((highlighted + 1).min(SortBy::ALL.len() - 1));
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                self.set_sort(SortBy::ALL[highlighted]);
+                self.sort_menu = None;
+            }
+            KeyCode::Char('d') | KeyCode::Left | KeyCode::Right => {
+                self.toggle_sort_direction();
+            }
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('S') => {
+                self.sort_menu = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Apply a mouse event: a click moves the cursor to the row under the mouse, the wheel scrolls
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if self.show_help || self.sort_menu.is_some() {
+            return;
+        }
+
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let area = self.list_area.get();
+                let offset = list_offset(self.list_state.selected(), inner_height(area));
+                if let Some(row) = row_at(area, offset, mouse.column, mouse.row) {
+                    if row < self.row_count() {
+                        self.list_state.select(Some(row));
+                        self.needs_redraw = true;
                     }
                 }
             }
+            MouseEventKind::ScrollDown => {
+                self.move_down(SCROLL_LINES);
+                self.needs_redraw = true;
+            }
+            MouseEventKind::ScrollUp => {
+                self.move_up(SCROLL_LINES);
+                self.needs_redraw = true;
+            }
+            _ => {}
         }
     }
 
+    /// Apply a key press, returning the final selection if the UI should close
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Vec<ScanResult>> {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        if self.sort_menu.is_some() && !(control && key.code == KeyCode::Char('c')) {
+            self.handle_sort_menu_key(key);
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                return Some(vec![]); // User cancelled
+            }
+            KeyCode::Char('c') if control => {
+                return Some(vec![]); // User cancelled with Ctrl+C
+            }
+            KeyCode::Enter => {
+                // Return selected items
+                return Some(self.get_selected_items());
+            }
+            KeyCode::Char('d') if control => {
+                self.half_page_down();
+            }
+            KeyCode::Char('u') if control => {
+                self.half_page_up();
+            }
+            KeyCode::Char(' ') => {
+                self.toggle_current_item();
+            }
+            KeyCode::Char('a') => {
+                self.toggle_all_items();
+            }
+            KeyCode::Char('i') => {
+                self.invert_selection();
+            }
+            KeyCode::Char('v') => {
+                self.toggle_current_risk_level();
+            }
+            KeyCode::Char('s') => {
+                self.cycle_sort();
+            }
+            KeyCode::Char('S') => {
+                self.open_sort_menu();
+            }
+            KeyCode::Char('t') => {
+                self.toggle_view();
+            }
+            KeyCode::Char('o') => {
+                self.open_current_dir();
+            }
+            KeyCode::Left => {
+                self.collapse_current_dir();
+            }
+            KeyCode::Right => {
+                self.expand_current_dir();
+            }
+            KeyCode::Char('h') | KeyCode::Char('?') => {
+                self.show_help = !self.show_help;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.previous_item();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.next_item();
+            }
+            KeyCode::Home | KeyCode::Char('g') => {
+                self.first_item();
+            }
+            KeyCode::End | KeyCode::Char('G') => {
+                self.last_item();
+            }
+            KeyCode::PageUp => {
+                self.page_up();
+            }
+            KeyCode::PageDown => {
+                self.page_down();
+            }
+            _ => {}
+        }
+
+        None
+    }
+
     fn draw(&self, f: &mut Frame<CrosstermBackend<io::Stdout>>) {
         if self.show_help {
             self.draw_help(f);
             return;
         }
+        if let Some(highlighted) = self.sort_menu {
+            self.draw_sort_menu(f, highlighted);
+            return;
+        }
 
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -211,19 +411,24 @@ impl InteractiveSelector {
             .sum::<u64>();
 
         let size_str = super::utils::format_size(total_size);
-        let sort_indicator = match self.sort_by {
-            SortBy::Size => "Size ↓",
-            SortBy::Age => "Age",
-            SortBy::Risk => "Risk",
-            SortBy::Name => "Name",
+        let sort_indicator = format!(
+            "{:?} {}",
+            self.sort_by,
+            if self.sort_ascending { "↑" } else { "↓" }
+        );
+
+        let view_indicator = match self.view {
+            View::Flat => "Flat",
+            View::Tree => "Tree",
         };
 
         let header_text = format!(
-            "Large Files - Selected: {}/{} ({}) - Sort: {} - Press 'h' for help",
+            "Large Files - Selected: {}/{} ({}) - Sort: {} - View: {} - Press 'h' for help",
             selected_count,
             self.items.len(),
             size_str,
-            sort_indicator
+            sort_indicator,
+            view_indicator
         );
 
         let header = Paragraph::new(header_text)
@@ -238,34 +443,16 @@ impl InteractiveSelector {
     }
 
     fn draw_file_list(&self, f: &mut Frame<CrosstermBackend<io::Stdout>>, area: tui::layout::Rect) {
+        self.list_area.set(area);
+
+        // The borders and the highlight symbol take up part of each row
+        let width = (area.width as usize).saturating_sub(LIST_CHROME_WIDTH);
         let items: Vec<ListItem> = self
-            .items
+            .visible_rows()
             .iter()
-            .map(|item| {
-                let checkbox = if item.selected { "☑" } else { "☐" };
-                let risk_color = match item.scan_result.risk_level {
-                    RiskLevel::Safe => Color::Green,
-                    RiskLevel::Low => Color::Yellow,
-                    RiskLevel::Medium => Color::Magenta,
-                    RiskLevel::High => Color::Red,
-                    RiskLevel::Critical => Color::LightRed,
-                };
-
-                let size_str = super::utils::format_size(item.scan_result.size);
-                let risk_str = format!("{:?}", item.scan_result.risk_level);
-                let path_str = item.scan_result.path.to_string_lossy();
-
-                let line = Spans::from(vec![
-                    Span::raw(format!("{} ", checkbox)),
-                    Span::styled(
-                        format!("{:>8} ", size_str),
-                        Style::default().fg(Color::Cyan),
-                    ),
-                    Span::styled(format!("{:>8} ", risk_str), Style::default().fg(risk_color)),
-                    Span::raw(path_str),
-                ]);
-
-                ListItem::new(line)
+            .map(|row| match row {
+                Row::Dir(group) => self.dir_list_item(group, width),
+                Row::Item(index) => self.file_list_item(&self.items[*index], width),
             })
             .collect();
 
@@ -281,9 +468,109 @@ impl InteractiveSelector {
         f.render_stateful_widget(list, area, &mut self.list_state.clone());
     }
 
+    fn file_list_item(&self, item: &SelectableItem, width: usize) -> ListItem<'static> {
+        let checkbox = if item.selected { "☑" } else { "☐" };
+        let risk_color = match item.scan_result.risk_level {
+            RiskLevel::Safe => Color::Green,
+            RiskLevel::Low => Color::Yellow,
+            RiskLevel::Medium => Color::Magenta,
+            RiskLevel::High => Color::Red,
+            RiskLevel::Critical => Color::LightRed,
+        };
+
+        let size_str = super::utils::format_size(item.scan_result.size);
+        let risk_str = format!("{:?}", item.scan_result.risk_level);
+        let age_str = item
+            .scan_result
+            .last_modified
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .map(super::utils::format_age)
+            .unwrap_or_default();
+
+        // The tree view shows the directory in the header row already
+        let (indent, mut path_str) = match self.view {
+            View::Flat => ("", item.scan_result.path.to_string_lossy().into_owned()),
+            View::Tree => (
+                "  ",
+                item.scan_result
+                    .path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned(),
+            ),
+        };
+
+        // A trailing separator tells directories apart from files of the same name
+        if item.is_dir {
+            path_str.push(std::path::MAIN_SEPARATOR);
+        }
+
+        let columns = vec![
+            Span::raw(format!("{}{} ", indent, checkbox)),
+            Span::styled(
+                format!("{:>8} ", size_str),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::styled(format!("{:>8} ", risk_str), Style::default().fg(risk_color)),
+            Span::styled(format!("{:>8} ", age_str), Style::default().fg(Color::Gray)),
+        ];
+        let path_width = width.saturating_sub(Spans::from(columns.clone()).width());
+
+        let mut line = Spans::from(columns);
+        line.0.push(Span::raw(truncate_path(&path_str, path_width)));
+
+        ListItem::new(line)
+    }
+
+    fn dir_list_item(&self, group: &DirGroup, width: usize) -> ListItem<'static> {
+        let selected = group
+            .items
+            .iter()
+            .filter(|&&index| self.items[index].selected)
+            .count();
+        let checkbox = if selected == 0 {
+            "☐"
+        } else if selected == group.items.len() {
+            "☑"
+        } else {
+            "▣"
+        };
+        let arrow = if self.collapsed.contains(&group.dir) {
+            "▶"
+        } else {
+            "▼"
+        };
+
+        let columns = vec![
+            Span::raw(format!("{} {} ", arrow, checkbox)),
+            Span::styled(
+                format!("{:>8} ", super::utils::format_size(group.total_size)),
+                Style::default().fg(Color::Cyan),
+            ),
+        ];
+        let noun = if group.items.iter().any(|&index| self.items[index].is_dir) {
+            "items"
+        } else {
+            "files"
+        };
+        let count = format!(" ({} {})", group.items.len(), noun);
+        let dir_width = width.saturating_sub(Spans::from(columns.clone()).width() + count.len());
+        let dir = truncate_path(&group.dir.to_string_lossy(), dir_width);
+
+        let mut line = Spans::from(columns);
+        line.0.push(Span::styled(
+            dir + &count,
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+
+        ListItem::new(line)
+    }
+
     fn draw_footer(&self, f: &mut Frame<CrosstermBackend<io::Stdout>>, area: tui::layout::Rect) {
-        let footer_text =
-            "Space: Toggle | Enter: Confirm | a: Toggle All | s: Sort | q/Esc: Cancel | h: Help";
+        let footer_text = self.status_message().unwrap_or(
+            "↑↓/jk: Move | Space: Toggle | Enter: Confirm | a: All | i: Invert | v: Same Risk | s/S: Sort | t: Tree | o: Open | q: Cancel | h: Help",
+        );
         let footer = Paragraph::new(footer_text)
             .block(Block::default().borders(Borders::ALL))
             .alignment(Alignment::Center);
@@ -296,16 +583,28 @@ impl InteractiveSelector {
             "HELP - Large File Selection",
             "",
             "Navigation:",
-            "  ↑/↓         Move selection up/down",
+            "  ↑/↓, k/j    Move selection up/down",
             "  Home/End    Go to first/last item",
+            "  g/G         Go to first/last item",
             "  PgUp/PgDn   Move page up/down",
+            "  Ctrl-u/d    Move half a page up/down",
+            "  Mouse       Click a row to move to it, scroll with the wheel",
             "",
             "Selection:",
             "  Space       Toggle current item",
             "  a           Toggle all items",
+            "  i           Invert selection",
+            "  v           Toggle all items with the current item's risk level",
+            "  Space on a directory toggles all of its files",
+            "",
+            "View:",
+            "  t           Toggle between flat list and directory tree",
+            "  ←/→         Collapse/expand the current directory",
+            "  o           Open the current item's directory in the file manager",
             "",
             "Sorting:",
             "  s           Cycle sort order (Size → Age → Risk → Name)",
+            "  S           Open the sort menu to pick the key and direction",
             "",
             "Actions:",
             "  Enter       Confirm selection and proceed",
@@ -341,11 +640,65 @@ impl InteractiveSelector {
         f.render_widget(help_paragraph, area);
     }
 
+    fn draw_sort_menu(&self, f: &mut Frame<CrosstermBackend<io::Stdout>>, highlighted: usize) {
+        let mut lines = vec![Spans::from("Sort by:"), Spans::from("")];
+        for (index, sort_by) in SortBy::ALL.iter().enumerate() {
+            let marker = if index == highlighted { "► " } else { "  " };
+            let current = if *sort_by == self.sort_by {
+                " (current)"
+            } else {
+                ""
+            };
+            let line = format!("{}{:?}{}", marker, sort_by, current);
+            lines.push(if index == highlighted {
+                Spans::from(Span::styled(
+                    line,
+                    Style::default().add_modifier(Modifier::BOLD),
+                ))
+            } else {
+                Spans::from(line)
+            });
+        }
+
+        let direction = if self.sort_ascending {
+            "Ascending ↑"
+        } else {
+            "Descending ↓"
+        };
+        lines.extend([
+            Spans::from(""),
+            Spans::from(format!("Direction: {}", direction)),
+            Spans::from(""),
+            Spans::from("↑/↓: Choose key | Enter: Sort | d/←/→: Toggle direction | Esc: Close"),
+        ]);
+
+        let menu = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Sort"))
+            .wrap(Wrap { trim: true });
+
+        let area = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(100)])
+            .margin(2)
+            .split(f.size())[0];
+
+        f.render_widget(Clear, area);
+        f.render_widget(menu, area);
+    }
+
     fn toggle_current_item(&mut self) {
-        if let Some(index) = self.list_state.selected() {
-            if index < self.items.len() {
+        match self.current_row() {
+            Some(Row::Item(index)) => {
                 self.items[index].selected = !self.items[index].selected;
             }
+            Some(Row::Dir(group)) => {
+                // Select every file in the directory, or deselect them if they all are
+                let all_selected = group.items.iter().all(|&i| self.items[i].selected);
+                for index in group.items {
+                    self.items[index].selected = !all_selected;
+                }
+            }
+            None => {}
         }
     }
 
@@ -354,61 +707,107 @@ impl InteractiveSelector {
         for item in &mut self.items {
             item.selected = !all_selected;
         }
+
+        if all_selected {
+            self.set_status("Deselected all files".to_string());
+        } else {
+            self.set_status(format!("Selected all {} files", self.items.len()));
+        }
     }
 
-    fn cycle_sort(&mut self) {
-        self.sort_by = match self.sort_by {
-            SortBy::Size => SortBy::Age,
-            SortBy::Age => SortBy::Risk,
-            SortBy::Risk => SortBy::Name,
-            SortBy::Name => SortBy::Size,
+    fn invert_selection(&mut self) {
+        for item in &mut self.items {
+            item.selected = !item.selected;
+        }
+
+        let selected = self.items.iter().filter(|item| item.selected).count();
+        self.set_status(format!("Inverted the selection, {} selected", selected));
+    }
+
+    /// Select all items with the risk level of the current item, or deselect them if they already are
+    fn toggle_current_risk_level(&mut self) {
+        let risk_level = match self.current_row() {
+            Some(Row::Item(index)) => self.items[index].scan_result.risk_level,
+            _ => return,
         };
+
+        let all_selected = self
+            .items
+            .iter()
+            .filter(|item| item.scan_result.risk_level == risk_level)
+            .all(|item| item.selected);
+        let mut count = 0;
+        for item in &mut self.items {
+            if item.scan_result.risk_level == risk_level {
+                item.selected = !all_selected;
+                count += 1;
+            }
+        }
+
+        let action = if all_selected {
+            "Deselected"
+        } else {
+            "Selected"
+        };
+        self.set_status(format!(
+            "{} all {} {:?} risk files",
+            action, count, risk_level
+        ));
+    }
+
+    fn cycle_sort(&mut self) {
+        let next = SortBy::ALL
+            .iter()
+            .position(|sort_by| *sort_by == self.sort_by)
+            .map_or(0, |index| (index + 1) % SortBy::ALL.len());
+        self.set_sort(SortBy::ALL[next]);
+    }
+
+    /// Open the sort menu with the current key highlighted
+    fn open_sort_menu(&mut self) {
+        self.sort_menu = SortBy::ALL
+            .iter()
+            .position(|sort_by| *sort_by == self.sort_by);
+    }
+
+    /// Sort by a key, in its usual direction if it is a different key
+    fn set_sort(&mut self, sort_by: SortBy) {
+        if sort_by != self.sort_by {
+            self.sort_by = sort_by;
+            self.sort_ascending = sort_by.is_ascending();
+        }
         self.sort_items();
+        self.set_status(format!("Sorted by {}", self.sort_description()));
+    }
+
+    fn toggle_sort_direction(&mut self) {
+        self.sort_ascending = !self.sort_ascending;
+        self.sort_items();
+        self.set_status(format!("Sorted by {}", self.sort_description()));
+    }
+
+    /// The sort key and direction, e.g. "size, descending"
+    fn sort_description(&self) -> String {
+        let direction = if self.sort_ascending {
+            "ascending"
+        } else {
+            "descending"
+        };
+        format!("{:?}, {}", self.sort_by, direction).to_lowercase()
     }
 
     fn sort_items(&mut self) {
-        match self.sort_by {
-            SortBy::Size => {
-                self.items
-                    .sort_by(|a, b| b.scan_result.size.cmp(&a.scan_result.size));
-            }
-            SortBy::Age => {
-                // Sort by modification time (newer first) - this would require additional metadata
-                // For now, we'll sort by description which contains age info
-                self.items
-                    .sort_by(|a, b| a.scan_result.description.cmp(&b.scan_result.description));
-            }
-            SortBy::Risk => {
-                self.items.sort_by(|a, b| {
-                    // Sort by risk level (critical first)
-                    let risk_order = |level: &RiskLevel| match level {
-                        RiskLevel::Critical => 0,
-                        RiskLevel::High => 1,
-                        RiskLevel::Medium => 2,
-                        RiskLevel::Low => 3,
-                        RiskLevel::Safe => 4,
-                    };
-                    risk_order(&a.scan_result.risk_level)
-                        .cmp(&risk_order(&b.scan_result.risk_level))
-                });
-            }
-            SortBy::Name => {
-                self.items.sort_by(|a, b| {
-                    a.scan_result
-                        .path
-                        .file_name()
-                        .and_then(|name| name.to_str())
-                        .unwrap_or("")
-                        .cmp(
-                            b.scan_result
-                                .path
-                                .file_name()
-                                .and_then(|name| name.to_str())
-                                .unwrap_or(""),
-                        )
-                });
+        let sort_by = self.sort_by;
+        // `compare` has the usual direction of its key, so flip it for the other one
+        let reverse = self.sort_ascending != sort_by.is_ascending();
+        self.items.sort_by(|a, b| {
+            let order = sort_by.compare(&a.scan_result, &b.scan_result);
+            if reverse {
+                order.reverse()
+            } else {
+                order
             }
-        }
+        });
 
         // Reset selection to first item after sorting
         if !self.items.is_empty() {
@@ -416,6 +815,181 @@ impl InteractiveSelector {
         }
     }
 
+    fn toggle_view(&mut self) {
+        let current = self.current_row();
+
+        self.view = match self.view {
+            View::Flat => View::Tree,
+            View::Tree => View::Flat,
+        };
+
+        // Keep the cursor on the same file, or on its directory if that is collapsed
+        let rows = self.visible_rows();
+        let position = match current {
+            Some(Row::Item(index)) => rows
+                .iter()
+                .position(|row| *row == Row::Item(index))
+                .or_else(|| {
+                    let dir = parent_dir(&self.items[index].scan_result.path);
+                    rows.iter()
+                        .position(|row| matches!(row, Row::Dir(group) if group.dir == dir))
+                }),
+            Some(Row::Dir(group)) => group
+                .items
+                .first()
+                .and_then(|&first| rows.iter().position(|row| *row == Row::Item(first))),
+            None => None,
+        };
+
+        if !rows.is_empty() {
+            self.list_state.select(Some(position.unwrap_or(0)));
+        }
+    }
+
+    /// Hide the files of the current directory in the tree view
+    fn collapse_current_dir(&mut self) {
+        let dir = match self.current_row() {
+            Some(Row::Dir(group)) => group.dir,
+            Some(Row::Item(index)) if self.view == View::Tree => {
+                parent_dir(&self.items[index].scan_result.path)
+            }
+            _ => return,
+        };
+
+        self.collapsed.insert(dir.clone());
+
+        // Move the cursor to the header of the collapsed directory
+        let position = self
+            .visible_rows()
+            .iter()
+            .position(|row| matches!(row, Row::Dir(group) if group.dir == dir));
+        self.list_state.select(position);
+    }
+
+    /// Show the files of the current directory in the tree view
+    fn expand_current_dir(&mut self) {
+        if let Some(Row::Dir(group)) = self.current_row() {
+            self.collapsed.remove(&group.dir);
+        }
+    }
+
+    /// Group the items by parent directory, in the order the directories first appear
+    fn dir_groups(&self) -> Vec<DirGroup> {
+        let mut groups: Vec<DirGroup> = Vec::new();
+
+        for (index, item) in self.items.iter().enumerate() {
+            let dir = parent_dir(&item.scan_result.path);
+
+            match groups.iter_mut().find(|group| group.dir == dir) {
+                Some(group) => {
+                    group.items.push(index);
+                    group.total_size += item.scan_result.size;
+                }
+                None => groups.push(DirGroup {
+                    dir,
+                    items: vec![index],
+                    total_size: item.scan_result.size,
+                }),
+            }
+        }
+
+        groups
+    }
+
+    /// The rows shown in the list for the current view
+    fn visible_rows(&self) -> Vec<Row> {
+        match self.view {
+            View::Flat => (0..self.items.len()).map(Row::Item).collect(),
+            View::Tree => {
+                let mut rows = Vec::new();
+                for group in self.dir_groups() {
+                    let expanded = !self.collapsed.contains(&group.dir);
+                    let items = group.items.clone();
+
+                    rows.push(Row::Dir(group));
+                    if expanded {
+                        rows.extend(items.into_iter().map(Row::Item));
+                    }
+                }
+                rows
+            }
+        }
+    }
+
+    fn row_count(&self) -> usize {
+        match self.view {
+            View::Flat => self.items.len(),
+            View::Tree => self.visible_rows().len(),
+        }
+    }
+
+    /// The row under the cursor
+    fn current_row(&self) -> Option<Row> {
+        let index = self.list_state.selected()?;
+        self.visible_rows().into_iter().nth(index)
+    }
+
+    /// Show the result of an action in the footer
+    fn set_status(&mut self, message: String) {
+        self.status = Some((message, Instant::now()));
+    }
+
+    /// The status message, if there is one
+    fn status_message(&self) -> Option<&str> {
+        self.status.as_ref().map(|(message, _)| message.as_str())
+    }
+
+    /// Clear the status message if it has been shown for long enough by `now`
+    fn expire_status(&mut self, now: Instant) {
+        if let Some((_, shown_at)) = &self.status {
+            if now.duration_since(*shown_at) >= STATUS_TIMEOUT {
+                self.status = None;
+                self.needs_redraw = true;
+            }
+        }
+    }
+
+    /// The directory of the row under the cursor, which is the directory itself for a header row
+    fn current_dir(&self) -> Option<PathBuf> {
+        match self.current_row()? {
+            Row::Dir(group) => Some(group.dir),
+            Row::Item(index) => Some(parent_dir(&self.items[index].scan_result.path)),
+        }
+    }
+
+    /// Open the directory of the row under the cursor in the file manager
+    ///
+    /// The file manager runs on its own, so the selector stays open. Failing
+    /// to start it is reported in the footer.
+    fn open_current_dir(&mut self) {
+        let Some(dir) = self.current_dir() else {
+            self.set_status("Nothing to open".to_string());
+            return;
+        };
+
+        let argv = file_manager_command(&dir, std::env::consts::OS);
+        let spawned = Command::new(&argv[0])
+            .args(&argv[1..])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        self.set_status(match spawned {
+            Ok(mut child) => {
+                // Reap the launcher once it exits, it only hands the directory over
+                std::thread::spawn(move || child.wait());
+                format!("Opened {}", dir.display())
+            }
+            Err(err) => format!(
+                "Could not open {} with {}: {}",
+                dir.display(),
+                argv[0].to_string_lossy(),
+                err
+            ),
+        });
+    }
+
     fn get_selected_items(&self) -> Vec<ScanResult> {
         self.items
             .iter()
@@ -425,9 +999,10 @@ impl InteractiveSelector {
     }
 
     fn next_item(&mut self) {
+        let row_count = self.row_count();
         let i = match self.list_state.selected() {
             Some(i) => {
-                if i >= self.items.len() - 1 {
+                if i >= row_count - 1 {
                     0
                 } else {
                     i + 1
@@ -439,10 +1014,11 @@ impl InteractiveSelector {
     }
 
     fn previous_item(&mut self) {
+        let row_count = self.row_count();
         let i = match self.list_state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.items.len() - 1
+                    row_count - 1
                 } else {
                     i - 1
                 }
@@ -452,43 +1028,179 @@ impl InteractiveSelector {
         self.list_state.select(Some(i));
     }
 
+    fn first_item(&mut self) {
+        if self.row_count() > 0 {
+            self.list_state.select(Some(0));
+        }
+    }
+
+    fn last_item(&mut self) {
+        let row_count = self.row_count();
+        if row_count > 0 {
+            self.list_state.select(Some(row_count - 1));
+        }
+    }
+
     fn page_up(&mut self) {
-        let page_size = 10;
+        self.move_up(PAGE_SIZE);
+    }
+
+    fn page_down(&mut self) {
+        self.move_down(PAGE_SIZE);
+    }
+
+    fn half_page_up(&mut self) {
+        self.move_up(PAGE_SIZE / 2);
+    }
+
+    fn half_page_down(&mut self) {
+        self.move_down(PAGE_SIZE / 2);
+    }
+
+    fn move_up(&mut self, distance: usize) {
         let i = match self.list_state.selected() {
-            Some(i) => i.saturating_sub(page_size),
+            Some(i) => i.saturating_sub(distance),
             None => 0,
         };
         self.list_state.select(Some(i));
     }
 
-    fn page_down(&mut self) {
-        let page_size = 10;
+    fn move_down(&mut self, distance: usize) {
+        let row_count = self.row_count();
+        if row_count == 0 {
+            return;
+        }
+
         let i = match self.list_state.selected() {
-            Some(i) => {
-                let new_i = i + page_size;
-                if new_i >= self.items.len() {
-                    self.items.len() - 1
-                } else {
-                    new_i
-                }
-            }
+            Some(i) => (i + distance).min(row_count - 1),
             None => 0,
         };
         self.list_state.select(Some(i));
     }
 }
 
+/// Check if an error from [`InteractiveSelector::run`] means there is no terminal to draw on
+pub fn is_no_terminal(error: &io::Error) -> bool {
+    // ENOTTY from the terminal setup calls
+    const NOT_A_TERMINAL: i32 = 25;
+
+    error.kind() == io::ErrorKind::Unsupported
+        || (cfg!(unix) && error.raw_os_error() == Some(NOT_A_TERMINAL))
+}
+
+/// Shortens a path to fit in `width` columns by replacing the middle with `…`
+///
+/// The start of the path is kept as a hint of where the file lives, and the
+/// file name is always kept whole, even if it does not fit on its own.
+fn truncate_path(path: &str, width: usize) -> String {
+    let length = path.chars().count();
+    if length <= width {
+        return path.to_string();
+    }
+
+    // The file name including the separator before it
+    let tail_start = path.rfind(['/', '\\']).unwrap_or(0);
+    let tail = &path[tail_start..];
+    let tail_length = tail.chars().count();
+
+    if tail_length + 1 > width {
+        return tail.trim_start_matches(['/', '\\']).to_string();
+    }
+
+    let head: String = path.chars().take(width - tail_length - 1).collect();
+    format!("{}…{}", head, tail)
+}
+
+/// The number of rows inside the borders of the file list
+fn inner_height(area: Rect) -> usize {
+    area.height.saturating_sub(2) as usize
+}
+
+/// The index of the first row the file list shows
+///
+/// The list is drawn from a fresh state every time, so it scrolls only as
+/// far as needed to show the cursor on its last row.
+fn list_offset(selected: Option<usize>, height: usize) -> usize {
+    selected.map_or(0, |selected| (selected + 1).saturating_sub(height))
+}
+
+/// The index of the list row at a terminal position, if the position is on a row
+///
+/// The index can be past the last row when the list doesn't fill its area.
+///
+/// # Arguments
+/// * `area`   - Where the list is drawn, including its borders
+/// * `offset` - The index of the first row shown
+/// * `column` - The terminal column of the position
+/// * `row`    - The terminal row of the position
+fn row_at(area: Rect, offset: usize, column: u16, row: u16) -> Option<usize> {
+    let inside_columns = column > area.x && column < area.right().saturating_sub(1);
+    let inside_rows = row > area.y && row < area.bottom().saturating_sub(1);
+
+    (inside_columns && inside_rows).then(|| offset + (row - area.y - 1) as usize)
+}
+
+/// The command line that opens a directory in the file manager of a platform
+///
+/// # Arguments
+/// * `dir` - The directory to open
+/// * `os`  - The platform, as in `std::env::consts::OS`
+fn file_manager_command(dir: &Path, os: &str) -> Vec<OsString> {
+    let program = match os {
+        "macos" => "open",
+        "windows" => "explorer",
+        _ => "xdg-open",
+    };
+    vec![OsString::from(program), dir.as_os_str().to_os_string()]
+}
+
+/// The directory an item is grouped under in the tree view
+fn parent_dir(path: &Path) -> PathBuf {
+    path.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_truncate_path() {
+        let path = "/home/user/projects/deep/nested/target/debug/big_file.bin";
+
+        // Fits as is
+        assert_eq!(truncate_path(path, 100), path);
+        assert_eq!(truncate_path(path, path.len()), path);
+
+        assert_eq!(truncate_path(path, 30), "/home/user/proje…/big_file.bin");
+        assert_eq!(truncate_path(path, 30).chars().count(), 30);
+        assert_eq!(truncate_path(path, 20), "/home/…/big_file.bin");
+        assert_eq!(truncate_path(path, 15), "/…/big_file.bin");
+        assert_eq!(truncate_path(path, 14), "…/big_file.bin");
+
+        // The file name is kept even if it does not fit
+        assert_eq!(truncate_path(path, 10), "big_file.bin");
+        assert_eq!(truncate_path(path, 0), "big_file.bin");
+    }
+
+    #[test]
+    fn test_truncate_path_short_and_windows_paths() {
+        assert_eq!(truncate_path("big_file.bin", 5), "big_file.bin");
+        assert_eq!(truncate_path("/a/b.bin", 8), "/a/b.bin");
+        assert_eq!(
+            truncate_path("C:\\Users\\me\\Downloads\\movie.mkv", 20),
+            "C:\\Users\\…\\movie.mkv"
+        );
+    }
+
     fn create_test_scan_result(path: &str, size: u64, risk: RiskLevel) -> ScanResult {
         ScanResult {
             path: PathBuf::from(path),
             size,
             description: format!("{} | Test file", super::super::utils::format_size(size)),
             risk_level: risk,
+            last_modified: None,
+            mime_type: None,
         }
     }
 
@@ -552,6 +1264,65 @@ mod tests {
         assert!(!selector.items[1].selected);
     }
 
+    #[test]
+    fn test_invert_selection() {
+        let results = vec![
+            create_test_scan_result("/test/large1.bin", 1000000, RiskLevel::Safe),
+            create_test_scan_result("/test/large2.bin", 2000000, RiskLevel::Low),
+            create_test_scan_result("/test/large3.bin", 3000000, RiskLevel::Medium),
+        ];
+
+        let mut selector = InteractiveSelector::new(results);
+        selector.items[1].selected = true;
+
+        selector.invert_selection();
+        assert!(selector.items[0].selected);
+        assert!(!selector.items[1].selected);
+        assert!(selector.items[2].selected);
+
+        selector.invert_selection();
+        assert!(!selector.items[0].selected);
+        assert!(selector.items[1].selected);
+        assert!(!selector.items[2].selected);
+    }
+
+    #[test]
+    fn test_toggle_current_risk_level() {
+        let results = vec![
+            create_test_scan_result("/test/safe1.bin", 4000, RiskLevel::Safe),
+            create_test_scan_result("/test/high.bin", 3000, RiskLevel::High),
+            create_test_scan_result("/test/safe2.bin", 2000, RiskLevel::Safe),
+            create_test_scan_result("/test/low.bin", 1000, RiskLevel::Low),
+        ];
+
+        let mut selector = InteractiveSelector::new(results);
+        selector.list_state.select(Some(0));
+
+        // Selects every Safe item and nothing else
+        selector.toggle_current_risk_level();
+        let selected: Vec<_> = selector
+            .get_selected_items()
+            .into_iter()
+            .map(|r| r.risk_level)
+            .collect();
+        assert_eq!(selected, vec![RiskLevel::Safe, RiskLevel::Safe]);
+
+        // Selecting another level keeps the Safe items selected
+        selector.list_state.select(Some(3));
+        selector.toggle_current_risk_level();
+        assert_eq!(selector.get_selected_items().len(), 3);
+
+        // Pressing again on a fully selected level deselects it
+        selector.list_state.select(Some(2));
+        selector.toggle_current_risk_level();
+        let selected: Vec<_> = selector
+            .get_selected_items()
+            .into_iter()
+            .map(|r| r.risk_level)
+            .collect();
+        assert_eq!(selected, vec![RiskLevel::Low]);
+    }
+
     #[test]
     fn test_sort_cycle() {
         let results = vec![
@@ -575,6 +1346,89 @@ mod tests {
         assert_eq!(selector.sort_by, SortBy::Size);
     }
 
+    fn sorted_paths(selector: &InteractiveSelector) -> Vec<PathBuf> {
+        selector
+            .items
+            .iter()
+            .map(|item| item.scan_result.path.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_toggling_direction_reverses_every_sort_key() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let results = [
+            ("/b/video.mp4", 300, RiskLevel::Low, 10),
+            ("/a/config.toml", 10, RiskLevel::Critical, 1),
+            ("/c/archive.zip", 200, RiskLevel::Medium, 30),
+            ("/d/backup.tar", 50, RiskLevel::Safe, 400),
+        ]
+        .into_iter()
+        .map(|(path, size, risk, days_old)| ScanResult {
+            last_modified: Some(SystemTime::now() - day * days_old),
+            ..create_test_scan_result(path, size, risk)
+        })
+        .collect();
+        let mut selector = InteractiveSelector::new(results);
+
+        for sort_by in SortBy::ALL {
+            selector.set_sort(sort_by);
+            assert_eq!(selector.sort_ascending, sort_by.is_ascending());
+            let usual = sorted_paths(&selector);
+
+            selector.toggle_sort_direction();
+            let mut toggled = sorted_paths(&selector);
+            toggled.reverse();
+            assert_eq!(toggled, usual, "{:?}", sort_by);
+
+            selector.toggle_sort_direction();
+            assert_eq!(sorted_paths(&selector), usual, "{:?}", sort_by);
+        }
+    }
+
+    #[test]
+    fn test_sort_menu() {
+        let results = vec![
+            create_test_scan_result("/test/b.bin", 1000, RiskLevel::High),
+            create_test_scan_result("/test/a.bin", 2000, RiskLevel::Safe),
+            create_test_scan_result("/test/c.bin", 3000, RiskLevel::Low),
+        ];
+        let mut selector = InteractiveSelector::new(results);
+        let none = KeyModifiers::NONE;
+
+        press(&mut selector, KeyCode::Char('S'), KeyModifiers::SHIFT);
+        assert_eq!(selector.sort_menu, Some(0));
+
+        // Pick name, which starts out ascending
+        for _ in 0..3 {
+            press(&mut selector, KeyCode::Down, none);
+        }
+        press(&mut selector, KeyCode::Enter, none);
+        assert_eq!(selector.sort_menu, None);
+        assert_eq!(selector.sort_by, SortBy::Name);
+        assert!(selector.sort_ascending);
+        assert_eq!(
+            selector.items[0].scan_result.path,
+            PathBuf::from("/test/a.bin")
+        );
+
+        // Keys don't reach the list while the menu is open
+        press(&mut selector, KeyCode::Char('S'), KeyModifiers::SHIFT);
+        press(&mut selector, KeyCode::Char('a'), none);
+        assert!(selector.items.iter().all(|item| !item.selected));
+
+        press(&mut selector, KeyCode::Char('d'), none);
+        assert!(!selector.sort_ascending);
+        assert_eq!(
+            selector.items[0].scan_result.path,
+            PathBuf::from("/test/c.bin")
+        );
+
+        press(&mut selector, KeyCode::Esc, none);
+        assert_eq!(selector.sort_menu, None);
+        assert_eq!(selector.sort_by, SortBy::Name);
+    }
+
     #[test]
     fn test_risk_level_sorting() {
         let results = vec![
@@ -596,6 +1450,46 @@ mod tests {
         assert_eq!(selector.items[2].scan_result.risk_level, RiskLevel::Safe);
     }
 
+    #[test]
+    fn test_equal_risk_and_size_sort_by_path() {
+        let results = vec![
+            create_test_scan_result("/test/c.bin", 1000, RiskLevel::Safe),
+            create_test_scan_result("/test/b.bin", 1000, RiskLevel::High),
+            create_test_scan_result("/test/a.bin", 1000, RiskLevel::Safe),
+            create_test_scan_result("/test/d.bin", 5000, RiskLevel::Safe),
+        ];
+        let paths = |selector: &InteractiveSelector| -> Vec<PathBuf> {
+            selector
+                .items
+                .iter()
+                .map(|item| item.scan_result.path.clone())
+                .collect()
+        };
+
+        let mut selector = InteractiveSelector::new(results);
+        assert_eq!(
+            paths(&selector),
+            vec![
+                PathBuf::from("/test/d.bin"),
+                PathBuf::from("/test/a.bin"),
+                PathBuf::from("/test/b.bin"),
+                PathBuf::from("/test/c.bin"),
+            ]
+        );
+
+        selector.sort_by = SortBy::Risk;
+        selector.sort_items();
+        assert_eq!(
+            paths(&selector),
+            vec![
+                PathBuf::from("/test/b.bin"),
+                PathBuf::from("/test/d.bin"),
+                PathBuf::from("/test/a.bin"),
+                PathBuf::from("/test/c.bin"),
+            ]
+        );
+    }
+
     #[test]
     fn test_get_selected_items() {
         let results = vec![
@@ -646,6 +1540,358 @@ mod tests {
         assert_eq!(selector.list_state.selected(), Some(0));
     }
 
+    fn press(selector: &mut InteractiveSelector, code: KeyCode, modifiers: KeyModifiers) {
+        assert!(selector
+            .handle_key(KeyEvent::new(code, modifiers))
+            .is_none());
+    }
+
+    #[test]
+    fn test_vim_navigation_keys() {
+        let results = (0..30)
+            .map(|i| {
+                create_test_scan_result(&format!("/test/{}.bin", i), 1000 - i, RiskLevel::Safe)
+            })
+            .collect();
+
+        let mut selector = InteractiveSelector::new(results);
+        let none = KeyModifiers::NONE;
+
+        press(&mut selector, KeyCode::Char('j'), none);
+        press(&mut selector, KeyCode::Char('j'), none);
+        assert_eq!(selector.list_state.selected(), Some(2));
+
+        press(&mut selector, KeyCode::Char('k'), none);
+        assert_eq!(selector.list_state.selected(), Some(1));
+
+        press(&mut selector, KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(selector.list_state.selected(), Some(29));
+
+        press(&mut selector, KeyCode::Char('g'), none);
+        assert_eq!(selector.list_state.selected(), Some(0));
+
+        press(&mut selector, KeyCode::Char('d'), KeyModifiers::CONTROL);
+        assert_eq!(selector.list_state.selected(), Some(5));
+
+        press(&mut selector, KeyCode::Char('u'), KeyModifiers::CONTROL);
+        press(&mut selector, KeyCode::Char('u'), KeyModifiers::CONTROL);
+        assert_eq!(selector.list_state.selected(), Some(0));
+
+        // Plain d and u do nothing
+        press(&mut selector, KeyCode::Char('d'), none);
+        assert_eq!(selector.list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_resize_triggers_redraw() {
+        let results = vec![create_test_scan_result(
+            "/test/1.bin",
+            3000,
+            RiskLevel::Safe,
+        )];
+        let mut selector = InteractiveSelector::new(results);
+        assert!(selector.needs_redraw);
+
+        selector.needs_redraw = false;
+        assert!(selector.handle_event(Event::Resize(40, 10)).is_none());
+        assert!(selector.needs_redraw);
+
+        // Tiny terminals must not break the layout maths
+        selector.needs_redraw = false;
+        assert!(selector.handle_event(Event::Resize(0, 0)).is_none());
+        assert!(selector.needs_redraw);
+        assert_eq!(selector.list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_key_event_triggers_redraw() {
+        let results = vec![
+            create_test_scan_result("/test/1.bin", 3000, RiskLevel::Safe),
+            create_test_scan_result("/test/2.bin", 2000, RiskLevel::Safe),
+        ];
+        let mut selector = InteractiveSelector::new(results);
+
+        selector.needs_redraw = false;
+        let down = Event::Key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        assert!(selector.handle_event(down).is_none());
+        assert!(selector.needs_redraw);
+        assert_eq!(selector.list_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn test_existing_keys_still_work() {
+        let results = vec![
+            create_test_scan_result("/test/1.bin", 3000, RiskLevel::Safe),
+            create_test_scan_result("/test/2.bin", 2000, RiskLevel::Safe),
+            create_test_scan_result("/test/3.bin", 1000, RiskLevel::Safe),
+        ];
+
+        let mut selector = InteractiveSelector::new(results);
+        let none = KeyModifiers::NONE;
+
+        press(&mut selector, KeyCode::Down, none);
+        assert_eq!(selector.list_state.selected(), Some(1));
+        press(&mut selector, KeyCode::End, none);
+        assert_eq!(selector.list_state.selected(), Some(2));
+        press(&mut selector, KeyCode::Home, none);
+        assert_eq!(selector.list_state.selected(), Some(0));
+        press(&mut selector, KeyCode::PageDown, none);
+        assert_eq!(selector.list_state.selected(), Some(2));
+
+        press(&mut selector, KeyCode::Char(' '), none);
+        let selected = selector.handle_key(KeyEvent::new(KeyCode::Enter, none));
+        assert_eq!(selected.unwrap().len(), 1);
+
+        let cancelled =
+            selector.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert_eq!(cancelled.unwrap().len(), 0);
+    }
+
+    fn tree_selector() -> InteractiveSelector {
+        let results = vec![
+            create_test_scan_result("/a/big.bin", 5000, RiskLevel::Safe),
+            create_test_scan_result("/b/one.bin", 4000, RiskLevel::Low),
+            create_test_scan_result("/a/small.bin", 1000, RiskLevel::Safe),
+            create_test_scan_result("/b/two.bin", 3000, RiskLevel::Low),
+        ];
+
+        let mut selector = InteractiveSelector::new(results);
+        selector.toggle_view();
+        selector
+    }
+
+    #[test]
+    fn test_dir_groups() {
+        let selector = tree_selector();
+        let groups = selector.dir_groups();
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].dir, PathBuf::from("/a"));
+        assert_eq!(groups[0].total_size, 6000);
+        assert_eq!(groups[1].dir, PathBuf::from("/b"));
+        assert_eq!(groups[1].total_size, 7000);
+
+        // Rows follow the size order of the flat list within each directory
+        let paths: Vec<_> = groups[1]
+            .items
+            .iter()
+            .map(|&i| selector.items[i].scan_result.path.clone())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("/b/one.bin"), PathBuf::from("/b/two.bin")]
+        );
+
+        // Two headers and four files
+        assert_eq!(selector.visible_rows().len(), 6);
+        assert!(matches!(selector.visible_rows()[0], Row::Dir(_)));
+    }
+
+    #[test]
+    fn test_tree_view_toggles_whole_directory() {
+        let mut selector = tree_selector();
+        let none = KeyModifiers::NONE;
+
+        // Cursor stays on the largest file when switching views
+        assert_eq!(selector.current_row(), Some(Row::Item(0)));
+
+        press(&mut selector, KeyCode::Char('g'), none);
+        press(&mut selector, KeyCode::Char(' '), none);
+        let selected: Vec<_> = selector
+            .get_selected_items()
+            .into_iter()
+            .map(|r| r.path)
+            .collect();
+        assert_eq!(
+            selected,
+            vec![PathBuf::from("/a/big.bin"), PathBuf::from("/a/small.bin")]
+        );
+
+        // Partially selected directory becomes fully selected, then deselected
+        selector.items[0].selected = false;
+        press(&mut selector, KeyCode::Char(' '), none);
+        assert_eq!(selector.get_selected_items().len(), 2);
+        press(&mut selector, KeyCode::Char(' '), none);
+        assert!(selector.get_selected_items().is_empty());
+    }
+
+    #[test]
+    fn test_tree_view_collapse_and_expand() {
+        let mut selector = tree_selector();
+        let none = KeyModifiers::NONE;
+
+        // Collapsing from a file moves the cursor to its directory
+        press(&mut selector, KeyCode::Left, none);
+        assert_eq!(selector.list_state.selected(), Some(0));
+        assert_eq!(selector.visible_rows().len(), 4);
+
+        // The cursor wraps around the visible rows only
+        press(&mut selector, KeyCode::Char('G'), none);
+        assert_eq!(selector.list_state.selected(), Some(3));
+        press(&mut selector, KeyCode::Char('j'), none);
+        assert_eq!(selector.list_state.selected(), Some(0));
+
+        // Space on a collapsed directory still selects its files
+        press(&mut selector, KeyCode::Char(' '), none);
+        assert_eq!(selector.get_selected_items().len(), 2);
+
+        press(&mut selector, KeyCode::Right, none);
+        assert_eq!(selector.visible_rows().len(), 6);
+
+        // Back to the flat view on the same file
+        press(&mut selector, KeyCode::Char('j'), none);
+        let current = selector.current_row();
+        press(&mut selector, KeyCode::Char('t'), none);
+        assert_eq!(selector.view, View::Flat);
+        assert_eq!(selector.current_row(), current);
+    }
+
+    #[test]
+    fn test_file_manager_command() {
+        let dir = Path::new("/home/user/videos");
+        let argv = |os| file_manager_command(dir, os);
+
+        assert_eq!(argv("macos"), ["open", "/home/user/videos"]);
+        assert_eq!(argv("windows"), ["explorer", "/home/user/videos"]);
+        assert_eq!(argv("linux"), ["xdg-open", "/home/user/videos"]);
+        assert_eq!(argv("freebsd"), ["xdg-open", "/home/user/videos"]);
+    }
+
+    #[test]
+    fn test_directory_results_are_marked() {
+        let temp_dir = tempdir::TempDir::new("ui_dirs_test").unwrap();
+        let target = temp_dir.path().join("target");
+        let file = temp_dir.path().join("dump.bin");
+        fs::create_dir(&target).unwrap();
+        fs::write(&file, "12").unwrap();
+
+        let selector = InteractiveSelector::new(vec![
+            create_test_scan_result(target.to_str().unwrap(), 2000, RiskLevel::Safe),
+            create_test_scan_result(file.to_str().unwrap(), 1000, RiskLevel::Low),
+        ]);
+
+        assert!(selector.items[0].is_dir);
+        assert!(!selector.items[1].is_dir);
+    }
+
+    #[test]
+    fn test_open_directory_of_current_row() {
+        // A header row stands for its own directory
+        let mut selector = tree_selector();
+        press(&mut selector, KeyCode::Char('g'), KeyModifiers::NONE);
+        assert_eq!(
+            selector.current_row(),
+            Some(Row::Dir(selector.dir_groups()[0].clone()))
+        );
+        assert_eq!(selector.current_dir(), Some(PathBuf::from("/a")));
+
+        press(&mut selector, KeyCode::Char('t'), KeyModifiers::NONE);
+        press(&mut selector, KeyCode::Char('j'), KeyModifiers::NONE);
+        assert_eq!(selector.current_dir(), Some(PathBuf::from("/b")));
+
+        let mut empty = InteractiveSelector::new(vec![]);
+        press(&mut empty, KeyCode::Char('o'), KeyModifiers::NONE);
+        assert_eq!(empty.status_message(), Some("Nothing to open"));
+    }
+
+    #[test]
+    fn test_status_message_clears_on_next_key() {
+        let mut selector = tree_selector();
+        let none = KeyModifiers::NONE;
+
+        press(&mut selector, KeyCode::Char('a'), none);
+        assert_eq!(selector.status_message(), Some("Selected all 4 files"));
+
+        let down = Event::Key(KeyEvent::new(KeyCode::Down, none));
+        assert!(selector.handle_event(down).is_none());
+        assert_eq!(selector.status_message(), None);
+
+        press(&mut selector, KeyCode::Char('s'), none);
+        assert_eq!(selector.status_message(), Some("Sorted by age, descending"));
+    }
+
+    #[test]
+    fn test_status_message_expires() {
+        let mut selector = tree_selector();
+        press(&mut selector, KeyCode::Char('i'), KeyModifiers::NONE);
+        assert_eq!(
+            selector.status_message(),
+            Some("Inverted the selection, 4 selected")
+        );
+
+        let shown_at = selector.status.as_ref().unwrap().1;
+        selector.needs_redraw = false;
+        selector.expire_status(shown_at + STATUS_TIMEOUT / 2);
+        assert!(selector.status_message().is_some());
+        assert!(!selector.needs_redraw);
+
+        selector.expire_status(shown_at + STATUS_TIMEOUT);
+        assert_eq!(selector.status_message(), None);
+        assert!(selector.needs_redraw);
+    }
+
+    #[test]
+    fn test_row_at_maps_positions_inside_the_borders() {
+        let area = Rect::new(0, 3, 40, 10);
+
+        // The first row is right below the top border
+        assert_eq!(row_at(area, 0, 5, 4), Some(0));
+        assert_eq!(row_at(area, 0, 38, 11), Some(7));
+        assert_eq!(row_at(area, 12, 5, 4), Some(12));
+        assert_eq!(row_at(area, 12, 5, 11), Some(19));
+
+        // Borders and the header or footer above and below
+        for (column, row) in [(5, 3), (5, 12), (0, 5), (39, 5), (5, 0), (5, 20)] {
+            assert_eq!(row_at(area, 0, column, row), None, "{} {}", column, row);
+        }
+    }
+
+    #[test]
+    fn test_list_offset_follows_the_cursor() {
+        assert_eq!(list_offset(None, 8), 0);
+        assert_eq!(list_offset(Some(7), 8), 0);
+        assert_eq!(list_offset(Some(8), 8), 1);
+        assert_eq!(list_offset(Some(20), 8), 13);
+        assert_eq!(list_offset(Some(3), 0), 4);
+    }
+
+    #[test]
+    fn test_mouse_click_and_wheel() {
+        let results = (0..20)
+            .map(|i| create_test_scan_result(&format!("/f/{:02}.bin", i), 1000 - i, RiskLevel::Low))
+            .collect();
+        let mut selector = InteractiveSelector::new(results);
+        selector.list_area.set(Rect::new(0, 3, 40, 10));
+        let mouse = |kind, row| {
+            Event::Mouse(MouseEvent {
+                kind,
+                column: 5,
+                row,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+
+        assert!(selector
+            .handle_event(mouse(MouseEventKind::Down(MouseButton::Left), 6))
+            .is_none());
+        assert_eq!(selector.list_state.selected(), Some(2));
+
+        selector.handle_event(mouse(MouseEventKind::ScrollDown, 0));
+        selector.handle_event(mouse(MouseEventKind::ScrollDown, 0));
+        assert_eq!(selector.list_state.selected(), Some(8));
+
+        // The list has scrolled by one row to show the cursor
+        selector.handle_event(mouse(MouseEventKind::Down(MouseButton::Left), 4));
+        assert_eq!(selector.list_state.selected(), Some(1));
+
+        selector.handle_event(mouse(MouseEventKind::ScrollUp, 0));
+        assert_eq!(selector.list_state.selected(), Some(0));
+
+        // Clicks on the borders change nothing
+        selector.handle_event(mouse(MouseEventKind::Down(MouseButton::Left), 3));
+        assert_eq!(selector.list_state.selected(), Some(0));
+    }
+
     #[test]
     fn test_empty_results() {
         let selector = InteractiveSelector::new(vec![]);
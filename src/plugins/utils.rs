@@ -1,15 +1,62 @@
 /// Utility functions for plugin operations
-use super::PluginError;
+use super::filter::SmartFilter;
+use super::{safety, PluginError, ScanResult};
 use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How many times a deletion is attempted before giving up
+const DELETE_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry, doubled for every further retry
+const RETRY_DELAY: Duration = Duration::from_millis(50);
 
 /// Parse a human-readable size string into bytes
-/// Supports formats like "100MB", "1.5GB", "500K", etc.
+/// Supports formats like "100MB", "1.5GB", "500K", "2GiB", "1PB", etc.
+/// Sizes too large for a `u64` saturate at `u64::MAX`.
+///
+/// Binary units (`KiB`, `MiB`, `GiB`, `TiB`) are always powers of 1024. For
+/// compatibility, `KB`, `MB`, `GB` and `TB` are powers of 1024 as well; use
+/// `parse_size_string_si` to read them as powers of 1000.
 pub fn parse_size_string(size_str: &str) -> Result<u64, PluginError> {
+    parse_size(size_str, 1024.0)
+}
+
+/// Parse a human-readable size string into bytes, using powers of 1000 for `KB`, `MB`, `GB` and `TB`
+pub fn parse_size_string_si(size_str: &str) -> Result<u64, PluginError> {
+    parse_size(size_str, 1000.0)
+}
+
+/// Parse a size string, with `kilo` as the base of the non-binary units
+fn parse_size(size_str: &str, kilo: f64) -> Result<u64, PluginError> {
+    let trimmed = size_str.trim();
+    if trimmed.is_empty() {
+        return Err(PluginError::Configuration(
+            "Invalid size format: size must not be empty".to_string(),
+        ));
+    }
+    if trimmed.starts_with('-') {
+        return Err(PluginError::Configuration(format!(
+            "Invalid size format: {} (size must not be negative)",
+            size_str
+        )));
+    }
+    if trimmed.matches('.').count() > 1 {
+        return Err(PluginError::Configuration(format!(
+            "Invalid size format: {} (number has more than one decimal point)",
+            size_str
+        )));
+    }
+
     // Regex to match number (with optional decimal) and unit
-    let re = Regex::new(r"^(\d+(?:\.\d+)?)\s*([KMGT]?B?)$")
+    let re = Regex::new(r"^(\d+(?:\.\d+)?)\s*((?:[KMGTP]I?)?B?)$")
         .map_err(|e| PluginError::Configuration(format!("Invalid regex: {}", e)))?;
 
-    let size_str_upper = size_str.to_uppercase();
+    let size_str_upper = trimmed.to_uppercase();
     let captures = re
         .captures(&size_str_upper)
         .ok_or_else(|| PluginError::Configuration(format!("Invalid size format: {}", size_str)))?;
@@ -25,10 +72,16 @@ pub fn parse_size_string(size_str: &str) -> Result<u64, PluginError> {
 
     let multiplier = match unit {
         "B" | "" => 1.0,
-        "K" | "KB" => 1024.0,
-        "M" | "MB" => 1024.0 * 1024.0,
-        "G" | "GB" => 1024.0 * 1024.0 * 1024.0,
-        "T" | "TB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        "K" | "KB" => kilo,
+        "M" | "MB" => kilo.powi(2),
+        "G" | "GB" => kilo.powi(3),
+        "T" | "TB" => kilo.powi(4),
+        "P" | "PB" => kilo.powi(5),
+        "KI" | "KIB" => 1024.0,
+        "MI" | "MIB" => 1024.0_f64.powi(2),
+        "GI" | "GIB" => 1024.0_f64.powi(3),
+        "TI" | "TIB" => 1024.0_f64.powi(4),
+        "PI" | "PIB" => 1024.0_f64.powi(5),
         _ => {
             return Err(PluginError::Configuration(format!(
                 "Unknown unit: {}",
@@ -37,36 +90,664 @@ pub fn parse_size_string(size_str: &str) -> Result<u64, PluginError> {
         }
     };
 
-    Ok((number * multiplier) as u64)
+    // Saturate instead of wrapping for sizes that don't fit in a u64
+    let bytes = number * multiplier;
+    if bytes >= u64::MAX as f64 {
+        Ok(u64::MAX)
+    } else {
+        Ok(bytes as u64)
+    }
 }
 
 /// Format bytes into human-readable string
+///
+/// This is the one canonical size format used everywhere in the output:
+/// three significant digits, e.g. `"1.50 KB"`, `"10.0 MB"` or `"100 GB"`.
 pub fn format_size(bytes: u64) -> String {
-    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
-    const THRESHOLD: f64 = 1024.0;
+    format_with_base(bytes, 1024.0)
+}
+
+/// Format bytes into human-readable string, using powers of 1000 for the units
+pub fn format_size_si(bytes: u64) -> String {
+    format_with_base(bytes, 1000.0)
+}
+
+/// Format bytes with `threshold` bytes per step between units
+fn format_with_base(bytes: u64, threshold: f64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB", "PB"];
 
     let mut size = bytes as f64;
     let mut unit_index = 0;
 
-    while size >= THRESHOLD && unit_index < UNITS.len() - 1 {
-        size /= THRESHOLD;
+    while size >= threshold && unit_index < UNITS.len() - 1 {
+        size /= threshold;
         unit_index += 1;
     }
 
+    // Values that round up to the threshold are shown in the next unit, so
+    // 1023.9 KB becomes "1.00 MB" instead of "1024 KB"
+    if unit_index > 0 && size.round() >= threshold && unit_index < UNITS.len() - 1 {
+        size /= threshold;
+        unit_index += 1;
+    }
+
+    // Pick the precision after rounding, so 99.97 shows as "100" and not "100.0"
     if unit_index == 0 {
         format!("{} {}", bytes, UNITS[unit_index])
-    } else if size >= 100.0 {
+    } else if size >= 99.95 {
         format!("{:.0} {}", size, UNITS[unit_index])
-    } else if size >= 10.0 {
+    } else if size >= 9.995 {
         format!("{:.1} {}", size, UNITS[unit_index])
     } else {
         format!("{:.2} {}", size, UNITS[unit_index])
     }
 }
 
+/// Parse a duration such as `90`, `30s`, `5m` or `1h`, where a bare number is in seconds
+pub fn parse_duration_string(duration_str: &str) -> Result<Duration, PluginError> {
+    let trimmed = duration_str.trim().to_lowercase();
+    let (number, unit) = match trimmed.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => trimmed.split_at(index),
+        None => (trimmed.as_str(), "s"),
+    };
+
+    let multiplier = match unit.trim() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        _ => {
+            return Err(PluginError::Configuration(format!(
+                "Invalid duration format: {} (use s, m or h)",
+                duration_str
+            )))
+        }
+    };
+
+    let number: u64 = number.parse().map_err(|_| {
+        PluginError::Configuration(format!("Invalid duration format: {}", duration_str))
+    })?;
+    if number == 0 {
+        return Err(PluginError::Configuration(format!(
+            "Invalid duration format: {} (duration must not be zero)",
+            duration_str
+        )));
+    }
+
+    let secs = number.checked_mul(multiplier).ok_or_else(|| {
+        PluginError::Configuration(format!(
+            "Invalid duration format: {} (duration is too long)",
+            duration_str
+        ))
+    })?;
+    Ok(Duration::from_secs(secs))
+}
+
+/// Format a duration in a human readable way, e.g. "4.2s" or "3m 12s"
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+
+    if secs < 60 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else if secs < 60 * 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
+/// Units of age from the largest, with their short and long names
+const AGE_UNITS: [(u64, &str, &str); 5] = [
+    (365 * 24 * 60 * 60, "y", "year"),
+    (30 * 24 * 60 * 60, "mo", "month"),
+    (24 * 60 * 60, "d", "day"),
+    (60 * 60, "h", "hour"),
+    (60, "m", "minute"),
+];
+
+/// Split an age into a count of the largest unit it has at least one of
+///
+/// # Returns
+/// The count with the short and long name of the unit, or `None` for less than a minute
+fn age_in_largest_unit(age: Duration) -> Option<(u64, &'static str, &'static str)> {
+    let secs = age.as_secs();
+    AGE_UNITS
+        .iter()
+        .find(|(unit, _, _)| secs >= *unit)
+        .map(|&(unit, short, long)| (secs / unit, short, long))
+}
+
+/// Format the age of a file compactly, e.g. "45s ago", "3d ago" or "2mo ago"
+pub fn format_age(age: Duration) -> String {
+    match age_in_largest_unit(age) {
+        Some((count, short, _)) => format!("{}{} ago", count, short),
+        None => format!("{}s ago", age.as_secs()),
+    }
+}
+
+/// Format the age of a file in words, e.g. "just now", "3 hours ago" or "5 months ago"
+pub fn humanize_age(age: Duration) -> String {
+    match age_in_largest_unit(age) {
+        Some((1, _, long)) => format!("1 {} ago", long),
+        Some((count, _, long)) => format!("{} {}s ago", count, long),
+        None => "just now".to_string(),
+    }
+}
+
+/// Format a point in time as an RFC 3339 timestamp in UTC, e.g. "2023-11-14T22:13:20Z"
+///
+/// Times before 1970 are formatted as the start of 1970.
+pub fn format_rfc3339(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from the days since 1970, in eras of 400 years starting in March
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60
+    )
+}
+
+/// Count the days from 1970-01-01 to a date of the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    // Eras of 400 years starting in March, like `format_rfc3339`
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Parse a date like "2023-01-31" or an RFC 3339 timestamp like "2023-01-31T08:00:00+01:00"
+///
+/// A date without a time is the start of that day in UTC.
+pub fn parse_date(s: &str) -> Result<SystemTime, String> {
+    let invalid = || {
+        format!(
+            "Invalid date '{}', expected YYYY-MM-DD or a timestamp like 2023-01-31T08:00:00Z",
+            s
+        )
+    };
+    let number = |digits: &str, len: usize| -> Result<i64, String> {
+        if digits.len() != len || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        digits.parse().map_err(|_| invalid())
+    };
+
+    let (date, time) = match s.find(['T', 't', ' ']) {
+        Some(index) => (&s[..index], Some(&s[index + 1..])),
+        None => (s, None),
+    };
+
+    let mut parts = date.split('-');
+    let (Some(year), Some(month), Some(day), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    let (year, month, day) = (number(year, 4)?, number(month, 2)?, number(day, 2)?);
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return Err(invalid()),
+    };
+    if !(1..=days_in_month).contains(&day) {
+        return Err(invalid());
+    }
+
+    let mut secs = days_from_civil(year, month, day) * 86_400;
+    let mut nanos = 0;
+
+    if let Some(time) = time {
+        // The offset is required, so the time is never read in the wrong zone
+        let (clock, offset) = match time.find(['Z', 'z', '+', '-']) {
+            Some(index) => time.split_at(index),
+            None => return Err(invalid()),
+        };
+        let (clock, fraction) = match clock.split_once('.') {
+            Some((clock, fraction)) => (clock, Some(fraction)),
+            None => (clock, None),
+        };
+
+        let mut fields = clock.split(':');
+        let (Some(hour), Some(minute), Some(second), None) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(invalid());
+        };
+        let (hour, minute, second) = (number(hour, 2)?, number(minute, 2)?, number(second, 2)?);
+        if hour > 23 || minute > 59 || second > 59 {
+            return Err(invalid());
+        }
+        secs += hour * 3600 + minute * 60 + second;
+
+        if let Some(fraction) = fraction {
+            if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            // Digits beyond nanoseconds don't matter
+            let digits = &fraction[..fraction.len().min(9)];
+            nanos =
+                digits.parse::<u32>().map_err(|_| invalid())? * 10u32.pow(9 - digits.len() as u32);
+        }
+
+        if !offset.eq_ignore_ascii_case("z") {
+            let (sign, hours_minutes) = offset.split_at(1);
+            let Some((hours, minutes)) = hours_minutes.split_once(':') else {
+                return Err(invalid());
+            };
+            let (hours, minutes) = (number(hours, 2)?, number(minutes, 2)?);
+            if hours > 23 || minutes > 59 {
+                return Err(invalid());
+            }
+            let offset = hours * 3600 + minutes * 60;
+            secs -= if sign == "+" { offset } else { -offset };
+        }
+    }
+
+    // Not every platform's clock reaches every date, Windows' starts in 1601
+    let epoch_offset = Duration::from_secs(secs.unsigned_abs());
+    let time = if secs >= 0 {
+        SystemTime::UNIX_EPOCH.checked_add(epoch_offset)
+    } else {
+        SystemTime::UNIX_EPOCH.checked_sub(epoch_offset)
+    };
+    time.and_then(|time| time.checked_add(Duration::from_nanos(u64::from(nanos))))
+        .ok_or_else(|| format!("Date '{}' is out of the range of the system clock", s))
+}
+
+/// Get the space available to the current user on the volume containing `path`
+pub fn available_space(path: &Path) -> io::Result<u64> {
+    fs2::available_space(path)
+}
+
+/// Get the free space on the volume containing `path`, including space reserved for root
+pub fn free_space(path: &Path) -> io::Result<u64> {
+    fs2::free_space(path)
+}
+
+/// Get the total size of the volume containing `path`
+pub fn total_space(path: &Path) -> io::Result<u64> {
+    fs2::total_space(path)
+}
+
+/// Keeps one path per volume, the first of the paths on it
+///
+/// Paths whose volume can't be told count as a volume of their own.
+///
+/// # Arguments
+/// * `paths` - The scan roots
+pub fn distinct_volumes(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    paths
+        .iter()
+        .filter(|path| volume_id(path).map_or(true, |id| seen.insert(id)))
+        .cloned()
+        .collect()
+}
+
+#[cfg(unix)]
+fn volume_id(path: &Path) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path)
+        .ok()
+        .map(|metadata| metadata.dev().to_string())
+}
+
+/// Tells volumes apart by drive letter or network share, as canonical paths start with them
+#[cfg(not(unix))]
+fn volume_id(path: &Path) -> Option<String> {
+    match path.components().next()? {
+        std::path::Component::Prefix(prefix) => {
+            Some(prefix.as_os_str().to_string_lossy().to_uppercase())
+        }
+        _ => None,
+    }
+}
+
+/// Check if a volume is at least `percent` full, for `--when-full`
+///
+/// A volume whose size is unknown or zero never counts as full.
+///
+/// * `free`    - The free space on the volume in bytes, as [`free_space`] reports it
+/// * `free`    - The space available on the volume in bytes
+/// * `total`   - The size of the volume in bytes
+/// * `percent` - How full the volume has to be, from 0 to 100
+pub fn is_volume_full(free: u64, total: u64, percent: f64) -> bool {
+    total > 0 && usage_percent(free, total) >= percent
+}
+
+/// How much of a volume is in use, in percent
+///
+/// `free` is all free space, as [`free_space`] reports it, so that space
+/// reserved for root doesn't count as used.
+pub fn usage_percent(free: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    total.saturating_sub(free) as f64 / total as f64 * 100.0
+}
+
+/// Check if a failed deletion may succeed when tried again shortly after
+///
+/// Antivirus scanners and search indexers briefly hold files open, which
+/// makes deleting them fail on Windows. Missing files and denied access
+/// won't go away by waiting, so they are never retried.
+pub fn is_retryable(error: &io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION
+    if cfg!(windows) && matches!(error.raw_os_error(), Some(32) | Some(33)) {
+        return true;
+    }
+
+    matches!(
+        error.kind(),
+        io::ErrorKind::Interrupted
+            | io::ErrorKind::WouldBlock
+            | io::ErrorKind::TimedOut
+            | io::ErrorKind::ResourceBusy
+    )
+}
+
+/// Delete a path, retrying a few times if the error is transient
+///
+/// # Arguments
+/// * `path`   - The path to delete
+/// * `remove` - The function that deletes the path, usually `fs::remove_file`
+///
+/// # Returns
+/// The error of the last attempt if every attempt failed
+pub fn remove_with_retry<F>(path: &Path, mut remove: F) -> io::Result<()>
+where
+    F: FnMut(&Path) -> io::Result<()>,
+{
+    let mut delay = RETRY_DELAY;
+    let mut attempt = 1;
+
+    loop {
+        match remove(path) {
+            Err(e) if attempt < DELETE_ATTEMPTS && is_retryable(&e) => {
+                thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Size of the blocks of zeros written when shredding a file
+const SHRED_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Overwrite the contents of a file with zeros and flush them to disk
+///
+/// Files with more than one hard link are left alone, because the other
+/// links still expect the original contents.
+///
+/// # Arguments
+/// * `path`     - The file to overwrite
+/// * `metadata` - Metadata of the file, used for its size and link count
+pub fn shred_file(path: &Path, metadata: &fs::Metadata) -> io::Result<()> {
+    if hard_link_count(metadata) > 1 {
+        return Ok(());
+    }
+
+    let mut file = fs::OpenOptions::new().write(true).open(path)?;
+    let zeros = vec![0u8; SHRED_CHUNK_SIZE];
+    let mut remaining = metadata.len();
+
+    while remaining > 0 {
+        let chunk = remaining.min(SHRED_CHUNK_SIZE as u64) as usize;
+        file.write_all(&zeros[..chunk])?;
+        remaining -= chunk as u64;
+    }
+
+    file.sync_all()
+}
+
+#[cfg(unix)]
+fn hard_link_count(metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.nlink()
+}
+
+#[cfg(not(unix))]
+fn hard_link_count(_metadata: &fs::Metadata) -> u64 {
+    1
+}
+
+/// Remove the directories left empty after deleting some paths
+///
+/// Walks up from each deleted path and removes every parent directory that
+/// is now empty. Stops at the first non-empty directory, and never removes
+/// a scan root, anything outside the roots, protected paths or system paths.
+///
+/// # Arguments
+/// * `deleted`            - The paths that were deleted
+/// * `roots`              - The scan roots
+/// * `filter`             - Decides which paths are protected
+/// * `allow_system_paths` - Allow removing directories inside system directories
+///
+/// # Returns
+/// The number of directories removed
+pub fn prune_empty_dirs(
+    deleted: &[PathBuf],
+    roots: &[PathBuf],
+    filter: &SmartFilter,
+    allow_system_paths: bool,
+) -> usize {
+    let mut pruned = 0;
+
+    for path in deleted {
+        for dir in path.ancestors().skip(1) {
+            let inside_root = roots
+                .iter()
+                .any(|root| dir != root.as_path() && dir.starts_with(root));
+
+            if !inside_root
+                || filter.is_protected(dir)
+                || safety::check_deletion(dir, allow_system_paths).is_err()
+            {
+                break;
+            }
+
+            // Only succeeds if the directory is empty
+            if fs::remove_dir(dir).is_err() {
+                break;
+            }
+
+            pruned += 1;
+        }
+    }
+
+    pruned
+}
+
+/// Spells a path the same way however it was reached
+///
+/// The parent directory is canonicalized and the file name kept, so `.`,
+/// `..` and symlinked directories are resolved but a symlink itself is not
+/// replaced by its target.
+///
+/// # Arguments
+/// * `path` - The path of a file or link
+///
+/// # Returns
+/// The canonical path, or `None` if the file no longer exists
+pub fn canonical_path(path: &Path) -> Option<PathBuf> {
+    let canonical = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => {
+            dunce::canonicalize(parent).ok()?.join(name)
+        }
+        _ => dunce::canonicalize(path).ok()?,
+    };
+
+    fs::symlink_metadata(&canonical).ok()?;
+    Some(canonical)
+}
+
+/// Removes the results whose path was already found, keeping the first of each
+///
+/// # Arguments
+/// * `results` - The results of one or more scans
+pub fn dedup_by_path(results: &mut Vec<ScanResult>) {
+    let mut seen = HashSet::new();
+    results.retain(|result| seen.insert(result.path.clone()));
+}
+
+/// Removes the directories that are inside another directory of the list, and duplicates
+///
+/// Directories are compared as spelled, so they should be canonical. The
+/// remaining directories keep their order, except that a directory listed
+/// after one of its subdirectories takes the place of the last of them.
+///
+/// # Arguments
+/// * `dirs` - The directories to walk or delete
+pub fn remove_nested_dirs(dirs: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut outermost: Vec<PathBuf> = Vec::new();
+    for dir in dirs {
+        // Check if an already accepted directory is this one or a parent of it
+        if outermost.iter().any(|parent| dir.starts_with(parent)) {
+            continue;
+        }
+
+        // Remove any accepted subdirectories of this new directory
+        outermost.retain(|existing| !existing.starts_with(&dir));
+        outermost.push(dir);
+    }
+    outermost
+}
+
+/// Keeps only the `limit` largest results of each parent directory
+///
+/// The kept results stay in their order.
+///
+/// # Returns
+/// The directories that had results removed, with how many, ordered by directory
+pub fn limit_per_dir(results: &mut Vec<ScanResult>, limit: usize) -> Vec<(PathBuf, usize)> {
+    let mut by_dir: HashMap<PathBuf, Vec<usize>> = HashMap::new();
+    for (index, result) in results.iter().enumerate() {
+        let dir = result
+            .path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        by_dir.entry(dir).or_default().push(index);
+    }
+
+    let mut keep = vec![true; results.len()];
+    let mut hidden = Vec::new();
+    for (dir, mut indices) in by_dir {
+        if indices.len() <= limit {
+            continue;
+        }
+        indices.sort_by(|&a, &b| super::sort::largest_first(&results[a], &results[b]));
+        for &index in &indices[limit..] {
+            keep[index] = false;
+        }
+        hidden.push((dir, indices.len() - limit));
+    }
+    hidden.sort();
+
+    let mut keep = keep.into_iter();
+    results.retain(|_| keep.next().unwrap_or(true));
+    hidden
+}
+
+/// Reads a list of paths, one per line or separated by NUL bytes
+///
+/// Empty entries are skipped, and so are the `\r` of Windows line endings
+/// when the list is separated by lines.
+///
+/// # Arguments
+/// * `reader` - The list, e.g. the output of `fd` or `find`
+/// * `nul`    - Whether the paths are separated by NUL bytes, as with `find -print0`
+pub fn read_path_list<R: BufRead>(reader: R, nul: bool) -> io::Result<Vec<PathBuf>> {
+    let separator = if nul { b'\0' } else { b'\n' };
+    let mut paths = Vec::new();
+
+    for entry in reader.split(separator) {
+        let mut entry = entry?;
+        if !nul && entry.last() == Some(&b'\r') {
+            entry.pop();
+        }
+        if !entry.is_empty() {
+            paths.push(path_from_bytes(entry));
+        }
+    }
+
+    Ok(paths)
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt;
+    PathBuf::from(std::ffi::OsString::from_vec(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Adds up the sizes of all files in a directory and its subdirectories
+///
+/// Symlinks are not followed and entries that can't be read are skipped.
+///
+/// # Arguments
+/// * `path` - The directory to measure
+pub fn dir_size(path: &Path) -> u64 {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Finds the most recent modification time of a directory or anything inside it
+///
+/// # Arguments
+/// * `path` - The directory to check
+pub fn newest_modification(path: &Path) -> Option<SystemTime> {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.metadata().ok()?.modified().ok())
+        .max()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::plugins::tests::result;
 
     #[test]
     fn test_parse_size_string() {
@@ -103,6 +784,76 @@ mod tests {
         assert!(parse_size_string("MB100").is_err());
     }
 
+    #[test]
+    fn test_petabytes() {
+        assert_eq!(parse_size_string("2PB").unwrap(), 2 * 1024_u64.pow(5));
+        assert_eq!(parse_size_string("1P").unwrap(), 1024_u64.pow(5));
+        assert_eq!(parse_size_string("1PiB").unwrap(), 1024_u64.pow(5));
+        assert_eq!(parse_size_string_si("2PB").unwrap(), 2 * 1000_u64.pow(5));
+        assert_eq!(format_size(2 * 1024_u64.pow(5)), "2.00 PB");
+    }
+
+    #[test]
+    fn test_malformed_sizes_are_rejected() {
+        for input in ["", "   ", "-5MB", "-1", "1.2.3GB", "1..5GB", "GB", "5XB"] {
+            match parse_size_string(input) {
+                Err(PluginError::Configuration(message)) => {
+                    assert!(message.starts_with("Invalid size format"), "{}", message)
+                }
+                other => panic!(
+                    "Expected a configuration error for {:?}, got {:?}",
+                    input, other
+                ),
+            }
+        }
+
+        assert!(parse_size_string("-5MB")
+            .unwrap_err()
+            .to_string()
+            .contains("negative"));
+        assert!(parse_size_string("1.2.3GB")
+            .unwrap_err()
+            .to_string()
+            .contains("decimal point"));
+    }
+
+    #[test]
+    fn test_huge_sizes_saturate() {
+        assert_eq!(parse_size_string("18446744073709551615").unwrap(), u64::MAX);
+        assert_eq!(parse_size_string("16777216PB").unwrap(), u64::MAX);
+        assert_eq!(parse_size_string("99999999999PB").unwrap(), u64::MAX);
+        assert_eq!(parse_size_string("8PiB").unwrap(), 8 * 1024_u64.pow(5));
+    }
+
+    #[test]
+    fn test_binary_units() {
+        assert_eq!(parse_size_string("1MiB").unwrap(), 1048576);
+        assert_eq!(parse_size_string("1KiB").unwrap(), 1024);
+        assert_eq!(parse_size_string("2 GiB").unwrap(), 2 * 1024 * 1024 * 1024);
+        assert_eq!(parse_size_string("1tib").unwrap(), 1024_u64.pow(4));
+
+        // Binary units don't change in SI mode
+        assert_eq!(parse_size_string_si("1MiB").unwrap(), 1048576);
+    }
+
+    #[test]
+    fn test_si_units() {
+        // Legacy mode
+        assert_eq!(parse_size_string("1MB").unwrap(), 1048576);
+
+        // SI mode
+        assert_eq!(parse_size_string_si("1MB").unwrap(), 1000000);
+        assert_eq!(parse_size_string_si("1K").unwrap(), 1000);
+        assert_eq!(parse_size_string_si("1.5GB").unwrap(), 1_500_000_000);
+        assert_eq!(parse_size_string_si("100").unwrap(), 100);
+
+        assert_eq!(format_size_si(999), "999 B");
+        assert_eq!(format_size_si(1000), "1.00 KB");
+        assert_eq!(format_size_si(1_500_000), "1.50 MB");
+        assert_eq!(format_size_si(1_048_576), "1.05 MB");
+        assert_eq!(format_size_si(999_999), "1.00 MB");
+    }
+
     #[test]
     fn test_format_size() {
         assert_eq!(format_size(0), "0 B");
@@ -123,6 +874,12 @@ mod tests {
         assert_eq!(format_size(1025), "1.00 KB");
         assert_eq!(format_size(10 * 1024), "10.0 KB");
         assert_eq!(format_size(100 * 1024), "100 KB");
+
+        // Test rounding at precision and unit boundaries
+        assert_eq!(format_size(1024 * 1024 - 1), "1.00 MB");
+        assert_eq!(format_size(1023 * 1024), "1023 KB");
+        assert_eq!(format_size(10 * 1024 - 1), "10.0 KB");
+        assert_eq!(format_size(100 * 1024 - 1), "100 KB");
     }
 
     #[test]
@@ -144,4 +901,454 @@ mod tests {
             assert!((parsed as i64 - size as i64).abs() < 1024);
         }
     }
+
+    #[test]
+    fn test_available_space() {
+        let temp_dir = tempdir::TempDir::new("space_test").unwrap();
+        let space = available_space(temp_dir.path()).unwrap();
+
+        assert!(space > 0);
+        assert!(available_space(Path::new("/this/path/does/not/exist")).is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_string() {
+        assert_eq!(
+            parse_duration_string("90").unwrap(),
+            Duration::from_secs(90)
+        );
+        assert_eq!(
+            parse_duration_string("30s").unwrap(),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            parse_duration_string("5m").unwrap(),
+            Duration::from_secs(300)
+        );
+        assert_eq!(
+            parse_duration_string("2 H").unwrap(),
+            Duration::from_secs(7200)
+        );
+
+        assert!(parse_duration_string("").is_err());
+        assert!(parse_duration_string("0s").is_err());
+        assert!(parse_duration_string("5d").is_err());
+        assert!(parse_duration_string("-5s").is_err());
+        assert!(parse_duration_string("1.5m").is_err());
+        assert!(parse_duration_string(&format!("{}h", u64::MAX / 60)).is_err());
+        assert_eq!(
+            parse_duration_string(&u64::MAX.to_string()).unwrap(),
+            Duration::from_secs(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(0)), "0.0s");
+        assert_eq!(format_duration(Duration::from_millis(4250)), "4.2s");
+        assert_eq!(format_duration(Duration::from_secs(192)), "3m 12s");
+        assert_eq!(
+            format_duration(Duration::from_secs(2 * 3600 + 5 * 60 + 9)),
+            "2h 5m"
+        );
+    }
+
+    #[test]
+    fn test_format_age() {
+        const DAY: u64 = 24 * 60 * 60;
+
+        assert_eq!(format_age(Duration::from_secs(0)), "0s ago");
+        assert_eq!(format_age(Duration::from_secs(59)), "59s ago");
+        assert_eq!(format_age(Duration::from_secs(60)), "1m ago");
+        assert_eq!(format_age(Duration::from_secs(3599)), "59m ago");
+        assert_eq!(format_age(Duration::from_secs(3600)), "1h ago");
+        assert_eq!(format_age(Duration::from_secs(DAY - 1)), "23h ago");
+        assert_eq!(format_age(Duration::from_secs(DAY)), "1d ago");
+        assert_eq!(format_age(Duration::from_secs(29 * DAY)), "29d ago");
+        assert_eq!(format_age(Duration::from_secs(30 * DAY)), "1mo ago");
+        assert_eq!(format_age(Duration::from_secs(364 * DAY)), "12mo ago");
+        assert_eq!(format_age(Duration::from_secs(365 * DAY)), "1y ago");
+        assert_eq!(format_age(Duration::from_secs(3 * 365 * DAY)), "3y ago");
+    }
+
+    #[test]
+    fn test_remove_nested_dirs() {
+        let dirs = |paths: &[&str]| paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+
+        assert_eq!(
+            remove_nested_dirs(dirs(&[
+                "/home/user/Downloads",
+                "/data",
+                "/home/user",
+                "/data",
+                "/home/user/Downloads/iso",
+                "/home/username",
+            ])),
+            dirs(&["/data", "/home/user", "/home/username"])
+        );
+        assert!(remove_nested_dirs(vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_limit_per_dir() {
+        let mut results = Vec::new();
+        for i in 1..=6 {
+            results.push(result(format!("/videos/{}.mp4", i), i * 100));
+            results.push(result(format!("/photos/{}.raw", i), i * 10));
+        }
+        results.push(result("/downloads/setup.iso", 50));
+
+        let hidden = limit_per_dir(&mut results, 2);
+        assert_eq!(
+            hidden,
+            vec![(PathBuf::from("/photos"), 4), (PathBuf::from("/videos"), 4)]
+        );
+
+        let paths: Vec<&str> = results.iter().map(|r| r.path.to_str().unwrap()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/videos/5.mp4",
+                "/photos/5.raw",
+                "/videos/6.mp4",
+                "/photos/6.raw",
+                "/downloads/setup.iso"
+            ]
+        );
+
+        // Nothing is hidden from directories within the limit
+        assert!(limit_per_dir(&mut results, 2).is_empty());
+        assert_eq!(results.len(), 5);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let at = |secs: u64| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+
+        assert_eq!(parse_date("1970-01-01"), Ok(at(0)));
+        assert_eq!(parse_date("2023-01-01"), Ok(at(1_672_531_200)));
+        assert_eq!(parse_date("2024-02-29"), Ok(at(1_709_164_800)));
+        assert_eq!(parse_date("2023-11-14T22:13:20Z"), Ok(at(1_700_000_000)));
+        assert_eq!(parse_date("2023-11-14t22:13:20z"), Ok(at(1_700_000_000)));
+        assert_eq!(parse_date("2023-11-14 22:13:20Z"), Ok(at(1_700_000_000)));
+        assert_eq!(
+            parse_date("2023-11-15T00:13:20+02:00"),
+            Ok(at(1_700_000_000))
+        );
+        assert_eq!(
+            parse_date("2023-11-14T17:13:20-05:00"),
+            Ok(at(1_700_000_000))
+        );
+        assert_eq!(
+            parse_date("2023-11-14T22:13:20.25Z"),
+            Ok(at(1_700_000_000) + Duration::from_millis(250))
+        );
+        assert_eq!(
+            parse_date("1969-12-31"),
+            Ok(SystemTime::UNIX_EPOCH - Duration::from_secs(86_400))
+        );
+
+        // Dates the system clock can't hold are errors rather than panics
+        if SystemTime::UNIX_EPOCH
+            .checked_sub(Duration::from_secs(62_167_219_200))
+            .is_none()
+        {
+            assert!(parse_date("0000-01-01").is_err());
+        } else {
+            assert!(parse_date("0000-01-01").is_ok());
+        }
+
+        // Round trips through the formatter
+        let time = at(1_234_567_890);
+        assert_eq!(parse_date(&format_rfc3339(time)), Ok(time));
+
+        for invalid in [
+            "",
+            "2023",
+            "2023-1-01",
+            "2023-13-01",
+            "2023-02-29",
+            "2023-04-31",
+            "2023-01-01T12:00:00",
+            "2023-01-01T25:00:00Z",
+            "2023-01-01T12:00Z",
+            "2023-01-01T12:00:00+0200",
+            "2023-01-01T12:00:00.Z",
+            "yesterday",
+        ] {
+            assert!(parse_date(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_format_rfc3339() {
+        let at = |secs| format_rfc3339(SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+
+        assert_eq!(at(0), "1970-01-01T00:00:00Z");
+        assert_eq!(at(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(at(1_700_000_000), "2023-11-14T22:13:20Z");
+        assert_eq!(at(4_107_542_399), "2100-02-28T23:59:59Z");
+        assert_eq!(
+            format_rfc3339(SystemTime::UNIX_EPOCH - Duration::from_secs(1)),
+            "1970-01-01T00:00:00Z"
+        );
+    }
+
+    #[test]
+    fn test_is_volume_full() {
+        const GB: u64 = 1024 * 1024 * 1024;
+
+        assert!(is_volume_full(10 * GB, 100 * GB, 90.0));
+        assert!(is_volume_full(5 * GB, 100 * GB, 90.0));
+        assert!(!is_volume_full(11 * GB, 100 * GB, 90.0));
+        assert!(!is_volume_full(100 * GB, 100 * GB, 90.0));
+        assert!(is_volume_full(100 * GB, 100 * GB, 0.0));
+        assert!(is_volume_full(0, 100 * GB, 100.0));
+
+        // Reserved blocks can make the free space look larger than it is
+        assert!(!is_volume_full(120 * GB, 100 * GB, 10.0));
+        assert!(!is_volume_full(0, 0, 0.0));
+
+        assert_eq!(usage_percent(25 * GB, 100 * GB), 75.0);
+    }
+
+    #[test]
+    fn test_distinct_volumes() {
+        let temp_dir = tempdir::TempDir::new("volume_test").unwrap();
+        let first = temp_dir.path().join("first");
+        let second = temp_dir.path().join("second");
+        let missing = temp_dir.path().join("missing");
+        fs::create_dir(&first).unwrap();
+        fs::create_dir(&second).unwrap();
+
+        // Both directories are on the volume of the temp dir
+        assert_eq!(
+            distinct_volumes(&[first.clone(), second]),
+            vec![first.clone()]
+        );
+        if cfg!(unix) {
+            assert_eq!(
+                distinct_volumes(&[first.clone(), missing.clone()]),
+                vec![first, missing]
+            );
+        }
+    }
+
+    #[test]
+    fn test_free_space_includes_reserved_space() {
+        let dir = std::env::temp_dir();
+        let free = free_space(&dir).unwrap();
+        assert!(free >= available_space(&dir).unwrap());
+        assert!(free <= total_space(&dir).unwrap());
+    }
+
+    #[test]
+    fn test_humanize_age() {
+        const DAY: u64 = 24 * 60 * 60;
+
+        assert_eq!(humanize_age(Duration::from_secs(0)), "just now");
+        assert_eq!(humanize_age(Duration::from_secs(59)), "just now");
+        assert_eq!(humanize_age(Duration::from_secs(60)), "1 minute ago");
+        assert_eq!(humanize_age(Duration::from_secs(3599)), "59 minutes ago");
+        assert_eq!(humanize_age(Duration::from_secs(3600)), "1 hour ago");
+        assert_eq!(humanize_age(Duration::from_secs(3 * 3600)), "3 hours ago");
+        assert_eq!(humanize_age(Duration::from_secs(DAY - 1)), "23 hours ago");
+        assert_eq!(humanize_age(Duration::from_secs(DAY)), "1 day ago");
+        assert_eq!(humanize_age(Duration::from_secs(2 * DAY)), "2 days ago");
+        assert_eq!(humanize_age(Duration::from_secs(29 * DAY)), "29 days ago");
+        assert_eq!(humanize_age(Duration::from_secs(30 * DAY)), "1 month ago");
+        assert_eq!(humanize_age(Duration::from_secs(150 * DAY)), "5 months ago");
+        assert_eq!(
+            humanize_age(Duration::from_secs(364 * DAY)),
+            "12 months ago"
+        );
+        assert_eq!(humanize_age(Duration::from_secs(365 * DAY)), "1 year ago");
+        assert_eq!(
+            humanize_age(Duration::from_secs(3 * 365 * DAY)),
+            "3 years ago"
+        );
+    }
+
+    #[test]
+    fn test_remove_with_retry() {
+        let busy = || io::Error::from(io::ErrorKind::ResourceBusy);
+
+        // Transient errors are retried until the deletion succeeds
+        let mut attempts = 0;
+        let result = remove_with_retry(Path::new("file"), |_| {
+            attempts += 1;
+            if attempts < 3 {
+                Err(busy())
+            } else {
+                Ok(())
+            }
+        });
+        assert!(result.is_ok());
+        assert_eq!(attempts, 3);
+
+        // Giving up after the last attempt
+        let mut attempts = 0;
+        let result = remove_with_retry(Path::new("file"), |_| {
+            attempts += 1;
+            Err(busy())
+        });
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::ResourceBusy);
+        assert_eq!(attempts, DELETE_ATTEMPTS);
+
+        // Permanent errors fail immediately
+        for kind in [io::ErrorKind::NotFound, io::ErrorKind::PermissionDenied] {
+            let mut attempts = 0;
+            let result = remove_with_retry(Path::new("file"), |_| {
+                attempts += 1;
+                Err(io::Error::from(kind))
+            });
+            assert_eq!(result.unwrap_err().kind(), kind);
+            assert_eq!(attempts, 1);
+        }
+    }
+
+    #[test]
+    fn test_canonical_path() {
+        let temp_dir = tempdir::TempDir::new("canonical_test").unwrap();
+        let root = dunce::canonicalize(temp_dir.path()).unwrap();
+        fs::create_dir(root.join("a")).unwrap();
+        fs::write(root.join("a").join("file.bin"), "data").unwrap();
+
+        let expected = Some(root.join("a").join("file.bin"));
+        assert_eq!(canonical_path(&root.join("a/./file.bin")), expected);
+        assert_eq!(canonical_path(&root.join("a/../a/file.bin")), expected);
+
+        // Vanished files have no canonical path
+        assert_eq!(canonical_path(&root.join("a").join("gone.bin")), None);
+        assert_eq!(canonical_path(&root.join("gone").join("file.bin")), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_canonical_path_keeps_symlinks() {
+        let temp_dir = tempdir::TempDir::new("canonical_test").unwrap();
+        let root = dunce::canonicalize(temp_dir.path()).unwrap();
+        fs::write(root.join("target.bin"), "data").unwrap();
+        std::os::unix::fs::symlink(root.join("target.bin"), root.join("link.bin")).unwrap();
+        std::os::unix::fs::symlink(root.join("missing.bin"), root.join("broken.bin")).unwrap();
+
+        assert_eq!(
+            canonical_path(&root.join("./link.bin")),
+            Some(root.join("link.bin"))
+        );
+        assert_eq!(
+            canonical_path(&root.join("broken.bin")),
+            Some(root.join("broken.bin"))
+        );
+    }
+
+    #[test]
+    fn test_read_path_list() {
+        let lines = "/data/a.bin\n\n./b c.bin\r\n/data/d.bin";
+        assert_eq!(
+            read_path_list(lines.as_bytes(), false).unwrap(),
+            vec![
+                PathBuf::from("/data/a.bin"),
+                PathBuf::from("./b c.bin"),
+                PathBuf::from("/data/d.bin"),
+            ]
+        );
+
+        let nul_separated = "/data/new\nline.bin\0/data/e.bin\0";
+        assert_eq!(
+            read_path_list(nul_separated.as_bytes(), true).unwrap(),
+            vec![
+                PathBuf::from("/data/new\nline.bin"),
+                PathBuf::from("/data/e.bin"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dir_size() {
+        let temp_dir = tempdir::TempDir::new("dir_size_test").unwrap();
+        let nested = temp_dir.path().join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(temp_dir.path().join("one.bin"), vec![0; 100]).unwrap();
+        fs::write(nested.join("two.bin"), vec![0; 250]).unwrap();
+
+        assert_eq!(dir_size(temp_dir.path()), 350);
+        assert_eq!(dir_size(&nested), 250);
+        assert_eq!(dir_size(&temp_dir.path().join("missing")), 0);
+    }
+
+    #[test]
+    fn test_prune_empty_dirs() {
+        let temp_dir = tempdir::TempDir::new("prune_test").unwrap();
+        let root = temp_dir.path().to_path_buf();
+
+        // root/a/b/c/deleted.bin, root/a/kept.txt, root/d/e/deleted.bin
+        let deep = root.join("a").join("b").join("c");
+        let other = root.join("d").join("e");
+        fs::create_dir_all(&deep).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        fs::write(root.join("a").join("kept.txt"), "keep").unwrap();
+
+        let deleted = vec![deep.join("deleted.bin"), other.join("deleted.bin")];
+        let pruned = prune_empty_dirs(
+            &deleted,
+            std::slice::from_ref(&root),
+            &SmartFilter::new(),
+            false,
+        );
+
+        assert_eq!(pruned, 4);
+        assert!(!root.join("a").join("b").exists());
+        assert!(root.join("a").join("kept.txt").exists());
+        assert!(!root.join("d").exists());
+        assert!(root.exists());
+    }
+
+    #[test]
+    fn test_prune_empty_dirs_keeps_protected_dirs() {
+        let temp_dir = tempdir::TempDir::new("prune_test").unwrap();
+        let root = temp_dir.path().to_path_buf();
+
+        let protected = root.join(".ssh");
+        let inner = protected.join("inner");
+        fs::create_dir_all(&inner).unwrap();
+
+        let mut filter = SmartFilter::new();
+        filter.add_protected_pattern(".ssh");
+
+        let deleted = vec![inner.join("deleted.bin")];
+        let pruned = prune_empty_dirs(&deleted, std::slice::from_ref(&root), &filter, false);
+
+        assert_eq!(pruned, 1);
+        assert!(!inner.exists());
+        assert!(protected.exists());
+    }
+
+    #[test]
+    fn test_shred_file() {
+        let temp_dir = tempdir::TempDir::new("shred_test").unwrap();
+        let path = temp_dir.path().join("secret.bin");
+        let content: Vec<u8> = (0..SHRED_CHUNK_SIZE + 100)
+            .map(|i| (i % 250 + 1) as u8)
+            .collect();
+        fs::write(&path, &content).unwrap();
+
+        shred_file(&path, &fs::metadata(&path).unwrap()).unwrap();
+
+        let shredded = fs::read(&path).unwrap();
+        assert_eq!(shredded.len(), content.len());
+        assert!(shredded.iter().all(|&b| b == 0));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shred_file_skips_hard_links() {
+        let temp_dir = tempdir::TempDir::new("shred_test").unwrap();
+        let path = temp_dir.path().join("original.bin");
+        fs::write(&path, b"shared").unwrap();
+        fs::hard_link(&path, temp_dir.path().join("link.bin")).unwrap();
+
+        shred_file(&path, &fs::metadata(&path).unwrap()).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"shared");
+    }
 }
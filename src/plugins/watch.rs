@@ -0,0 +1,199 @@
+/// Rescans the paths on an interval for `--watch` and reports what changed
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use yansi::Color;
+
+use super::{utils, FeaturePlugin, PluginError, ScanResult};
+use crate::output;
+use crate::settings::Settings;
+
+/// A file that got larger between two scans
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrownFile {
+    pub result: ScanResult,
+    pub previous_size: u64,
+}
+
+/// The changes between two scans
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScanDiff {
+    /// Files that were not found by the previous scan
+    pub added: Vec<ScanResult>,
+    /// Files that are larger than in the previous scan
+    pub grown: Vec<GrownFile>,
+    /// Files that are no longer found
+    pub removed: Vec<ScanResult>,
+}
+
+impl ScanDiff {
+    /// Check if nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.grown.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Compares two scans of the same paths
+///
+/// Files that shrank are not reported, since a shrinking file is no cause for alarm.
+///
+/// # Arguments
+/// * `old` - The results of the previous scan
+/// * `new` - The results of the current scan
+pub fn diff_scans(old: &[ScanResult], new: &[ScanResult]) -> ScanDiff {
+    let old_sizes: HashMap<&PathBuf, u64> = old.iter().map(|r| (&r.path, r.size)).collect();
+    let new_paths: HashSet<&PathBuf> = new.iter().map(|r| &r.path).collect();
+    let mut diff = ScanDiff::default();
+
+    for result in new {
+        match old_sizes.get(&result.path) {
+            None => diff.added.push(result.clone()),
+            Some(&previous_size) if result.size > previous_size => diff.grown.push(GrownFile {
+                result: result.clone(),
+                previous_size,
+            }),
+            Some(_) => {}
+        }
+    }
+
+    diff.removed = old
+        .iter()
+        .filter(|result| !new_paths.contains(&result.path))
+        .cloned()
+        .collect();
+
+    diff
+}
+
+/// Prints the changes between two scans
+///
+/// # Arguments
+/// * `diff`        - The changes to print
+/// * `format_size` - Formats a size in bytes for display
+pub fn print_diff(diff: &ScanDiff, format_size: fn(u64) -> String) {
+    for result in &diff.added {
+        output::println(
+            "Added",
+            Color::Green,
+            &format!("{} ({})", result.path.display(), format_size(result.size)),
+        );
+    }
+
+    for grown in &diff.grown {
+        output::println(
+            "Grown",
+            Color::Yellow,
+            &format!(
+                "{} ({} -> {}, +{})",
+                grown.result.path.display(),
+                format_size(grown.previous_size),
+                format_size(grown.result.size),
+                format_size(grown.result.size - grown.previous_size)
+            ),
+        );
+    }
+
+    for result in &diff.removed {
+        output::println("Removed", Color::Blue, &result.path.to_string_lossy());
+    }
+}
+
+/// Scans the paths every `interval` and prints what changed, until the process is interrupted
+///
+/// Nothing is selected or deleted. Without raw mode or an alternate screen
+/// there is no terminal state to restore, so Ctrl-C can end the loop at any point.
+///
+/// # Arguments
+/// * `plugins`  - The enabled feature plugins
+/// * `settings` - The application settings struct
+/// * `interval` - The time to wait between scans
+pub fn watch(
+    mut plugins: Vec<Box<dyn FeaturePlugin>>,
+    settings: &Settings,
+    interval: Duration,
+) -> Result<(), PluginError> {
+    let format_size = if settings.si {
+        utils::format_size_si
+    } else {
+        utils::format_size
+    };
+
+    for plugin in plugins.iter_mut() {
+        plugin.configure(settings)?;
+    }
+
+    let mut previous: Option<Vec<ScanResult>> = None;
+    loop {
+        let mut results = Vec::new();
+        for plugin in &plugins {
+            for path in &settings.paths {
+                results.append(&mut plugin.scan(path)?);
+            }
+        }
+        utils::dedup_by_path(&mut results);
+
+        match &previous {
+            None => {
+                let total_size: u64 = results.iter().map(|r| r.size).sum();
+                output::println(
+                    "Watching",
+                    Color::Blue,
+                    &format!(
+                        "{} items ({}), rescanning every {}. Press Ctrl-C to stop",
+                        results.len(),
+                        format_size(total_size),
+                        utils::format_duration(interval)
+                    ),
+                );
+            }
+            Some(old) => print_diff(&diff_scans(old, &results), format_size),
+        }
+
+        previous = Some(results);
+        thread::sleep(interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::tests::result;
+
+    #[test]
+    fn test_diff_scans() {
+        let old = vec![
+            result("/logs/app.log", 100),
+            result("/logs/old.log", 500),
+            result("/build/out.bin", 300),
+            result("/data/shrunk.dat", 900),
+        ];
+        let new = vec![
+            result("/logs/app.log", 250),
+            result("/build/out.bin", 300),
+            result("/data/shrunk.dat", 400),
+            result("/logs/new.log", 700),
+        ];
+
+        let diff = diff_scans(&old, &new);
+        assert_eq!(diff.added, vec![result("/logs/new.log", 700)]);
+        assert_eq!(
+            diff.grown,
+            vec![GrownFile {
+                result: result("/logs/app.log", 250),
+                previous_size: 100,
+            }]
+        );
+        assert_eq!(diff.removed, vec![result("/logs/old.log", 500)]);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn test_diff_scans_unchanged() {
+        let scan = vec![result("/logs/app.log", 100)];
+
+        assert!(diff_scans(&scan, &scan).is_empty());
+        assert!(diff_scans(&[], &[]).is_empty());
+    }
+}
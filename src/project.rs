@@ -1,7 +1,18 @@
 use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use globset::GlobBuilder;
+use walkdir::WalkDir;
 
 use crate::output;
 use crate::swpfile::parse_swpfile;
+use crate::utils::file_utils::{file_name, is_glob, matches_glob};
+
+/// How deep `**/` patterns in a `.swpfile` look for matching directories
+const SWPFILE_MAX_DEPTH: usize = 10;
+
+/// Directories that never count towards the modified date of a project
+const ALWAYS_IGNORE_DIRS: [&str; 3] = [".idea", ".vscode", ".git"];
 
 /// Describes a discovered cleanable project
 #[derive(Debug)]
@@ -12,8 +23,8 @@ pub struct Project {
     /// Directories containing dependencies
     dependency_dirs: Vec<PathBuf>,
 
-    /// Timestamp indicating when the project was last modified
-    #[allow(dead_code)]
+    /// Timestamp indicating when the project was last modified, in seconds
+    /// since the Unix epoch. Zero until `update_last_modified` is called.
     last_modified: u64,
 }
 
@@ -34,6 +45,42 @@ impl Project {
         &self.root
     }
 
+    /// Gets when a source file of this project was last modified, in
+    /// seconds since the Unix epoch, or zero if it's unknown
+    pub fn last_modified(&self) -> u64 {
+        self.last_modified
+    }
+
+    /// Sets `last_modified` to the modified time of the newest file in the
+    /// project, not counting the cleanable directories and editor or VCS
+    /// directories
+    ///
+    /// Call this after all cleanable directories were added, so their
+    /// contents aren't mistaken for source files.
+    pub fn update_last_modified(&mut self) {
+        let dependency_dirs = &self.dependency_dirs;
+
+        self.last_modified = WalkDir::new(&self.root)
+            .into_iter()
+            .filter_entry(|entry| {
+                if !entry.file_type().is_dir() {
+                    return true;
+                }
+
+                let ignored = ALWAYS_IGNORE_DIRS.contains(&file_name(entry.path()));
+
+                !ignored && !dependency_dirs.iter().any(|dir| dir == entry.path())
+            })
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| !entry.file_type().is_dir())
+            .filter_map(|entry| entry.metadata().ok())
+            .filter_map(|metadata| metadata.modified().ok())
+            .filter_map(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|since_epoch| since_epoch.as_secs())
+            .max()
+            .unwrap_or(0);
+    }
+
     /// Marks a subdirectory of this project's root directory as cleanable,
     /// if that directory exists. If the subdirectory doesn't exist, nothing
     /// happens.
@@ -65,7 +112,62 @@ impl Project {
         }
     }
 
+    /// Add directories matching a relative glob path (e.g., "packages/*/dist")
+    ///
+    /// `*` and `?` match within one component of the path, `**` matches any
+    /// number of directories. Only directories inside the project can match,
+    /// so patterns like `../*` find nothing.
+    pub fn add_cleanable_dirs_by_glob(&mut self, pattern: &str) {
+        let matcher = match GlobBuilder::new(pattern).literal_separator(true).build() {
+            Ok(glob) => glob.compile_matcher(),
+            Err(e) => {
+                output::error(format!("Invalid pattern {}", pattern));
+                output::println_info(e.to_string());
+                return;
+            }
+        };
+
+        let recursive = pattern.contains("**");
+        let max_depth = if recursive {
+            SWPFILE_MAX_DEPTH
+        } else {
+            pattern.split('/').filter(|c| !c.is_empty()).count()
+        };
+
+        let mut matched = Vec::new();
+        let mut walker = WalkDir::new(&self.root)
+            .min_depth(1)
+            .max_depth(max_depth)
+            .into_iter();
+        while let Some(entry) = walker.next() {
+            let Ok(entry) = entry else { continue };
+            if !entry.file_type().is_dir() {
+                continue;
+            }
+
+            let path = entry.path();
+            let matches = path
+                .strip_prefix(&self.root)
+                .is_ok_and(|relative| matcher.is_match(relative));
+            if matches {
+                matched.push(path.to_path_buf());
+                // Everything inside is cleaned along with the directory
+                walker.skip_current_dir();
+            } else if recursive && is_skipped_dir(file_name(path)) {
+                walker.skip_current_dir();
+            }
+        }
+
+        for dir in matched {
+            if !self.dependency_dirs.contains(&dir) {
+                self.dependency_dirs.push(dir);
+            }
+        }
+    }
+
     /// Recursively find and add directories with a specific name (e.g., "__pycache__")
+    ///
+    /// The name may contain `*` and `?` wildcards.
     pub fn add_cleanable_dirs_recursive(&mut self, dir_name: &str, max_depth: usize) {
         self.find_dirs_recursive(&self.root.clone(), dir_name, 0, max_depth);
     }
@@ -86,11 +188,13 @@ impl Project {
                 let entry_path = entry.path();
                 if entry_path.is_dir() {
                     if let Some(name) = entry.file_name().to_str() {
-                        if name == target_name && !self.dependency_dirs.contains(&entry_path) {
+                        if matches_glob(name, target_name)
+                            && !self.dependency_dirs.contains(&entry_path)
+                        {
                             self.dependency_dirs.push(entry_path.clone());
                         }
                         // Don't recurse into hidden directories or common large directories
-                        if !name.starts_with('.') && name != "node_modules" && name != "target" {
+                        if !is_skipped_dir(name) {
                             self.find_dirs_recursive(
                                 &entry_path,
                                 target_name,
@@ -104,6 +208,13 @@ impl Project {
         }
     }
 
+    /// Adds the cleanable directories listed in a `.swpfile`
+    ///
+    /// Lines are plain directory names, glob paths like `*.egg-info`, or
+    /// `**/name` to find directories with that name anywhere in the project.
+    ///
+    /// # Arguments
+    /// `filename` - Name of the file inside the project root directory
     pub fn load_swpfile(&mut self, filename: &str) {
         let paths = match parse_swpfile(&self.root, &PathBuf::from(filename)) {
             Ok(paths) => paths,
@@ -118,7 +229,15 @@ impl Project {
         };
 
         for path in paths {
-            self.add_cleanable_dir_if_exists(path);
+            let path = path.trim_end_matches('/');
+
+            if let Some(name) = path.strip_prefix("**/") {
+                self.add_cleanable_dirs_recursive(name, SWPFILE_MAX_DEPTH);
+            } else if is_glob(path) {
+                self.add_cleanable_dirs_by_glob(path);
+            } else {
+                self.add_cleanable_dir_if_exists(path);
+            }
         }
     }
 
@@ -133,3 +252,161 @@ impl Project {
         self.dependency_dirs
     }
 }
+
+/// Checks if recursive searches skip a directory, which they do for hidden
+/// directories and the common large ones
+fn is_skipped_dir(name: &str) -> bool {
+    name.starts_with('.') || name == "node_modules" || name == "target"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use std::time::{Duration, SystemTime};
+    use tempdir::TempDir;
+
+    fn set_modified(path: &Path, time: SystemTime) {
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+
+    fn unix_secs(time: SystemTime) -> u64 {
+        time.duration_since(UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    #[test]
+    fn swpfile_with_globs() {
+        let temp_dir = TempDir::new("project_test").unwrap();
+        let root = temp_dir.path();
+
+        for dir in [
+            "target",
+            "foo.egg-info",
+            "bar.egg-info",
+            "src/__pycache__",
+            "src/deep/__pycache__",
+            "packages/a/dist",
+            "packages/b/dist",
+            "packages/c",
+            "keep",
+        ] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(root.join("not-a-dir.egg-info"), "").unwrap();
+        fs::write(
+            root.join(".swpfile"),
+            "# Build output\n\ntarget/\nmissing\n*.egg-info\n**/__pycache__\npackages/*/dist\n",
+        )
+        .unwrap();
+
+        let mut project = Project::new(root);
+        project.load_swpfile(".swpfile");
+
+        let mut dirs = project.into_cleanable_dirs();
+        dirs.sort();
+
+        let mut expected: Vec<PathBuf> = [
+            "target",
+            "foo.egg-info",
+            "bar.egg-info",
+            "src/__pycache__",
+            "src/deep/__pycache__",
+            "packages/a/dist",
+            "packages/b/dist",
+        ]
+        .iter()
+        .map(|dir| root.join(dir))
+        .collect();
+        expected.sort();
+
+        assert_eq!(dirs, expected);
+    }
+
+    #[test]
+    fn globs_stay_inside_the_project() {
+        let temp_dir = TempDir::new("project_test").unwrap();
+        let root = temp_dir.path().join("project");
+
+        for dir in [
+            "outside",
+            "project/packages/a/dist",
+            "project/packages/nested/b/dist",
+            "project/packages/c/dist/dist",
+        ] {
+            fs::create_dir_all(temp_dir.path().join(dir)).unwrap();
+        }
+
+        let mut project = Project::new(&root);
+        project.add_cleanable_dirs_by_glob("../*");
+        project.add_cleanable_dirs_by_glob("packages/*/../*/dist");
+        assert!(project.into_cleanable_dirs().is_empty());
+
+        // `*` stays within one directory, `**` crosses any number of them
+        let mut project = Project::new(&root);
+        project.add_cleanable_dirs_by_glob("packages/*/dist");
+        let mut dirs = project.into_cleanable_dirs();
+        dirs.sort();
+        assert_eq!(
+            dirs,
+            vec![root.join("packages/a/dist"), root.join("packages/c/dist")]
+        );
+
+        let mut project = Project::new(&root);
+        project.add_cleanable_dirs_by_glob("packages/**/dist");
+        let mut dirs = project.into_cleanable_dirs();
+        dirs.sort();
+        assert_eq!(
+            dirs,
+            vec![
+                root.join("packages/a/dist"),
+                root.join("packages/c/dist"),
+                root.join("packages/nested/b/dist"),
+            ]
+        );
+    }
+
+    #[test]
+    fn last_modified_is_newest_source_file() {
+        let temp_dir = TempDir::new("project_test").unwrap();
+        let root = temp_dir.path();
+        let year_ago = SystemTime::now() - Duration::from_secs(365 * 24 * 60 * 60);
+        let hour_ago = SystemTime::now() - Duration::from_secs(60 * 60);
+
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("package.json"), "{}").unwrap();
+        fs::write(root.join("src").join("index.js"), "").unwrap();
+        set_modified(&root.join("package.json"), year_ago);
+        set_modified(&root.join("src").join("index.js"), hour_ago);
+
+        let mut project = Project::new(root);
+        assert_eq!(project.last_modified(), 0);
+
+        project.update_last_modified();
+        assert_eq!(project.last_modified(), unix_secs(hour_ago));
+    }
+
+    #[test]
+    fn last_modified_ignores_cleanable_dirs() {
+        let temp_dir = TempDir::new("project_test").unwrap();
+        let root = temp_dir.path();
+        let year_ago = SystemTime::now() - Duration::from_secs(365 * 24 * 60 * 60);
+
+        fs::create_dir_all(root.join("node_modules")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join("package.json"), "{}").unwrap();
+        fs::write(root.join("node_modules").join("fresh.js"), "").unwrap();
+        fs::write(root.join(".git").join("index"), "").unwrap();
+        set_modified(&root.join("package.json"), year_ago);
+
+        let mut project = Project::new(root);
+        project.add_cleanable_dir_if_exists("node_modules");
+        project.update_last_modified();
+
+        assert_eq!(project.last_modified(), unix_secs(year_ago));
+    }
+}
@@ -1,15 +1,89 @@
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use dunce::canonicalize;
 use regex::Regex;
+use structopt::clap::ArgMatches;
 use structopt::StructOpt;
 
+use crate::config::Config;
+use crate::output::{ColorChoice, OutputFormat};
+use crate::plugins::filter::{FileType, CASE_INSENSITIVE_FILESYSTEM};
+use crate::plugins::sort::SortBy;
+use crate::plugins::{utils, PluginError, RiskLevel};
+
+#[derive(Debug)]
 pub enum SettingsError {
     InvalidPath(PathBuf),
+    InvalidConfig(PathBuf, String),
+    /// A size flag that is not a valid size, with the reason it was rejected
+    InvalidSize {
+        option: &'static str,
+        value: String,
+        reason: String,
+    },
+    /// A `--when-full` percentage outside of 0 to 100
+    InvalidPercent(f64),
+    /// A number of days too large to count in seconds
+    InvalidDays {
+        option: &'static str,
+        days: u64,
+    },
+    /// A flag given without the flags it needs
+    MissingFlag {
+        option: &'static str,
+        requires: &'static str,
+    },
+}
+
+impl std::fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingsError::InvalidPath(path) => write!(f, "Invalid path: {}", path.display()),
+            SettingsError::InvalidConfig(path, message) => {
+                write!(f, "Invalid config file {}: {}", path.display(), message)
+            }
+            SettingsError::InvalidSize {
+                option,
+                value,
+                reason,
+            } => write!(f, "Invalid size {} for {}: {}", value, option, reason),
+            SettingsError::InvalidPercent(percent) => write!(
+                f,
+                "Invalid percentage {} for --when-full, expected 0 to 100",
+                percent
+            ),
+            SettingsError::InvalidDays { option, days } => {
+                write!(
+                    f,
+                    "Invalid number of days {} for {}, too large",
+                    days, option
+                )
+            }
+            SettingsError::MissingFlag { option, requires } => {
+                write!(f, "{} requires {}", option, requires)
+            }
+        }
+    }
 }
 
+impl std::error::Error for SettingsError {}
+
 pub type Result<T> = std::result::Result<T, SettingsError>;
 
+/// Modes that replace the regular sweep
+#[derive(Debug, StructOpt)]
+pub enum Command {
+    /// Show why a file gets its risk level, without scanning or deleting anything.
+    Explain {
+        /// The file to explain
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+    },
+    /// Show the files the last cleanup deleted, as recorded before it started.
+    UndoReport,
+}
+
 /// Deletes unnecessary build artifacts and dependency directories in your projects.
 ///
 /// Detects Rust, Java and NodeJS projects by default, or define your own cleanable directories by adding a `.cleanuprc` file to your project directory.
@@ -22,26 +96,323 @@ pub struct Settings {
     #[structopt(name = "PATH...")]
     pub paths: Vec<PathBuf>,
 
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+
     /// Sweep even projects that were modified within the last 30 days.
     #[structopt(short = "a", long = "all")]
     pub all: bool,
 
-    /// Exclude projects in directories matched by this regex pattern.
-    #[structopt(short = "i", long = "ignore")]
-    pub ignore: Option<Regex>,
+    /// Exclude projects in directories matched by this regex pattern. Repeat the flag to
+    /// ignore paths matching any of several patterns.
+    #[structopt(short = "i", long = "ignore", number_of_values = 1)]
+    pub ignore: Vec<Regex>,
 
-    /// Skip confirmation prompt before removing directories. Use at your own risk.
+    /// Classify the files listed in FILE instead of scanning the paths, e.g. the
+    /// output of `fd` or `find`. Use `-` to read the list from stdin.
+    #[structopt(
+        long = "paths-from",
+        value_name = "FILE",
+        parse(from_os_str),
+        conflicts_with = "watch"
+    )]
+    pub paths_from: Option<PathBuf>,
+
+    /// The `--paths-from` list is separated by NUL bytes, as written by `find -print0` or `fd -0`.
+    #[structopt(short = "0", long = "null", requires = "paths-from")]
+    pub null_separated: bool,
+
+    /// Skip everything inside this directory, or this file. Can be repeated, and applies
+    /// together with `--ignore`.
+    #[structopt(
+        long = "exclude",
+        value_name = "PATH",
+        parse(from_os_str),
+        number_of_values = 1
+    )]
+    pub exclude: Vec<PathBuf>,
+
+    /// Go past safety limits, such as `--confirm-over` or scanning the home directory, when
+    /// given with `--yes`. On its own it skips the plain confirmation like `--yes` does, but
+    /// still asks at a safety limit. Use at your own risk.
     #[structopt(short = "f", long = "force")]
     pub force: bool,
+
+    /// Answer yes to the questions before scanning and deleting, for scripts. Stops at safety
+    /// limits, such as `--confirm-over`, unless `--force` is given too.
+    #[structopt(short = "y", long = "yes")]
+    pub yes: bool,
+
+    /// Clean every file rated safe without showing the interactive selection. Requires `--yes`
+    /// or `--force`.
+    #[structopt(long = "select-safe")]
+    pub select_safe: bool,
+
+    /// Review the results in the interactive selection before cleaning, whichever plugin found
+    /// them. With `--select-safe` the selection starts from the safe results only.
+    #[structopt(long = "interactive")]
+    pub interactive: bool,
+
+    /// Don't show progress bars. Progress is also hidden when output is not a terminal.
+    #[structopt(short = "q", long = "quiet", alias = "no-progress")]
+    pub quiet: bool,
+
+    /// When to color the output: auto, always or never. Auto colors only on a terminal
+    /// and when `NO_COLOR` is not set.
+    #[structopt(long = "color", value_name = "WHEN", default_value = "auto")]
+    pub color: ColorChoice,
+
+    /// How to print the results: text, json or ndjson. The JSON formats only list the results,
+    /// nothing is selected or deleted. ndjson prints each result as soon as it is found.
+    #[structopt(long = "format", value_name = "FORMAT", default_value = "text")]
+    pub format: OutputFormat,
+
+    /// Only print the paths of the results, each followed by a NUL byte instead of a newline,
+    /// as `find -print0` does. Nothing is selected or deleted.
+    #[structopt(long = "print0")]
+    pub print0: bool,
+
+    /// Only descend this many directories deep, where 1 means only the files directly in each path.
+    /// Unlimited if not set.
+    #[structopt(long = "max-depth", value_name = "N")]
+    pub max_depth: Option<usize>,
+
+    /// Save the scan results to this file, to review them later with `--load-scan`.
+    #[structopt(long = "save-scan", value_name = "FILE", parse(from_os_str))]
+    pub save_scan: Option<PathBuf>,
+
+    /// Load scan results saved with `--save-scan` instead of scanning again.
+    #[structopt(long = "load-scan", value_name = "FILE", parse(from_os_str))]
+    pub load_scan: Option<PathBuf>,
+
+    /// Only show the N largest files found.
+    #[structopt(long = "top", value_name = "N")]
+    pub top: Option<usize>,
+
+    /// Only show the N largest files of each directory, so one full directory
+    /// doesn't crowd out the rest.
+    #[structopt(long = "per-dir-limit", value_name = "N")]
+    pub per_dir_limit: Option<usize>,
+
+    /// Order the results by size, age, name or risk before printing them.
+    #[structopt(long = "sort", value_name = "KEY")]
+    pub sort: Option<SortBy>,
+
+    /// Reverse the order of --sort, e.g. smallest or newest first.
+    #[structopt(long = "reverse", requires = "sort")]
+    pub reverse: bool,
+
+    /// Overwrite files with zeros before deleting them. Not reliable on SSDs
+    /// and copy-on-write filesystems.
+    #[structopt(long = "shred")]
+    pub shred: bool,
+
+    /// Show the number and total size of the found files per file type.
+    #[structopt(long = "stats")]
+    pub stats: bool,
+
+    /// Show the number and total size of the found files per top-level directory of each scan path.
+    #[structopt(long = "stats-by-dir")]
+    pub stats_by_dir: bool,
+
+    /// Only print how much space could be freed per risk level, without selecting or cleaning.
+    #[structopt(long = "summary-only")]
+    pub summary_only: bool,
+
+    /// Remove directories that are left empty by the cleanup, up to the scan root.
+    #[structopt(long = "prune-empty-dirs")]
+    pub prune_empty_dirs: bool,
+
+    /// Ask for confirmation if more than this size would be deleted, even with `--force`.
+    /// With `--yes` nothing is deleted past this size, unless `--force` is given too.
+    #[structopt(long = "confirm-over", value_name = "SIZE")]
+    pub confirm_over: Option<String>,
+
+    /// Only sweep the paths whose volume is at least PERCENT full, e.g. `90`. If none is,
+    /// exit without scanning, which makes `swp` safe to run from cron.
+    #[structopt(long = "when-full", value_name = "PERCENT")]
+    pub when_full: Option<f64>,
+
+    /// Rescan every INTERVAL, e.g. `30s` or `5m`, and print new and growing files until
+    /// interrupted. Nothing is deleted.
+    #[structopt(long = "watch", value_name = "INTERVAL")]
+    pub watch: Option<String>,
+
+    /// Write a script with a delete command for each selected file to this
+    /// file, instead of deleting anything.
+    #[structopt(long = "export-script", value_name = "FILE", parse(from_os_str))]
+    pub export_script: Option<PathBuf>,
+
+    /// Run this shell command after cleaning. The cleanup results are passed in
+    /// `SWEEP_ITEMS_CLEANED`, `SWEEP_ITEMS_SKIPPED`, `SWEEP_ERRORS` and `SWEEP_SPACE_FREED`.
+    #[structopt(long = "post-clean-hook", value_name = "COMMAND")]
+    pub post_clean_hook: Option<String>,
+
+    /// Follow symbolic links while scanning. Symlink loops are detected and skipped.
+    #[structopt(long = "follow-symlinks")]
+    pub follow_symlinks: bool,
+
+    /// Include files in hidden directories and hidden files, such as `.cache` or `.git`.
+    #[structopt(long = "include-hidden")]
+    pub include_hidden: bool,
+
+    /// Scan into network filesystems such as NFS and SMB mounts, which are skipped by default.
+    #[structopt(long = "include-remote")]
+    pub include_remote: bool,
+
+    /// Detect the MIME type of the files found from their first bytes, e.g. for `--save-scan`.
+    /// Costs a read of every file that is shown.
+    #[structopt(long = "detect-mime")]
+    pub detect_mime: bool,
+
+    /// Number of threads to scan with. Uses all cores if omitted or 0.
+    #[structopt(long = "threads", value_name = "N")]
+    pub threads: Option<usize>,
+
+    /// Find large files instead of sweeping project dependencies.
+    #[structopt(long = "large-files")]
+    pub enable_large_files: bool,
+
+    /// Find the `vendor` directories of Go modules, detected by their `go.mod` file.
+    #[structopt(long = "go")]
+    pub enable_go: bool,
+
+    /// Also offer the Go module cache in `$GOPATH/pkg/mod`. It is shared by all your
+    /// Go projects, which have to download their modules again. Requires `--go`.
+    #[structopt(long = "go-module-cache", requires = "enable-go")]
+    pub include_go_module_cache: bool,
+
+    /// Find the `bin` and `obj` directories of .NET projects and solutions.
+    #[structopt(long = "dotnet")]
+    pub enable_dotnet: bool,
+
+    /// List the directories taking the most space, like a sorted `du -sh *`, rated by the
+    /// riskiest file in each. `--max-depth` sets how deep they are listed, 1 by default.
+    #[structopt(long = "largest-dirs")]
+    pub enable_largest_dirs: bool,
+
+    /// Reserved for the Python language plugin.
+    #[allow(dead_code)]
+    #[structopt(skip)]
+    pub enable_python: bool,
+
+    /// Reserved for the Java language plugin.
+    #[allow(dead_code)]
+    #[structopt(skip)]
+    pub enable_java: bool,
+
+    /// Reserved for the JavaScript language plugin.
+    #[allow(dead_code)]
+    #[structopt(skip)]
+    pub enable_javascript: bool,
+
+    /// Reserved for the Rust language plugin.
+    #[allow(dead_code)]
+    #[structopt(skip)]
+    pub enable_rust: bool,
+
+    /// Only include files that have not been accessed in this many days.
+    #[structopt(long = "older-than", value_name = "DAYS")]
+    pub older_than_days: Option<u64>,
+
+    /// Only include files last modified before this date, e.g. `2023-01-01` or
+    /// `2023-01-01T12:00:00+02:00`. Dates without a time are midnight UTC.
+    #[structopt(long = "modified-before", value_name = "DATE", parse(try_from_str = utils::parse_date))]
+    pub modified_before: Option<SystemTime>,
+
+    /// Only include files last accessed before this date, like `--modified-before`.
+    #[structopt(long = "accessed-before", value_name = "DATE", parse(try_from_str = utils::parse_date))]
+    pub accessed_before: Option<SystemTime>,
+
+    /// Rate files modified within this many days High risk. Defaults to 3.
+    #[structopt(long = "risk-recent-days", value_name = "DAYS")]
+    pub risk_recent_days: Option<u64>,
+
+    /// Rate files modified within this many days Medium risk. Defaults to 7.
+    #[structopt(long = "risk-week-days", value_name = "DAYS")]
+    pub risk_week_days: Option<u64>,
+
+    /// Rate files modified within this many days Low risk. Defaults to 30.
+    #[structopt(long = "risk-month-days", value_name = "DAYS")]
+    pub risk_month_days: Option<u64>,
+
+    /// Minimum size of files to report, e.g. `500MB` or `1.5GB`. Also applies to directories with
+    /// `--largest-dirs`.
+    #[structopt(long = "min-size", alias = "size-threshold", default_value = "100MB")]
+    pub size_threshold: String,
+
+    /// Maximum size of files to report, e.g. `1GB`. Unbounded if not set.
+    #[structopt(long = "max-size")]
+    pub max_size: Option<String>,
+
+    /// Use powers of 1000 for KB, MB, GB and TB instead of 1024. KiB, MiB, GiB and TiB are always powers of 1024.
+    #[structopt(long = "si")]
+    pub si: bool,
+
+    /// Only report files up to this risk level: safe, low, medium, high or critical.
+    #[structopt(long = "max-risk", value_name = "LEVEL")]
+    pub max_risk: Option<RiskLevel>,
+
+    /// Leave out files of this type, e.g. `media` or `test-data`. Can be repeated.
+    #[structopt(long = "exclude-type", value_name = "TYPE", number_of_values = 1)]
+    pub exclude_types: Vec<FileType>,
+
+    /// Include files tracked by git in the results. Use with caution.
+    #[structopt(long = "include-git-tracked")]
+    pub include_git_tracked: bool,
+
+    /// Don't rate files Safe because `.gitignore`, `.ignore` or `.fdignore` match them.
+    /// Their risk is computed from type, age and git status like any other file.
+    #[structopt(long = "no-gitignore")]
+    pub no_gitignore: bool,
+
+    /// Never delete files matching this name pattern, e.g. `*.iso`. Can be repeated.
+    #[structopt(long = "protect", value_name = "PATTERN", number_of_values = 1)]
+    pub protected_patterns: Vec<String>,
+
+    /// Match protected and test data patterns ignoring case, e.g. to protect `.ENV`.
+    /// The default on Windows and macOS, whose filesystems ignore case.
+    #[structopt(long = "case-insensitive")]
+    pub case_insensitive: bool,
+
+    /// Match protected and test data patterns with exact case. The default on Linux.
+    #[structopt(long = "case-sensitive", conflicts_with = "case-insensitive")]
+    pub case_sensitive: bool,
+
+    /// Names of the directories that hold editor and IDE settings, replacing the defaults.
+    /// Only set from the config file.
+    #[structopt(skip)]
+    pub ide_dirs: Option<Vec<String>>,
+
+    /// Where the manifest of a cleanup is kept, instead of the state directory.
+    /// Only set by programs embedding the cleaner and by tests.
+    #[structopt(skip)]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Allow scanning and deleting inside protected system directories.
+    #[structopt(long = "i-know-what-im-doing")]
+    pub i_know_what_im_doing: bool,
 }
 
 impl Settings {
     /// Gets a Settings struct from the CLI arguments
+    ///
+    /// Settings that weren't given on the command line are read from the
+    /// config files, if any exist.
     pub fn get() -> Result<Settings> {
-        let mut settings: Settings = Settings::from_args();
+        Settings::from_matches(&Settings::clap().get_matches())
+    }
+
+    /// Gets a Settings struct from parsed CLI arguments and the config files
+    fn from_matches(matches: &ArgMatches) -> Result<Settings> {
+        let mut settings: Settings = Settings::from_clap(matches);
 
         settings.validate()?;
 
+        Config::load(&settings.paths)?.apply(&mut settings, matches);
+        // The config files are only read once the paths are known, so check their values here
+        settings.check_values()?;
+
         Ok(settings)
     }
 
@@ -66,46 +437,200 @@ impl Settings {
             paths?
         };
 
+        // A path inside another one would be walked twice
+        self.paths = utils::remove_nested_dirs(std::mem::take(&mut self.paths));
+
+        self.check_values()?;
+        if let Some(percent) = self.when_full {
+            if !(0.0..=100.0).contains(&percent) {
+                return Err(SettingsError::InvalidPercent(percent));
+            }
+        }
+
+        // Cleaning without the selection has to be asked for without a question too
+        if self.select_safe && !(self.yes || self.force) {
+            return Err(SettingsError::MissingFlag {
+                option: "--select-safe",
+                requires: "--yes or --force",
+            });
+        }
+
+        // Messages would end up between the results
+        if self.lists_only() {
+            self.quiet = true;
+        }
+
+        // Excluded paths that don't exist can't match anything, so keep them as given
+        self.exclude = self
+            .exclude
+            .iter()
+            .map(|p| canonicalize(p).unwrap_or_else(|_| p.clone()))
+            .collect();
+
         Ok(())
     }
 
+    /// Checks the sizes and day counts, which the config files can set too
+    ///
+    /// Catches values the plugins can't use before anything runs.
+    fn check_values(&self) -> Result<()> {
+        check_size("--min-size", &self.size_threshold)?;
+        if let Some(max_size) = &self.max_size {
+            check_size("--max-size", max_size)?;
+        }
+
+        for (option, days) in [
+            ("--older-than", self.older_than_days),
+            ("--risk-recent-days", self.risk_recent_days),
+            ("--risk-week-days", self.risk_week_days),
+            ("--risk-month-days", self.risk_month_days),
+        ] {
+            if let Some(days) = days.filter(|days| days.checked_mul(24 * 60 * 60).is_none()) {
+                return Err(SettingsError::InvalidDays { option, days });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks if only the results are printed, without messages, selection or deletion
+    pub fn lists_only(&self) -> bool {
+        self.format.is_machine_readable() || self.print0
+    }
+
+    /// Checks if file name patterns ignore case, from the flags or the platform's usual filesystem
+    pub fn ignores_name_case(&self) -> bool {
+        if self.case_insensitive || self.case_sensitive {
+            self.case_insensitive
+        } else {
+            CASE_INSENSITIVE_FILESYSTEM
+        }
+    }
+
+    /// Checks if a given path is one of the `--exclude` paths or inside one
+    ///
+    /// # Arguments
+    /// * `path` - Path to check, canonicalized like the excluded paths
+    pub fn is_path_excluded(&self, path: &Path) -> bool {
+        is_excluded(&self.exclude, path)
+    }
+
     /// Checks if a given path is ignored
     ///
     /// # Arguments
-    /// * `ignore` - The ignore regex, if set
-    /// * `path`   - Path to check against the ignore regex
+    /// * `path` - Path to check against the ignore regexes
     ///
     /// # Returns
-    /// * `true`  - If the path matches the regex
-    /// * `false` - If the regex and path don't match, if no ignore
+    /// * `true`  - If the path matches any of the regexes
+    /// * `false` - If none of the regexes match, if no ignore
     ///   regex was given, or if the path is empty
     pub fn is_path_ignored(&self, path: &Path) -> bool {
-        if self.ignore.is_none() {
-            return false;
-        }
-
-        let re = self.ignore.as_ref().unwrap();
         let path = path.to_str().unwrap_or("");
 
-        if path.is_empty() {
-            false
-        } else {
-            re.is_match(path)
-        }
+        !path.is_empty() && self.ignore.iter().any(|re| re.is_match(path))
     }
 }
 
+/// Checks that the value of a size flag can be parsed
+///
+/// # Arguments
+/// * `option` - The flag the value was given for, for the error message
+/// * `value`  - The size, e.g. `100MB`
+fn check_size(option: &'static str, value: &str) -> Result<()> {
+    utils::parse_size_string(value)
+        .map(|_| ())
+        .map_err(|err| SettingsError::InvalidSize {
+            option,
+            value: value.to_string(),
+            reason: match err {
+                PluginError::Configuration(reason) => reason,
+                other => other.to_string(),
+            },
+        })
+}
+
+/// Checks if a path is one of the excluded paths or inside one
+///
+/// # Arguments
+/// * `exclude` - The excluded files and directories
+/// * `path`    - Path to check
+pub fn is_excluded(exclude: &[PathBuf], path: &Path) -> bool {
+    exclude.iter().any(|excluded| path.starts_with(excluded))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
 
     #[test]
     fn valid_settings() {
         let mut settings = Settings {
             paths: vec![],
+            command: None,
             all: false,
-            ignore: None,
+            ignore: vec![],
+            exclude: vec![],
+            paths_from: None,
+            null_separated: false,
             force: false,
+            yes: false,
+            select_safe: false,
+            interactive: false,
+            quiet: false,
+            color: ColorChoice::Auto,
+            format: OutputFormat::Text,
+            print0: false,
+            max_depth: None,
+            follow_symlinks: false,
+            include_hidden: false,
+            include_remote: false,
+            detect_mime: false,
+            threads: None,
+            enable_large_files: false,
+            enable_go: false,
+            include_go_module_cache: false,
+            enable_dotnet: false,
+            enable_largest_dirs: false,
+            enable_python: false,
+            enable_java: false,
+            enable_javascript: false,
+            enable_rust: false,
+            older_than_days: None,
+            modified_before: None,
+            accessed_before: None,
+            risk_month_days: None,
+            risk_week_days: None,
+            risk_recent_days: None,
+            size_threshold: "100MB".to_string(),
+            max_size: None,
+            si: false,
+            max_risk: None,
+            exclude_types: vec![],
+            include_git_tracked: false,
+            no_gitignore: false,
+            save_scan: None,
+            load_scan: None,
+            post_clean_hook: None,
+            export_script: None,
+            confirm_over: None,
+            when_full: None,
+            watch: None,
+            prune_empty_dirs: false,
+            stats: false,
+            stats_by_dir: false,
+            summary_only: false,
+            shred: false,
+            top: None,
+            per_dir_limit: None,
+            sort: None,
+            reverse: false,
+            i_know_what_im_doing: false,
+            protected_patterns: vec![],
+            case_insensitive: false,
+            case_sensitive: false,
+            ide_dirs: None,
+            manifest_path: None,
         };
 
         assert!(
@@ -115,13 +640,119 @@ mod tests {
         assert!(!settings.paths.is_empty(), "Settings contains no paths");
     }
 
+    #[test]
+    fn case_flags() {
+        let parse = |args: &[&str]| Settings::from_iter([&["swp"], args].concat());
+
+        assert_eq!(parse(&[]).ignores_name_case(), CASE_INSENSITIVE_FILESYSTEM);
+        assert!(parse(&["--case-insensitive"]).ignores_name_case());
+        assert!(!parse(&["--case-sensitive"]).ignores_name_case());
+        assert!(
+            Settings::from_iter_safe(["swp", "--case-sensitive", "--case-insensitive"]).is_err()
+        );
+    }
+
+    #[test]
+    fn yes_and_force_flags() {
+        let parse = |args: &[&str]| {
+            let settings = Settings::from_iter([&["swp"], args].concat());
+            (settings.yes, settings.force)
+        };
+
+        assert_eq!(parse(&[]), (false, false));
+        assert_eq!(parse(&["-y"]), (true, false));
+        assert_eq!(parse(&["--yes"]), (true, false));
+        assert_eq!(parse(&["-f"]), (false, true));
+        assert_eq!(parse(&["-y", "--force"]), (true, true));
+    }
+
+    #[test]
+    fn color_flag() {
+        let parse = |args: &[&str]| Settings::from_iter([&["swp"], args].concat()).color;
+
+        assert_eq!(parse(&[]), ColorChoice::Auto);
+        assert_eq!(parse(&["--color", "always"]), ColorChoice::Always);
+        assert_eq!(parse(&["--color", "NEVER"]), ColorChoice::Never);
+        assert!(Settings::from_iter_safe(["swp", "--color", "sometimes"]).is_err());
+
+        // Always and never ignore the environment
+        assert!(ColorChoice::Always.should_color(true, false));
+        assert!(!ColorChoice::Never.should_color(false, true));
+
+        // Auto colors only terminals, unless NO_COLOR is set
+        assert!(ColorChoice::Auto.should_color(false, true));
+        assert!(!ColorChoice::Auto.should_color(false, false));
+        assert!(!ColorChoice::Auto.should_color(true, true));
+    }
+
     #[test]
     fn invalid_path() {
         let mut settings = Settings {
             paths: vec!["./this_path_does_not_exist_1".into()],
+            command: None,
             all: false,
-            ignore: None,
+            ignore: vec![],
+            exclude: vec![],
+            paths_from: None,
+            null_separated: false,
             force: false,
+            yes: false,
+            select_safe: false,
+            interactive: false,
+            quiet: false,
+            color: ColorChoice::Auto,
+            format: OutputFormat::Text,
+            print0: false,
+            max_depth: None,
+            follow_symlinks: false,
+            include_hidden: false,
+            include_remote: false,
+            detect_mime: false,
+            threads: None,
+            enable_large_files: false,
+            enable_go: false,
+            include_go_module_cache: false,
+            enable_dotnet: false,
+            enable_largest_dirs: false,
+            enable_python: false,
+            enable_java: false,
+            enable_javascript: false,
+            enable_rust: false,
+            older_than_days: None,
+            modified_before: None,
+            accessed_before: None,
+            risk_month_days: None,
+            risk_week_days: None,
+            risk_recent_days: None,
+            size_threshold: "100MB".to_string(),
+            max_size: None,
+            si: false,
+            max_risk: None,
+            exclude_types: vec![],
+            include_git_tracked: false,
+            no_gitignore: false,
+            save_scan: None,
+            load_scan: None,
+            post_clean_hook: None,
+            export_script: None,
+            confirm_over: None,
+            when_full: None,
+            watch: None,
+            prune_empty_dirs: false,
+            stats: false,
+            stats_by_dir: false,
+            summary_only: false,
+            shred: false,
+            top: None,
+            per_dir_limit: None,
+            sort: None,
+            reverse: false,
+            i_know_what_im_doing: false,
+            protected_patterns: vec![],
+            case_insensitive: false,
+            case_sensitive: false,
+            ide_dirs: None,
+            manifest_path: None,
         };
 
         let validate = settings.validate();
@@ -132,16 +763,210 @@ mod tests {
 
         match validate.unwrap_err() {
             SettingsError::InvalidPath(_) => (),
+            _ => panic!("Expected an invalid path error"),
         }
     }
 
+    #[test]
+    fn repeated_ignore_flag() {
+        let settings =
+            Settings::from_iter(["swp", "-i", "node_modules", "--ignore", "target", "."]);
+        assert_eq!(settings.ignore.len(), 2);
+        assert_eq!(settings.paths, vec![PathBuf::from(".")]);
+
+        assert!(settings.is_path_ignored(Path::new("./web/node_modules/react")));
+        assert!(settings.is_path_ignored(Path::new("./cli/target/debug")));
+        assert!(!settings.is_path_ignored(Path::new("./cli/src")));
+    }
+
+    #[test]
+    fn invalid_size() {
+        let validate = |args: &[&str]| Settings::from_iter([&["swp"], args].concat()).validate();
+
+        assert!(validate(&["--min-size", "500MB"]).is_ok());
+        assert!(validate(&["--min-size", "1.5GiB", "--max-size", "2GB"]).is_ok());
+
+        match validate(&["--size-threshold", "100XB"]) {
+            Err(SettingsError::InvalidSize { option, value, .. }) => {
+                assert_eq!(option, "--min-size");
+                assert_eq!(value, "100XB");
+            }
+            _ => panic!("Expected an invalid size error"),
+        }
+
+        assert!(validate(&["--when-full", "90"]).is_ok());
+        assert!(matches!(
+            validate(&["--when-full", "150"]),
+            Err(SettingsError::InvalidPercent(_))
+        ));
+        assert!(matches!(
+            validate(&["--select-safe"]),
+            Err(SettingsError::MissingFlag {
+                option: "--select-safe",
+                ..
+            })
+        ));
+        assert!(validate(&["--select-safe", "--yes"]).is_ok());
+        assert!(validate(&["--risk-month-days", "36500"]).is_ok());
+        let too_many = u64::MAX.to_string();
+        assert!(matches!(
+            validate(&["--risk-month-days", &too_many]),
+            Err(SettingsError::InvalidDays {
+                option: "--risk-month-days",
+                ..
+            })
+        ));
+        assert!(matches!(
+            validate(&["--older-than", &too_many]),
+            Err(SettingsError::InvalidDays {
+                option: "--older-than",
+                ..
+            })
+        ));
+        assert!(validate(&["--select-safe", "--force"]).is_ok());
+        assert!(matches!(
+            validate(&["--max-size", "lots"]),
+            Err(SettingsError::InvalidSize {
+                option: "--max-size",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn invalid_config_values() {
+        let temp_dir = tempdir::TempDir::new("settings_test").unwrap();
+        let root = temp_dir.path().to_str().unwrap();
+        let from_config = |text: &str| {
+            fs::write(temp_dir.path().join(".sweep.toml"), text).unwrap();
+            Settings::from_matches(&Settings::clap().get_matches_from(vec!["swp", root]))
+        };
+
+        assert!(from_config("size_threshold = \"1GB\"").is_ok());
+        assert!(matches!(
+            from_config("size_threshold = \"100XB\""),
+            Err(SettingsError::InvalidSize {
+                option: "--min-size",
+                ..
+            })
+        ));
+        assert!(matches!(
+            from_config("max_size = \"lots\""),
+            Err(SettingsError::InvalidSize {
+                option: "--max-size",
+                ..
+            })
+        ));
+        assert!(matches!(
+            from_config(&format!("older_than_days = {}", i64::MAX)),
+            Err(SettingsError::InvalidDays {
+                option: "--older-than",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn error_messages() {
+        assert_eq!(
+            SettingsError::InvalidPath("/no/such/dir".into()).to_string(),
+            "Invalid path: /no/such/dir"
+        );
+        assert_eq!(
+            SettingsError::InvalidConfig(".sweeprc".into(), "expected `=`".to_string()).to_string(),
+            "Invalid config file .sweeprc: expected `=`"
+        );
+        assert_eq!(
+            SettingsError::InvalidSize {
+                option: "--min-size",
+                value: "100XB".to_string(),
+                reason: "Unknown unit: XB".to_string(),
+            }
+            .to_string(),
+            "Invalid size 100XB for --min-size: Unknown unit: XB"
+        );
+        assert_eq!(
+            SettingsError::InvalidPercent(120.0).to_string(),
+            "Invalid percentage 120 for --when-full, expected 0 to 100"
+        );
+        assert_eq!(
+            SettingsError::MissingFlag {
+                option: "--select-safe",
+                requires: "--yes or --force",
+            }
+            .to_string(),
+            "--select-safe requires --yes or --force"
+        );
+    }
+
     #[test]
     fn ignore_flag() {
         let settings = Settings {
             paths: vec![],
+            command: None,
             all: false,
-            ignore: Some(Regex::new("src").unwrap()),
+            ignore: vec![Regex::new("src").unwrap()],
+            exclude: vec![],
+            paths_from: None,
+            null_separated: false,
             force: false,
+            yes: false,
+            select_safe: false,
+            interactive: false,
+            quiet: false,
+            color: ColorChoice::Auto,
+            format: OutputFormat::Text,
+            print0: false,
+            max_depth: None,
+            follow_symlinks: false,
+            include_hidden: false,
+            include_remote: false,
+            detect_mime: false,
+            threads: None,
+            enable_large_files: false,
+            enable_go: false,
+            include_go_module_cache: false,
+            enable_dotnet: false,
+            enable_largest_dirs: false,
+            enable_python: false,
+            enable_java: false,
+            enable_javascript: false,
+            enable_rust: false,
+            older_than_days: None,
+            modified_before: None,
+            accessed_before: None,
+            risk_month_days: None,
+            risk_week_days: None,
+            risk_recent_days: None,
+            size_threshold: "100MB".to_string(),
+            max_size: None,
+            si: false,
+            max_risk: None,
+            exclude_types: vec![],
+            include_git_tracked: false,
+            no_gitignore: false,
+            save_scan: None,
+            load_scan: None,
+            post_clean_hook: None,
+            export_script: None,
+            confirm_over: None,
+            when_full: None,
+            watch: None,
+            prune_empty_dirs: false,
+            stats: false,
+            stats_by_dir: false,
+            summary_only: false,
+            shred: false,
+            top: None,
+            per_dir_limit: None,
+            sort: None,
+            reverse: false,
+            i_know_what_im_doing: false,
+            protected_patterns: vec![],
+            case_insensitive: false,
+            case_sensitive: false,
+            ide_dirs: None,
+            manifest_path: None,
         };
 
         assert!(settings.is_path_ignored(Path::new("./src")));
@@ -75,3 +75,68 @@ mod test {
         });
     }
 }
+
+/// Checks if a path component contains glob wildcards
+///
+/// # Arguments
+/// `pattern` - The pattern to check
+pub fn is_glob(pattern: &str) -> bool {
+    pattern.contains('*') || pattern.contains('?')
+}
+
+/// Matches a file name against a glob pattern
+///
+/// Supports `*` for any number of characters and `?` for a single
+/// character. A pattern without wildcards only matches the exact name.
+///
+/// # Arguments
+/// `name`    - The file name
+/// `pattern` - The glob pattern
+///
+/// # Returns
+/// True if the whole name matches the pattern
+pub fn matches_glob(name: &str, pattern: &str) -> bool {
+    let name: Vec<char> = name.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+
+    // Position in the name and pattern to resume from when a `*` has to match more characters
+    let mut backtrack: Option<(usize, usize)> = None;
+    let (mut n, mut p) = (0, 0);
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            n += 1;
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((n, p));
+            p += 1;
+        } else if let Some((star_n, star_p)) = backtrack {
+            backtrack = Some((star_n + 1, star_p));
+            n = star_n + 1;
+            p = star_p + 1;
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_matching() {
+        assert!(matches_glob("target", "target"));
+        assert!(!matches_glob("targets", "target"));
+        assert!(matches_glob("foo.egg-info", "*.egg-info"));
+        assert!(!matches_glob("foo.egg-info.bak", "*.egg-info"));
+        assert!(matches_glob("build-1", "build-?"));
+        assert!(!matches_glob("build-10", "build-?"));
+        assert!(matches_glob("a.test.b", "*.test.*"));
+        assert!(matches_glob("", "*"));
+        assert!(!is_glob("node_modules"));
+        assert!(is_glob("*.egg-info"));
+    }
+}
@@ -28,7 +28,7 @@ const TIMEOUT_MS_BETWEEN_TRIES: u64 = 50;
 ///                 Argument is the number of retries that have been attempted
 ///
 /// # Example
-/// ```rust
+/// ```ignore
 /// let queue = SegQueue::new();
 /// queue.push(7);
 /// queue.push(42);